@@ -0,0 +1,158 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{Data, DeriveInput, Error, LitStr};
+
+/// Names of the sound fields the API returns, as in `freesound_rs::Field`
+pub(crate) const FIELDS: [&str; 34] = [
+    "id",
+    "url",
+    "name",
+    "tags",
+    "description",
+    "geotag",
+    "created",
+    "license",
+    "type",
+    "channels",
+    "filesize",
+    "md5",
+    "bitrate",
+    "bitdepth",
+    "duration",
+    "samplerate",
+    "username",
+    "pack",
+    "download",
+    "bookmark",
+    "previews",
+    "images",
+    "num_downloads",
+    "avg_rating",
+    "num_ratings",
+    "rate",
+    "comments",
+    "num_comments",
+    "comment",
+    "similar_sounds",
+    "analysis",
+    "analysis_stats",
+    "analysis_frames",
+    "ac_analysis",
+];
+
+/// Expands the derive of `FreesoundFields` on a struct
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "FreesoundFields cannot be derived for generic types",
+        ));
+    }
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            name,
+            "FreesoundFields can only be derived for structs",
+        ));
+    };
+    let syn::Fields::Named(named) = &data.fields else {
+        return Err(Error::new_spanned(
+            name,
+            "FreesoundFields needs a struct with named fields",
+        ));
+    };
+
+    let mut idents = Vec::new();
+    let mut types = Vec::new();
+    let mut keys = Vec::new();
+    let mut variants = Vec::new();
+    for field in &named.named {
+        let ident = field.ident.as_ref().expect("named fields have a name");
+        let key = match rename(field)? {
+            Some(key) if !FIELDS.contains(&key.value().as_str()) => {
+                return Err(Error::new_spanned(
+                    &key,
+                    format!("unknown sound field '{}'", key.value()),
+                ));
+            }
+            Some(key) => key.value(),
+            None if !FIELDS.contains(&ident.unraw().to_string().as_str()) => {
+                return Err(Error::new_spanned(
+                    ident,
+                    format!(
+                        "unknown sound field '{}', name it with #[freesound(rename = \"...\")]",
+                        ident.unraw()
+                    ),
+                ));
+            }
+            None => ident.unraw().to_string(),
+        };
+        if keys.contains(&key) {
+            return Err(Error::new_spanned(ident, format!("'{key}' is read twice")));
+        }
+        variants.push(format_ident!("{}", variant(&key)));
+        idents.push(ident);
+        types.push(&field.ty);
+        keys.push(key);
+    }
+
+    Ok(quote! {
+        impl ::freesound_rs::SearchFields for #name {
+            const FIELDS: &'static [::freesound_rs::Field] =
+                &[#(::freesound_rs::Field::#variants),*];
+        }
+
+        impl<'de> ::freesound_rs::__private::serde::Deserialize<'de> for #name {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: ::freesound_rs::__private::serde::Deserializer<'de>,
+            {
+                #[derive(::freesound_rs::__private::serde::Deserialize)]
+                #[serde(crate = "::freesound_rs::__private::serde")]
+                struct __FreesoundFields {
+                    #(#[serde(rename = #keys)] #idents: #types,)*
+                }
+
+                let fields = <__FreesoundFields as ::freesound_rs::__private::serde::Deserialize>::deserialize(deserializer)?;
+                ::core::result::Result::Ok(Self {
+                    #(#idents: fields.#idents,)*
+                })
+            }
+        }
+    })
+}
+
+/// Returns the name given with `#[freesound(rename = "...")]`, if any
+fn rename(field: &syn::Field) -> syn::Result<Option<LitStr>> {
+    let mut name = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|a| a.path().is_ident("freesound"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                name = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `rename`"))
+            }
+        })?;
+    }
+    Ok(name)
+}
+
+/// Returns the name of the `Field` variant of a sound field, such as
+/// `NumDownloads` for `num_downloads`
+fn variant(key: &str) -> String {
+    key.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
+}
@@ -0,0 +1,31 @@
+//! Procedural macros for [freesound-rs](https://crates.io/crates/freesound-rs),
+//! re-exported by it with its `macros` feature
+
+mod fields;
+mod query;
+
+use proc_macro::TokenStream;
+use syn::{DeriveInput, LitStr, parse_macro_input};
+
+/// Builds a `SearchQueryBuilder` from an expression checked at compile time
+///
+/// The expression is a list of comma-separated `key: value` entries. See
+/// `freesound_rs::freesound_query` for the keys.
+#[proc_macro]
+pub fn freesound_query(input: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+    query::expand(&literal)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `SearchFields` and `Deserialize` from the sound fields of a struct
+///
+/// See `freesound_rs::FreesoundFields`.
+#[proc_macro_derive(FreesoundFields, attributes(freesound))]
+pub fn derive_freesound_fields(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    fields::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
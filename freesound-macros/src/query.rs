@@ -0,0 +1,195 @@
+use crate::fields::FIELDS;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::LitStr;
+
+/// Keys that can be given more than once, their filters being combined
+const REPEATABLE: [&str; 2] = ["filter", "tag"];
+
+/// Expands a query expression into the calls of `SearchQueryBuilder`
+pub(crate) fn expand(literal: &LitStr) -> syn::Result<TokenStream> {
+    let error = |message: String| syn::Error::new(literal.span(), message);
+    let mut calls = Vec::new();
+    let mut filters = Vec::new();
+    let mut seen = Vec::new();
+
+    for entry in split(&literal.value()).map_err(error)? {
+        let Some((key, value)) = entry.split_once(':') else {
+            return Err(error(format!("expected 'key: value', found '{entry}'")));
+        };
+        let key = key.trim();
+        let value = unquote(value.trim());
+        if value.is_empty() {
+            return Err(error(format!("missing value for '{key}'")));
+        }
+        if !REPEATABLE.contains(&key) && seen.contains(&key) {
+            return Err(error(format!("'{key}' is given twice")));
+        }
+        seen.push(key);
+
+        match key {
+            "query" => calls.push(quote!(.query(#value))),
+            "filter" => filters.push(value.to_string()),
+            "dur" | "duration" => {
+                filters.push(format!("duration:{}", range(value).map_err(error)?))
+            }
+            "license" => filters.push(format!("license:\"{}\"", license(value).map_err(error)?)),
+            "tag" => filters.push(format!("tag:{}", quote_words(value))),
+            "type" => filters.push(format!("type:{}", file_type(value).map_err(error)?)),
+            "user" | "username" => filters.push(format!("username:{}", quote_words(value))),
+            "pack" => filters.push(format!("pack:{}", quote_words(value))),
+            "sort" => {
+                let sort = format_ident!("{}", sort(value).map_err(error)?);
+                calls.push(quote!(.sort(::freesound_rs::SortOption::#sort)));
+            }
+            "page" => {
+                let page = positive(key, value).map_err(error)?;
+                calls.push(quote!(.page(#page)));
+            }
+            "page_size" => {
+                let size = positive(key, value).map_err(error)?;
+                calls.push(quote!(.page_size(#size)));
+            }
+            "fields" => {
+                let fields = words(value);
+                if let Some(unknown) = fields.iter().find(|f| !FIELDS.contains(f)) {
+                    return Err(error(format!("unknown field '{unknown}'")));
+                }
+                calls.push(quote!(.fields([#(#fields),*])));
+            }
+            "descriptors" => {
+                let descriptors = words(value);
+                calls.push(quote!(.descriptors([#(#descriptors),*])));
+            }
+            "group_by_pack" => {
+                let group = boolean(key, value).map_err(error)?;
+                calls.push(quote!(.group_by_pack(#group)));
+            }
+            "normalized" => {
+                let normalized = boolean(key, value).map_err(error)?;
+                calls.push(quote!(.normalized(#normalized)));
+            }
+            other => return Err(error(format!("unknown key '{other}'"))),
+        }
+    }
+
+    if !filters.is_empty() {
+        let filter = filters.join(" ");
+        calls.push(quote!(.filter(#filter)));
+    }
+    Ok(quote!(::freesound_rs::SearchQueryBuilder::new() #(#calls)*))
+}
+
+/// Splits an expression on commas, keeping double-quoted sections together
+fn split(expression: &str) -> Result<Vec<&str>, String> {
+    let mut entries = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    for (i, c) in expression.char_indices() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => {
+                entries.push(&expression[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if in_quotes {
+        return Err("unterminated quote".to_string());
+    }
+    entries.push(&expression[start..]);
+    Ok(entries
+        .into_iter()
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .collect())
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+fn words(value: &str) -> Vec<&str> {
+    value.split_whitespace().collect()
+}
+
+fn quote_words(value: &str) -> String {
+    if value.contains(char::is_whitespace) {
+        format!("\"{value}\"")
+    } else {
+        value.to_string()
+    }
+}
+
+fn seconds(value: &str) -> Result<f32, String> {
+    match value.trim().parse::<f32>() {
+        Ok(seconds) if seconds.is_finite() && seconds >= 0.0 => Ok(seconds),
+        _ => Err(format!("invalid duration '{value}'")),
+    }
+}
+
+/// Turns `a..b`, `a..`, `..b` or `a` into a Solr range
+fn range(value: &str) -> Result<String, String> {
+    let Some((min, max)) = value.split_once("..") else {
+        return Ok(seconds(value)?.to_string());
+    };
+    let min = (!min.trim().is_empty()).then(|| seconds(min)).transpose()?;
+    let max = (!max.trim().is_empty()).then(|| seconds(max)).transpose()?;
+    if let (Some(min), Some(max)) = (min, max)
+        && min > max
+    {
+        return Err(format!("empty duration range '{value}'"));
+    }
+    let bound = |b: Option<f32>| b.map_or_else(|| "*".to_string(), |b| b.to_string());
+    Ok(format!("[{} TO {}]", bound(min), bound(max)))
+}
+
+fn license(value: &str) -> Result<&'static str, String> {
+    match value.to_lowercase().as_str() {
+        "cc0" | "0" => Ok("Creative Commons 0"),
+        "by" | "attribution" => Ok("Attribution"),
+        "by-nc" | "noncommercial" => Ok("Attribution NonCommercial"),
+        _ => Err(format!("unknown license '{value}'")),
+    }
+}
+
+fn file_type(value: &str) -> Result<String, String> {
+    let value = value.to_lowercase();
+    match value.as_str() {
+        "wav" | "aif" | "aiff" | "ogg" | "mp3" | "m4a" | "flac" => Ok(value),
+        _ => Err(format!("unknown file type '{value}'")),
+    }
+}
+
+/// Returns the name of the `SortOption` variant for a sort order
+fn sort(value: &str) -> Result<&'static str, String> {
+    match value {
+        "score" => Ok("Score"),
+        "rating" | "rating_desc" => Ok("RatingDesc"),
+        "rating_asc" => Ok("RatingAsc"),
+        "downloads" | "downloads_desc" => Ok("DownloadsDesc"),
+        "downloads_asc" => Ok("DownloadsAsc"),
+        "newest" | "created" | "created_desc" => Ok("CreatedDesc"),
+        "oldest" | "created_asc" => Ok("CreatedAsc"),
+        "longest" | "duration" | "duration_desc" => Ok("DurationDesc"),
+        "shortest" | "duration_asc" => Ok("DurationAsc"),
+        other => Err(format!("unknown sort option: {other}")),
+    }
+}
+
+fn positive(key: &str, value: &str) -> Result<i32, String> {
+    match value.parse::<i32>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("'{key}' must be a positive integer")),
+    }
+}
+
+fn boolean(key: &str, value: &str) -> Result<bool, String> {
+    value
+        .parse()
+        .map_err(|_| format!("'{key}' must be true or false"))
+}
@@ -0,0 +1,222 @@
+//! Python bindings of [freesound-rs](https://crates.io/crates/freesound-rs)
+//!
+//! Built with [maturin](https://www.maturin.rs) into the `freesound_rs`
+//! module. The client calls block, releasing the GIL while the requests run on
+//! a runtime owned by the client; responses are returned as the dicts and
+//! lists `json.loads` would give.
+
+use freesound_rs::{
+    DownloadJob, DownloadManager, DownloadSource, FreesoundClient, GetSoundOptions, PreviewQuality,
+    SearchQueryBuilder, SortOption,
+};
+use futures::{StreamExt, TryStreamExt, stream};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Number of sound details fetched at once before a download
+const FETCH_CONCURRENCY: usize = 4;
+
+create_exception!(
+    freesound_rs,
+    FreesoundError,
+    PyException,
+    "An error raised by the Freesound API or the client"
+);
+
+/// A search query, each method returning a new query
+#[pyclass(name = "Query", frozen, from_py_object)]
+#[derive(Clone)]
+struct Query(SearchQueryBuilder);
+
+#[pymethods]
+impl Query {
+    #[new]
+    #[pyo3(signature = (text = None))]
+    fn new(text: Option<String>) -> Self {
+        let builder = SearchQueryBuilder::new();
+        Self(match text {
+            Some(text) => builder.query(text),
+            None => builder,
+        })
+    }
+
+    /// Parses an expression such as `piano dur:1..5 license:cc0 sort:rating`
+    #[staticmethod]
+    fn parse(expression: &str) -> PyResult<Self> {
+        SearchQueryBuilder::parse(expression)
+            .map(Self)
+            .map_err(|e| PyValueError::new_err(e.to_string()))
+    }
+
+    fn filter(&self, filter: String) -> Self {
+        Self(self.0.clone().filter(filter))
+    }
+
+    /// Sorts by an API name such as `rating_desc`
+    fn sort(&self, sort: &str) -> PyResult<Self> {
+        let sort: SortOption = sort.parse().map_err(PyValueError::new_err)?;
+        Ok(Self(self.0.clone().sort(sort)))
+    }
+
+    fn page(&self, page: i32) -> Self {
+        Self(self.0.clone().page(page))
+    }
+
+    fn page_size(&self, size: i32) -> Self {
+        Self(self.0.clone().page_size(size))
+    }
+
+    fn fields(&self, fields: Vec<String>) -> Self {
+        Self(self.0.clone().fields(fields))
+    }
+
+    fn descriptors(&self, descriptors: Vec<String>) -> Self {
+        Self(self.0.clone().descriptors(descriptors))
+    }
+
+    fn group_by_pack(&self, group: bool) -> Self {
+        Self(self.0.clone().group_by_pack(group))
+    }
+
+    fn normalized(&self, normalized: bool) -> Self {
+        Self(self.0.clone().normalized(normalized))
+    }
+
+    /// Returns the query parameters sent to the API
+    fn params(&self) -> Vec<(String, String)> {
+        self.0.build()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Query({:?})", self.0.canonical_string())
+    }
+}
+
+/// A query given as a `Query` or as an expression
+#[derive(FromPyObject)]
+enum QueryArg {
+    Query(Query),
+    Expression(String),
+}
+
+impl QueryArg {
+    fn params(self) -> PyResult<Vec<(String, String)>> {
+        match self {
+            Self::Query(query) => Ok(query.0.build()),
+            Self::Expression(expression) => Ok(Query::parse(&expression)?.0.build()),
+        }
+    }
+}
+
+/// A Freesound client
+#[pyclass(frozen)]
+struct Client {
+    runtime: tokio::runtime::Runtime,
+    client: FreesoundClient,
+}
+
+#[pymethods]
+impl Client {
+    #[new]
+    #[pyo3(signature = (api_key, access_token = None))]
+    fn new(api_key: String, access_token: Option<String>) -> PyResult<Self> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+        let mut client = FreesoundClient::new(api_key, None);
+        if let Some(token) = access_token {
+            client = client.with_access_token(token);
+        }
+        Ok(Self { runtime, client })
+    }
+
+    /// Searches for sounds, returning the first page of results
+    fn search<'py>(&self, py: Python<'py>, query: QueryArg) -> PyResult<Bound<'py, PyAny>> {
+        let params = query.params()?;
+        let page = py
+            .detach(|| self.runtime.block_on(self.client.search(&params)))
+            .map_err(error)?;
+        to_python(py, &page)
+    }
+
+    /// Gets the details of a sound
+    fn get_sound<'py>(&self, py: Python<'py>, sound_id: i32) -> PyResult<Bound<'py, PyAny>> {
+        let sound = py
+            .detach(|| {
+                self.runtime
+                    .block_on(self.client.get_sound(sound_id, &GetSoundOptions::new()))
+            })
+            .map_err(error)?;
+        to_python(py, &sound)
+    }
+
+    /// Downloads sounds into `dir`, returning the download report
+    ///
+    /// `source` is `original`, which needs an access token, or a preview
+    /// quality such as `hq-mp3`.
+    #[pyo3(signature = (sound_ids, dir, source = "hq-mp3", concurrency = None))]
+    fn download<'py>(
+        &self,
+        py: Python<'py>,
+        sound_ids: Vec<i32>,
+        dir: PathBuf,
+        source: &str,
+        concurrency: Option<usize>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let source = match source {
+            "original" => DownloadSource::Original,
+            quality => DownloadSource::Preview(
+                quality
+                    .parse::<PreviewQuality>()
+                    .map_err(PyValueError::new_err)?,
+            ),
+        };
+        let client = &self.client;
+        let dir = &dir;
+        let report = py
+            .detach(|| {
+                self.runtime.block_on(async {
+                    let jobs: Vec<DownloadJob> = stream::iter(sound_ids)
+                        .map(|id| async move {
+                            let sound = client.get_sound(id, &GetSoundOptions::new()).await?;
+                            DownloadJob::new(client, &sound, source, dir)
+                        })
+                        .buffered(FETCH_CONCURRENCY)
+                        .try_collect()
+                        .await?;
+                    let mut manager = DownloadManager::new(client.clone());
+                    if let Some(concurrency) = concurrency {
+                        manager = manager.concurrency(concurrency);
+                    }
+                    for job in jobs {
+                        manager.enqueue(job)?;
+                    }
+                    Ok(manager.run().await)
+                })
+            })
+            .map_err(error)?;
+        to_python(py, &report)
+    }
+}
+
+fn error(error: freesound_rs::FreesoundError) -> PyErr {
+    FreesoundError::new_err(error.to_string())
+}
+
+/// Converts a response to Python through JSON
+fn to_python<'py>(py: Python<'py>, value: &impl Serialize) -> PyResult<Bound<'py, PyAny>> {
+    let text = serde_json::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    py.import("json")?.call_method1("loads", (text,))
+}
+
+#[pymodule]
+#[pyo3(name = "freesound_rs")]
+fn freesound_python(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Client>()?;
+    m.add_class::<Query>()?;
+    m.add("FreesoundError", m.py().get_type::<FreesoundError>())?;
+    Ok(())
+}
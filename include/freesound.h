@@ -0,0 +1,45 @@
+/*
+ * C interface of freesound-rs, built with the `ffi` feature
+ *
+ * Requests block until they complete and must not be made from an audio
+ * thread. Strings are UTF-8. Every string written to `out_json` must be
+ * released with freesound_string_free, whatever the status.
+ */
+#ifndef FREESOUND_H
+#define FREESOUND_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The result is the requested JSON value */
+#define FREESOUND_OK 0
+/* The request failed; the result is {"error": "...", "sound_id": ...} */
+#define FREESOUND_ERROR 1
+/* An argument is null, not UTF-8 or not valid; the result is {"error": "..."} */
+#define FREESOUND_INVALID_ARGUMENT 2
+/* The library panicked; the result is {"error": "..."} */
+#define FREESOUND_PANIC 3
+
+typedef struct FreesoundHandle FreesoundHandle;
+
+/* Returns NULL on failure; access_token may be NULL */
+FreesoundHandle *freesound_client_new(const char *api_key, const char *access_token);
+void freesound_client_free(FreesoundHandle *handle);
+
+/* query_json is an object such as {"query": "piano", "page_size": 20} */
+int freesound_search(const FreesoundHandle *handle, const char *query_json, char **out_json);
+int freesound_get_sound(const FreesoundHandle *handle, int32_t sound_id, char **out_json);
+/* source is "original" (needs an access token) or "hq-mp3", "lq-mp3", "hq-ogg", "lq-ogg" */
+int freesound_download(const FreesoundHandle *handle, int32_t sound_id, const char *source,
+                       const char *path, char **out_json);
+
+void freesound_string_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FREESOUND_H */
@@ -0,0 +1,250 @@
+//! Descriptor tables built from the analysis of many sounds
+//!
+//! [`collect`] fetches the descriptors of a list of sounds and lays them out as
+//! a [`FeatureTable`], one row per sound and one column per descriptor value,
+//! ready to be fed to a machine learning pipeline.
+
+use crate::client::FreesoundClient;
+use crate::error::Result;
+use crate::models::{SearchQueryBuilder, Sound};
+use futures::{StreamExt, TryStreamExt, stream};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Number of searches [`collect`] runs at once
+pub const COLLECT_CONCURRENCY: usize = 4;
+
+/// Number of sounds fetched by each search of [`collect`], the largest page size
+pub const COLLECT_BATCH_SIZE: usize = 150;
+
+/// A matrix of descriptor values, sounds by descriptor columns
+///
+/// Descriptors holding several values are spread over several columns:
+/// statistics get dotted names (`lowlevel.pitch.mean`) and arrays get
+/// indexes (`lowlevel.mfcc.mean[0]`). Values a sound does not have are `None`.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::Sound;
+/// use freesound_rs::analysis::FeatureTable;
+/// use serde_json::json;
+///
+/// let sound = |id, analysis| Sound { id, analysis: Some(analysis), ..Default::default() };
+/// let sounds = [
+///     sound(1, json!({ "rhythm": { "bpm": 120.0 }, "lowlevel": { "mfcc": { "mean": [1.0, 2.0] } } })),
+///     sound(2, json!({ "rhythm": { "bpm": 90.0 } })),
+/// ];
+/// let table = FeatureTable::from_sounds(&[1, 2, 3], ["rhythm.bpm", "lowlevel.mfcc.mean"], &sounds);
+///
+/// assert_eq!(table.columns, ["rhythm.bpm", "lowlevel.mfcc.mean[0]", "lowlevel.mfcc.mean[1]"]);
+/// assert_eq!(table.rows[0], [Some(120.0), Some(1.0), Some(2.0)]);
+/// assert_eq!(table.rows[2], [None, None, None]);
+/// assert_eq!(table.column("rhythm.bpm").unwrap(), [Some(120.0), Some(90.0), None]);
+/// assert_eq!(
+///     table.to_csv(),
+///     "id,rhythm.bpm,lowlevel.mfcc.mean[0],lowlevel.mfcc.mean[1]\n1,120,1,2\n2,90,,\n3,,,\n"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FeatureTable {
+    /// Sound ids, one per row, in the order requested
+    pub ids: Vec<i32>,
+    /// Column names
+    pub columns: Vec<String>,
+    /// Values, one row per sound and one value per column
+    pub rows: Vec<Vec<Option<f64>>>,
+}
+
+impl FeatureTable {
+    /// Builds a table from sounds fetched with their descriptors
+    ///
+    /// Rows follow `ids`; an id without a matching sound gets a row of `None`.
+    /// Columns follow `descriptors`, then the order in which values are found.
+    pub fn from_sounds<I, S>(ids: &[i32], descriptors: I, sounds: &[Sound]) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let descriptors: Vec<S> = descriptors.into_iter().collect();
+        let by_id: HashMap<i32, &Sound> = sounds.iter().map(|s| (s.id, s)).collect();
+
+        let mut columns: Vec<String> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let values: Vec<Vec<(usize, f64)>> = ids
+            .iter()
+            .map(|id| {
+                let mut values = Vec::new();
+                let Some(sound) = by_id.get(id) else {
+                    return values;
+                };
+                for descriptor in &descriptors {
+                    let name = descriptor.as_ref();
+                    let Some(value) = sound.analysis_value(name) else {
+                        continue;
+                    };
+                    let mut flat = Vec::new();
+                    flatten(name.to_string(), value, &mut flat);
+                    for (column, value) in flat {
+                        let i = *index.entry(column.clone()).or_insert_with(|| {
+                            columns.push(column);
+                            columns.len() - 1
+                        });
+                        values.push((i, value));
+                    }
+                }
+                values
+            })
+            .collect();
+
+        let rows = values
+            .into_iter()
+            .map(|values| {
+                let mut row = vec![None; columns.len()];
+                for (i, value) in values {
+                    row[i] = Some(value);
+                }
+                row
+            })
+            .collect();
+
+        Self {
+            ids: ids.to_vec(),
+            columns,
+            rows,
+        }
+    }
+
+    /// Returns the values of a column, one per row
+    pub fn column(&self, name: &str) -> Option<Vec<Option<f64>>> {
+        let i = self.columns.iter().position(|c| c == name)?;
+        Some(self.rows.iter().map(|row| row[i]).collect())
+    }
+
+    /// Returns the values of a sound, one per column
+    pub fn row(&self, sound_id: i32) -> Option<&[Option<f64>]> {
+        let i = self.ids.iter().position(|&id| id == sound_id)?;
+        Some(&self.rows[i])
+    }
+
+    /// Formats the table as CSV, with an `id` column first and empty missing values
+    pub fn to_csv(&self) -> String {
+        let mut csv = std::iter::once("id")
+            .chain(self.columns.iter().map(String::as_str))
+            .map(csv_field)
+            .collect::<Vec<_>>()
+            .join(",");
+        csv.push('\n');
+        for (id, row) in self.ids.iter().zip(&self.rows) {
+            csv.push_str(&id.to_string());
+            for value in row {
+                csv.push(',');
+                if let Some(value) = value {
+                    csv.push_str(&value.to_string());
+                }
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+/// Fetches the descriptors of many sounds as a [`FeatureTable`]
+///
+/// Sounds are fetched by batches of [`COLLECT_BATCH_SIZE`] with an id search,
+/// running at most [`COLLECT_CONCURRENCY`] searches at once. Raw descriptor
+/// values are used. Sounds that cannot be found get a row of `None`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::env;
+/// use freesound_rs::FreesoundClient;
+/// use freesound_rs::analysis;
+/// use freesound_rs::descriptor::{Lowlevel, Rhythm, Stat};
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     dotenvy::dotenv().ok();
+///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+///     let client = FreesoundClient::new(api_key, None);
+/// let descriptors = [Rhythm::Bpm.as_str().to_string(), Lowlevel::Mfcc.stat(Stat::Mean)];
+/// let table = analysis::collect(&client, &[1234, 5678], &descriptors).await?;
+/// std::fs::write("features.csv", table.to_csv())?;
+///      Ok(())
+///  }
+/// ```
+pub async fn collect<S: AsRef<str>>(
+    client: &FreesoundClient,
+    sound_ids: &[i32],
+    descriptors: &[S],
+) -> Result<FeatureTable> {
+    collect_with(client, sound_ids, descriptors, COLLECT_CONCURRENCY).await
+}
+
+/// Fetches the descriptors of many sounds, running up to `concurrency` searches at once
+///
+/// See [`collect`].
+pub async fn collect_with<S: AsRef<str>>(
+    client: &FreesoundClient,
+    sound_ids: &[i32],
+    descriptors: &[S],
+    concurrency: usize,
+) -> Result<FeatureTable> {
+    let batches: Vec<Vec<Sound>> = stream::iter(sound_ids.chunks(COLLECT_BATCH_SIZE))
+        .map(|ids| {
+            let filter = format!(
+                "id:({})",
+                ids.iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" OR ")
+            );
+            let query = SearchQueryBuilder::new()
+                .filter(filter)
+                .page_size(COLLECT_BATCH_SIZE as i32)
+                .fields(["id", "analysis"])
+                .descriptors(descriptors.iter().map(|d| d.as_ref()))
+                .build();
+            async move { client.search(&query).await.map(|page| page.results) }
+        })
+        .buffered(concurrency.max(1))
+        .try_collect()
+        .await?;
+
+    let sounds: Vec<Sound> = batches.into_iter().flatten().collect();
+    Ok(FeatureTable::from_sounds(
+        sound_ids,
+        descriptors.iter().map(|d| d.as_ref()),
+        &sounds,
+    ))
+}
+
+/// Lists the numbers of a descriptor value under their column names
+fn flatten(name: String, value: &serde_json::Value, out: &mut Vec<(String, f64)>) {
+    match value {
+        serde_json::Value::Number(n) => {
+            if let Some(n) = n.as_f64() {
+                out.push((name, n));
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for (i, value) in values.iter().enumerate() {
+                flatten(format!("{name}[{i}]"), value, out);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                flatten(format!("{name}.{key}"), value, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
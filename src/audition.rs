@@ -0,0 +1,259 @@
+use crate::client::FreesoundClient;
+use crate::error::{FreesoundError, Result};
+use crate::models::{PreviewQuality, Sound};
+use futures::future::{self, BoxFuture, Either};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Default interval at which [`AuditionQueue::wait`] checks the player
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Plays encoded preview audio
+///
+/// Implement it on top of an audio output, such as a rodio `Sink` decoding the
+/// bytes, to use an [`AuditionQueue`]. Requires the `playback` feature.
+pub trait Player {
+    /// Start playing a preview, replacing whatever was playing
+    ///
+    /// `audio` is the content of an MP3 or OGG file, depending on `quality`.
+    fn play(&mut self, audio: Vec<u8>, quality: PreviewQuality) -> Result<()>;
+
+    /// Pause playback
+    fn pause(&mut self);
+
+    /// Resume paused playback
+    fn resume(&mut self);
+
+    /// Stop playback
+    fn stop(&mut self);
+
+    /// Returns true once the current preview has been played to the end, or stopped
+    fn is_finished(&self) -> bool;
+}
+
+enum Prefetch {
+    Pending(BoxFuture<'static, Result<Vec<u8>>>),
+    Ready(Result<Vec<u8>>),
+}
+
+/// Plays the previews of a list of sounds, one after the other
+///
+/// While a preview plays, the next one is downloaded in the background of
+/// [`wait`](Self::wait), so skipping to it starts at once. Requires the
+/// `playback` feature.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::env;
+/// use freesound_rs::{AuditionQueue, FreesoundClient, Player, PreviewQuality, SearchQueryBuilder};
+///
+/// /// Stands for a real audio output
+/// struct Speaker;
+///
+/// impl Player for Speaker {
+///     fn play(&mut self, audio: Vec<u8>, _: PreviewQuality) -> freesound_rs::Result<()> {
+///         println!("Playing {} bytes", audio.len());
+///         Ok(())
+///     }
+///     fn pause(&mut self) {}
+///     fn resume(&mut self) {}
+///     fn stop(&mut self) {}
+///     fn is_finished(&self) -> bool {
+///         true
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     dotenvy::dotenv().ok();
+///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+///     let client = FreesoundClient::new(api_key, None);
+/// let results = client.search(&SearchQueryBuilder::new().query("rain").build()).await?;
+/// let mut queue = AuditionQueue::new(client, Speaker).quality(PreviewQuality::LqMp3);
+/// queue.extend(results);
+/// while let Some(sound) = queue.skip().await? {
+///     println!("Now playing {}", sound.name);
+///     queue.wait().await?;
+/// }
+///      Ok(())
+///  }
+/// ```
+pub struct AuditionQueue<P> {
+    client: FreesoundClient,
+    player: P,
+    quality: PreviewQuality,
+    poll_interval: Duration,
+    pending: VecDeque<Sound>,
+    current: Option<Sound>,
+    prefetch: Option<(i32, Prefetch)>,
+    paused: bool,
+}
+
+impl<P: Player> AuditionQueue<P> {
+    /// Create an empty queue playing through `player`
+    pub fn new(client: FreesoundClient, player: P) -> Self {
+        Self {
+            client,
+            player,
+            quality: PreviewQuality::default(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            pending: VecDeque::new(),
+            current: None,
+            prefetch: None,
+            paused: false,
+        }
+    }
+
+    /// Set the quality of the previews played (defaults to [`PreviewQuality::HqMp3`])
+    pub fn quality(mut self, quality: PreviewQuality) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// Set how often [`wait`](Self::wait) checks whether the player is done
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Add a sound at the end of the queue
+    ///
+    /// The sound must have been fetched with its `previews` field.
+    pub fn push(&mut self, sound: Sound) {
+        self.pending.push_back(sound);
+    }
+
+    /// Add sounds at the end of the queue
+    pub fn extend(&mut self, sounds: impl IntoIterator<Item = Sound>) {
+        self.pending.extend(sounds);
+    }
+
+    /// Returns the sound playing, if any
+    pub fn current(&self) -> Option<&Sound> {
+        self.current.as_ref()
+    }
+
+    /// Returns the sounds waiting to be played
+    pub fn pending(&self) -> impl Iterator<Item = &Sound> {
+        self.pending.iter()
+    }
+
+    /// Returns the number of sounds waiting to be played
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns true if no sound is waiting to be played
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Returns the player
+    pub fn player(&self) -> &P {
+        &self.player
+    }
+
+    /// Returns the player, mutably
+    pub fn player_mut(&mut self) -> &mut P {
+        &mut self.player
+    }
+
+    /// Stop the current preview and play the next one
+    ///
+    /// Also starts playing the first sound. Returns the sound now playing, or
+    /// `None` when the queue is over. If the next preview cannot be fetched or
+    /// played, the error is returned and the sound is dropped from the queue.
+    pub async fn skip(&mut self) -> Result<Option<&Sound>> {
+        self.player.stop();
+        self.current = None;
+        self.paused = false;
+        let Some(sound) = self.pending.pop_front() else {
+            self.prefetch = None;
+            return Ok(None);
+        };
+
+        let audio = match self.prefetch.take() {
+            Some((id, Prefetch::Pending(fetch))) if id == sound.id => fetch.await,
+            Some((id, Prefetch::Ready(result))) if id == sound.id => result,
+            _ => self.client.fetch_preview(&sound, self.quality).await,
+        };
+        self.start_prefetch();
+        self.player.play(audio?, self.quality)?;
+        self.current = Some(sound);
+        Ok(self.current.as_ref())
+    }
+
+    /// Pause the current preview
+    pub fn pause(&mut self) {
+        if self.current.is_some() {
+            self.player.pause();
+            self.paused = true;
+        }
+    }
+
+    /// Resume the current preview
+    pub fn resume(&mut self) {
+        if self.paused {
+            self.player.resume();
+            self.paused = false;
+        }
+    }
+
+    /// Returns true if the current preview is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Wait for the current preview to finish, downloading the next one meanwhile
+    ///
+    /// Returns at once when nothing is playing. Dropping the future does not
+    /// lose the download progress, so it can be raced against user input.
+    pub async fn wait(&mut self) -> Result<()> {
+        if self.current.is_none() {
+            return Ok(());
+        }
+        loop {
+            if !self.paused && self.player.is_finished() {
+                return Ok(());
+            }
+            let tick = self.client.sleeper().sleep(self.poll_interval);
+            let Some((id, Prefetch::Pending(fetch))) = &mut self.prefetch else {
+                tick.await;
+                continue;
+            };
+            let id = *id;
+            if let Either::Left((result, _)) = future::select(fetch, tick).await {
+                self.prefetch = Some((id, Prefetch::Ready(result)));
+            }
+        }
+    }
+
+    /// Play every sound of the queue to the end
+    pub async fn play_all(&mut self) -> Result<()> {
+        while self.skip().await?.is_some() {
+            self.wait().await?;
+        }
+        Ok(())
+    }
+
+    /// Starts downloading the preview of the next sound
+    fn start_prefetch(&mut self) {
+        self.prefetch = self.pending.front().map(|sound| {
+            let client = self.client.clone();
+            let quality = self.quality;
+            let fetch: BoxFuture<'static, Result<Vec<u8>>> = match &sound.previews {
+                Some(previews) => {
+                    let url = previews.url(quality).to_string();
+                    Box::pin(async move { client.fetch_media(&url).await })
+                }
+                None => {
+                    let error =
+                        FreesoundError::ApiError(format!("Sound {} has no previews", sound.id));
+                    Box::pin(future::ready(Err(error)))
+                }
+            };
+            (sound.id, Prefetch::Pending(fetch))
+        });
+    }
+}
@@ -0,0 +1,340 @@
+//! Running mixed API operations together
+//!
+//! [`execute`] runs a list of [`Operation`]s (sound fetches, searches, similar
+//! sounds, downloads) concurrently, pacing their starts so the whole batch stays
+//! under a shared rate, and gathers every outcome in a [`BatchOutcome`] instead
+//! of stopping at the first error.
+
+use crate::client::FreesoundClient;
+use crate::error::{FreesoundError, Result};
+use crate::models::{GetSoundOptions, PreviewQuality, SearchResponse, Sound, SoundListOptions};
+use futures::stream::{self, StreamExt};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// Default number of operations [`execute`] runs at once
+pub const BATCH_CONCURRENCY: usize = 4;
+
+/// An API call run by [`execute`]
+#[derive(Debug, Clone)]
+pub enum Operation {
+    /// [`FreesoundClient::get_sound`], giving an [`Output::Sound`]
+    GetSound {
+        /// The sound id
+        id: i32,
+        /// The options of the request
+        options: GetSoundOptions,
+    },
+    /// [`FreesoundClient::search`], giving an [`Output::Sounds`]
+    Search {
+        /// The search parameters, as built by a
+        /// [`SearchQueryBuilder`](crate::SearchQueryBuilder)
+        query: Vec<(String, String)>,
+    },
+    /// [`FreesoundClient::get_similar_sounds`], giving an [`Output::Sounds`]
+    SimilarSounds {
+        /// The id of the target sound
+        id: i32,
+        /// The options of the request
+        options: SoundListOptions,
+    },
+    /// [`FreesoundClient::download_preview`], giving an [`Output::Written`]
+    DownloadPreview {
+        /// The sound, fetched with its `previews` field
+        sound: Box<Sound>,
+        /// The quality of the preview
+        quality: PreviewQuality,
+        /// The file to write
+        path: PathBuf,
+    },
+    /// [`FreesoundClient::download_original`], giving an [`Output::Written`]
+    DownloadOriginal {
+        /// The sound id
+        id: i32,
+        /// The file to write
+        path: PathBuf,
+    },
+}
+
+impl Operation {
+    async fn run(self, client: &FreesoundClient) -> Result<Output> {
+        match self {
+            Self::GetSound { id, options } => client
+                .get_sound(id, &options)
+                .await
+                .map(|sound| Output::Sound(Box::new(sound))),
+            Self::Search { query } => client
+                .search(&query)
+                .await
+                .map(|page| Output::Sounds(Box::new(page))),
+            Self::SimilarSounds { id, options } => client
+                .get_similar_sounds(id, &options)
+                .await
+                .map(|page| Output::Sounds(Box::new(page))),
+            Self::DownloadPreview {
+                sound,
+                quality,
+                path,
+            } => client
+                .download_preview(&sound, quality, path)
+                .await
+                .map(Output::Written),
+            Self::DownloadOriginal { id, path } => client
+                .download_original(id, path)
+                .await
+                .map(Output::Written),
+        }
+    }
+}
+
+/// The result of a successful [`Operation`]
+#[derive(Debug, Clone)]
+pub enum Output {
+    /// A sound
+    Sound(Box<Sound>),
+    /// A page of sounds
+    Sounds(Box<SearchResponse>),
+    /// The number of bytes written to a file
+    Written(u64),
+}
+
+/// How [`execute`] spreads its operations
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use freesound_rs::batch::Limits;
+///
+/// // Freesound allows 60 requests per minute to standard API keys
+/// let limits = Limits::new().concurrency(8).per_minute(60).fail_fast(true);
+/// assert_eq!(
+///     limits,
+///     Limits::new().concurrency(8).min_interval(Duration::from_secs(1)).fail_fast(true)
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    concurrency: usize,
+    min_interval: Duration,
+    fail_fast: bool,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            concurrency: BATCH_CONCURRENCY,
+            min_interval: Duration::ZERO,
+            fail_fast: false,
+        }
+    }
+}
+
+impl Limits {
+    /// Create limits running [`BATCH_CONCURRENCY`] operations at once, unpaced
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how many operations run at once (at least one)
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Set the shortest time between the starts of two operations
+    pub fn min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    /// Start at most `requests` operations per minute
+    pub fn per_minute(self, requests: u32) -> Self {
+        self.min_interval(Duration::from_secs(60) / requests.max(1))
+    }
+
+    /// Set whether to start no new operation once one failed
+    pub fn fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+}
+
+/// The outcomes of the operations of a batch, in the order they were given
+#[derive(Debug, Default)]
+pub struct BatchOutcome {
+    /// The outcome of each operation, `None` for the ones not started because
+    /// another failed under [`Limits::fail_fast`]
+    pub results: Vec<Option<Result<Output>>>,
+}
+
+impl BatchOutcome {
+    /// Returns the outputs of the successful operations, with their index
+    pub fn outputs(&self) -> impl Iterator<Item = (usize, &Output)> {
+        self.results.iter().enumerate().filter_map(|(i, result)| {
+            result
+                .as_ref()
+                .and_then(|result| result.as_ref().ok())
+                .map(|output| (i, output))
+        })
+    }
+
+    /// Returns the errors of the failed operations, with their index
+    pub fn errors(&self) -> impl Iterator<Item = (usize, &FreesoundError)> {
+        self.results.iter().enumerate().filter_map(|(i, result)| {
+            result
+                .as_ref()
+                .and_then(|result| result.as_ref().err())
+                .map(|error| (i, error))
+        })
+    }
+
+    /// Returns the indexes of the operations that were not started
+    pub fn skipped(&self) -> impl Iterator<Item = usize> {
+        self.results
+            .iter()
+            .enumerate()
+            .filter_map(|(i, result)| result.is_none().then_some(i))
+    }
+
+    /// Returns true if every operation succeeded
+    pub fn is_success(&self) -> bool {
+        self.results
+            .iter()
+            .all(|result| matches!(result, Some(Ok(_))))
+    }
+
+    /// Returns the outputs in order, or the first error met
+    pub fn into_result(self) -> Result<Vec<Output>> {
+        self.results
+            .into_iter()
+            .flatten()
+            .collect::<Result<Vec<_>>>()
+    }
+}
+
+/// Spaces the starts of the operations by the minimum interval
+#[derive(Debug)]
+struct Pacer {
+    interval: Duration,
+    next: Mutex<Option<Instant>>,
+}
+
+impl Pacer {
+    /// Returns how long to wait after `now` before starting an operation, reserving its slot
+    fn reserve(&self, now: Instant) -> Duration {
+        if self.interval.is_zero() {
+            return Duration::ZERO;
+        }
+        let mut next = self.next.lock().unwrap();
+        let start = next.map_or(now, |next| next.max(now));
+        *next = Some(start + self.interval);
+        start - now
+    }
+}
+
+/// Run operations concurrently, sharing the limits, and collect every outcome
+///
+/// At most [`Limits::concurrency`] operations run at once, and their starts are
+/// spaced by [`Limits::min_interval`] across the whole batch, whatever their
+/// kind. A failing operation does not stop the others, unless
+/// [`Limits::fail_fast`] is set, in which case the operations not started yet
+/// are skipped. Time is read from the [clock](FreesoundClient::with_clock) of
+/// the client and waiting goes through its [sleeper](FreesoundClient::with_sleeper).
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::env;
+/// use freesound_rs::batch::{self, Limits, Operation, Output};
+/// use freesound_rs::{FreesoundClient, PreviewQuality, SearchQueryBuilder};
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     dotenvy::dotenv().ok();
+///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+///     let client = FreesoundClient::new(api_key, None);
+/// let sound = client.get_sound(1234, &Default::default()).await?;
+/// let ops = vec![
+///     Operation::Search { query: SearchQueryBuilder::new().query("rain").build() },
+///     Operation::SimilarSounds { id: 1234, options: Default::default() },
+///     Operation::DownloadPreview {
+///         sound: Box::new(sound),
+///         quality: PreviewQuality::HqMp3,
+///         path: "1234.mp3".into(),
+///     },
+/// ];
+/// let outcome = batch::execute(&client, ops, Limits::new().per_minute(60)).await;
+/// for (i, error) in outcome.errors() {
+///     eprintln!("operation {i} failed: {error}");
+/// }
+/// for (i, output) in outcome.outputs() {
+///     if let Output::Written(bytes) = output {
+///         println!("operation {i} wrote {bytes} bytes");
+///     }
+/// }
+///      Ok(())
+///  }
+/// ```
+///
+/// Operations failing before any request still get their outcome:
+///
+/// ```
+/// use freesound_rs::batch::{self, Limits, Operation};
+/// use freesound_rs::{FreesoundClient, PreviewQuality, Sound};
+///
+/// let client = FreesoundClient::new("API_KEY".to_string(), None);
+/// // Sounds fetched without their previews cannot be downloaded
+/// let ops = (1..=3).map(|id| Operation::DownloadPreview {
+///     sound: Box::new(Sound { id, ..Default::default() }),
+///     quality: PreviewQuality::HqMp3,
+///     path: format!("{id}.mp3").into(),
+/// });
+/// let limits = Limits::new().concurrency(1).fail_fast(true);
+/// let outcome = futures::executor::block_on(batch::execute(&client, ops, limits));
+///
+/// assert_eq!(outcome.errors().count(), 1);
+/// assert_eq!(outcome.skipped().collect::<Vec<_>>(), [1, 2]);
+/// assert!(outcome.into_result().is_err());
+/// ```
+pub async fn execute(
+    client: &FreesoundClient,
+    ops: impl IntoIterator<Item = Operation>,
+    limits: Limits,
+) -> BatchOutcome {
+    let pacer = Pacer {
+        interval: limits.min_interval,
+        next: Mutex::new(None),
+    };
+    let failed = AtomicBool::new(false);
+    let (pacer, failed) = (&pacer, &failed);
+
+    let mut finished: Vec<(usize, Option<Result<Output>>)> =
+        stream::iter(ops.into_iter().enumerate())
+            .map(|(i, op)| async move {
+                if limits.fail_fast && failed.load(Ordering::SeqCst) {
+                    return (i, None);
+                }
+                let wait = pacer.reserve(client.clock().now());
+                if !wait.is_zero() {
+                    client.sleeper().sleep(wait).await;
+                }
+                if limits.fail_fast && failed.load(Ordering::SeqCst) {
+                    return (i, None);
+                }
+                let result = op.run(client).await;
+                if result.is_err() {
+                    failed.store(true, Ordering::SeqCst);
+                }
+                (i, Some(result))
+            })
+            .buffer_unordered(limits.concurrency)
+            .collect()
+            .await;
+
+    finished.sort_by_key(|(i, _)| *i);
+    BatchOutcome {
+        results: finished.into_iter().map(|(_, result)| result).collect(),
+    }
+}
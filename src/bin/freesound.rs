@@ -0,0 +1,386 @@
+//! Command line interface to the Freesound API
+//!
+//! Build with `cargo install freesound-rs --features cli`.
+
+use clap::{Parser, Subcommand, ValueEnum};
+use freesound_rs::{
+    DownloadJob, DownloadManager, FileTokenStore, FreesoundClient, IndicatifProgress,
+    OverwritePolicy, Pack, PackId, PreviewQuality, SearchQueryBuilder, SearchResponse, SortOption,
+    Sound, TokenStore, User, Username,
+};
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "freesound",
+    version,
+    about = "Search and fetch sounds from Freesound"
+)]
+struct Cli {
+    /// Freesound API key
+    #[arg(long, env = "FREESOUND_API_KEY", hide_env_values = true)]
+    api_key: String,
+
+    /// Custom base URL for the API
+    #[arg(long, global = true)]
+    base_url: Option<String>,
+
+    /// OAuth2 client id, used to log in and to refresh the saved tokens
+    #[arg(long, env = "FREESOUND_CLIENT_ID", global = true)]
+    client_id: Option<String>,
+
+    /// OAuth2 client secret
+    #[arg(
+        long,
+        env = "FREESOUND_CLIENT_SECRET",
+        hide_env_values = true,
+        global = true
+    )]
+    client_secret: Option<String>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Table, global = true)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Format {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Search sounds with a text query
+    Search {
+        /// Search expression, e.g. "piano dur:1..5 license:cc0 sort:rating"
+        query: String,
+        /// Raw filter (Solr syntax), replacing filters given in the search expression
+        #[arg(long)]
+        filter: Option<String>,
+        /// Sort order (score, rating_desc, created_desc, ...)
+        #[arg(long)]
+        sort: Option<SortOption>,
+        /// Page number
+        #[arg(long)]
+        page: Option<i32>,
+        /// Number of results per page
+        #[arg(long)]
+        page_size: Option<i32>,
+    },
+    /// Show details of a sound
+    Get {
+        /// Sound identifier
+        id: i32,
+    },
+    /// Download the previews of sounds
+    Download {
+        /// Sound identifiers
+        #[arg(required = true)]
+        ids: Vec<i32>,
+        /// Preview quality (hq-mp3, lq-mp3, hq-ogg, lq-ogg)
+        #[arg(long, default_value_t = PreviewQuality::HqMp3)]
+        quality: PreviewQuality,
+        /// Download the original files instead, which needs `freesound auth login`
+        #[arg(long)]
+        original: bool,
+        /// Destination directory, files are named `<id>.<ext>`
+        #[arg(short, long, default_value = ".")]
+        dir: PathBuf,
+        /// What to do with existing files (skip, overwrite, rename-suffix)
+        #[arg(long, default_value_t = OverwritePolicy::Overwrite)]
+        overwrite: OverwritePolicy,
+        /// Write a JSON report of the downloads to this file
+        #[arg(long)]
+        report: Option<PathBuf>,
+    },
+    /// Show details of a pack
+    Pack {
+        /// Pack identifier
+        id: PackId,
+        /// List the sounds of the pack instead of its details
+        #[arg(long)]
+        sounds: bool,
+    },
+    /// Show details of a user
+    User {
+        /// Username
+        username: Username,
+        /// List the sounds of the user instead of their profile
+        #[arg(long)]
+        sounds: bool,
+    },
+    /// Log in to Freesound, for the commands needing OAuth2
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum AuthCommand {
+    /// Authorize the application in the browser and save the tokens
+    Login {
+        /// Paste the authorization code instead of receiving it on this machine
+        #[arg(long)]
+        no_browser: bool,
+    },
+    /// Forget the saved tokens
+    Logout,
+}
+
+#[tokio::main]
+async fn main() {
+    dotenvy::dotenv().ok();
+    let cli = Cli::parse();
+
+    if let Err(e) = run(cli).await {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let mut builder = FreesoundClient::builder(cli.api_key);
+    if let Some(base_url) = cli.base_url {
+        builder = builder.base_url(base_url);
+    }
+    let mut client = builder.build()?;
+    let credentials = cli.client_id.as_deref().zip(cli.client_secret.as_deref());
+
+    if let Command::Auth { command } = &cli.command {
+        let store = FileTokenStore::in_config_dir()?;
+        return auth(&client, &store, credentials, command).await;
+    }
+    // Without a configuration directory there cannot be saved tokens, and the
+    // other commands only need the API key
+    let store = FileTokenStore::default_path().map(FileTokenStore::new);
+    let tokens = match (&store, credentials) {
+        (None, _) => None,
+        (Some(store), Some((client_id, client_secret))) => {
+            store
+                .fresh_tokens(&client, client_id, client_secret)
+                .await?
+        }
+        (Some(store), None) => store.load()?.filter(|tokens| !tokens.is_expired()),
+    };
+    if let Some(tokens) = tokens {
+        client = client.with_tokens(&tokens);
+    }
+
+    match cli.command {
+        Command::Search {
+            query,
+            filter,
+            sort,
+            page,
+            page_size,
+        } => {
+            let mut builder = SearchQueryBuilder::parse(&query)?;
+            if let Some(filter) = filter {
+                builder = builder.filter(filter);
+            }
+            if let Some(sort) = sort {
+                builder = builder.sort(sort);
+            }
+            if let Some(page) = page {
+                builder = builder.page(page);
+            }
+            if let Some(page_size) = page_size {
+                builder = builder.page_size(page_size);
+            }
+            let results = client.search(&builder.build()).await?;
+            print_sounds(&results, cli.format)?;
+        }
+        Command::Get { id } => {
+            let sound = client.get_sound(id, &Default::default()).await?;
+            print_sound(&sound, cli.format)?;
+        }
+        Command::Download {
+            ids,
+            quality,
+            original,
+            dir,
+            overwrite,
+            report,
+        } => {
+            let mut manager = DownloadManager::new(client.clone())
+                .progress(IndicatifProgress::new())
+                .overwrite(overwrite);
+            for id in ids {
+                let sound = client.get_sound(id, &Default::default()).await?;
+                if original {
+                    manager.enqueue(DownloadJob::original(&client, &sound, &dir))?;
+                } else {
+                    manager.enqueue_preview(&sound, quality, &dir)?;
+                }
+            }
+
+            manager.shutdown_handle().shutdown_on_ctrl_c();
+            let outcome = manager.run().await;
+            for item in outcome.failed() {
+                let error = item.error.as_deref().unwrap_or_default();
+                eprintln!("{}: {error}", item.job.sound_id);
+            }
+            if let Some(path) = report {
+                std::fs::write(path, serde_json::to_string_pretty(&outcome)?)?;
+            }
+            if !outcome.is_success() {
+                return Err("some downloads failed".into());
+            }
+        }
+        Command::Pack { id, sounds: true } => {
+            let results = client.get_pack_sounds(id, &Default::default()).await?;
+            print_sounds(&results, cli.format)?;
+        }
+        Command::Pack { id, sounds: false } => {
+            let pack = client.get_pack(id).await?;
+            print_pack(&pack, cli.format)?;
+        }
+        Command::User {
+            username,
+            sounds: true,
+        } => {
+            let results = client
+                .get_user_sounds(&username, &Default::default())
+                .await?;
+            print_sounds(&results, cli.format)?;
+        }
+        Command::User {
+            username,
+            sounds: false,
+        } => {
+            let user = client.get_user(&username).await?;
+            print_user(&user, cli.format)?;
+        }
+        Command::Auth { .. } => unreachable!("handled before the client is authorized"),
+    }
+
+    Ok(())
+}
+
+async fn auth(
+    client: &FreesoundClient,
+    store: &FileTokenStore,
+    credentials: Option<(&str, &str)>,
+    command: &AuthCommand,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        AuthCommand::Login { no_browser } => {
+            let (client_id, client_secret) = credentials
+                .ok_or("logging in needs --client-id and --client-secret, or FREESOUND_CLIENT_ID and FREESOUND_CLIENT_SECRET")?;
+            let tokens = if *no_browser {
+                client.login_out_of_band(client_id, client_secret).await?
+            } else {
+                client.login_interactive(client_id, client_secret).await?
+            };
+            store.save(&tokens)?;
+            let me = client.clone().with_tokens(&tokens).get_me().await?;
+            eprintln!(
+                "Logged in as {}, tokens saved to {}",
+                me.username,
+                store.path().display()
+            );
+        }
+        AuthCommand::Logout => {
+            store.clear()?;
+            eprintln!("Logged out");
+        }
+    }
+    Ok(())
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> serde_json::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn print_sounds(results: &SearchResponse, format: Format) -> serde_json::Result<()> {
+    match format {
+        Format::Json => print_json(results),
+        Format::Table => {
+            println!(
+                "{:>8}  {:<40}  {:<20}  {:>8}  LICENSE",
+                "ID", "NAME", "USERNAME", "DURATION"
+            );
+            for sound in &results.results {
+                println!(
+                    "{:>8}  {:<40}  {:<20}  {:>7.2}s  {}",
+                    sound.id,
+                    truncate(&sound.name, 40),
+                    truncate(&sound.username, 20),
+                    sound.duration,
+                    sound.license
+                );
+            }
+            println!("\n{} results", results.count);
+            Ok(())
+        }
+    }
+}
+
+fn print_sound(sound: &Sound, format: Format) -> serde_json::Result<()> {
+    match format {
+        Format::Json => print_json(sound),
+        Format::Table => {
+            print_row("id", &sound.id.to_string());
+            print_row("name", &sound.name);
+            print_row("username", &sound.username);
+            print_row("duration", &format!("{:.2}s", sound.duration));
+            print_row("type", &sound.sound_type);
+            print_row("samplerate", &sound.samplerate.to_string());
+            print_row("channels", &sound.channels.to_string());
+            print_row("license", &sound.license);
+            print_row("tags", &sound.tags.join(", "));
+            print_row("url", &sound.url);
+            Ok(())
+        }
+    }
+}
+
+fn print_pack(pack: &Pack, format: Format) -> serde_json::Result<()> {
+    match format {
+        Format::Json => print_json(pack),
+        Format::Table => {
+            print_row("id", &pack.id.to_string());
+            print_row("name", &pack.name);
+            print_row("username", &pack.username);
+            print_row("sounds", &pack.num_sounds.to_string());
+            print_row("downloads", &pack.num_downloads.to_string());
+            print_row("created", &pack.created);
+            print_row("url", &pack.url);
+            Ok(())
+        }
+    }
+}
+
+fn print_user(user: &User, format: Format) -> serde_json::Result<()> {
+    match format {
+        Format::Json => print_json(user),
+        Format::Table => {
+            print_row("username", &user.username);
+            print_row("joined", &user.date_joined);
+            print_row("sounds", &user.num_sounds.to_string());
+            print_row("packs", &user.num_packs.to_string());
+            print_row("url", &user.url);
+            Ok(())
+        }
+    }
+}
+
+fn print_row(key: &str, value: &str) {
+    println!("{key:<12} {value}");
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let mut truncated: String = s.chars().take(max - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
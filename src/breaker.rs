@@ -0,0 +1,136 @@
+use crate::clock::{Clock, SystemClock};
+use crate::error::{FreesoundError, Result};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default number of consecutive failures opening the circuit
+pub const DEFAULT_FAILURE_THRESHOLD: u32 = 5;
+
+/// Default time the circuit stays open before a probe is let through
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// State of a [`CircuitBreaker`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent normally
+    Closed,
+    /// Requests fail right away, without reaching Freesound
+    Open,
+    /// The cooldown is over: the next request is sent as a probe
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct Inner {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Stops sending requests after repeated upstream failures
+///
+/// Server errors (5xx), timeouts and connection failures count as failures;
+/// any other response means Freesound is up. After `threshold` consecutive
+/// failures the circuit opens, and requests fail with
+/// [`CircuitOpen`](FreesoundError::CircuitOpen) without being sent. Once the
+/// cooldown is over, one request is let through as a probe: if it succeeds the
+/// circuit closes, otherwise it stays open for another cooldown.
+///
+/// Attach it with [`FreesoundClient::with_circuit_breaker`](crate::FreesoundClient::with_circuit_breaker);
+/// clones of the client share it.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use freesound_rs::{CircuitBreaker, CircuitState, FreesoundClient};
+///
+/// let client = FreesoundClient::new("API_KEY".to_string(), None)
+///     .with_circuit_breaker(CircuitBreaker::new(3, Duration::from_secs(60)));
+/// assert_eq!(client.circuit_breaker().unwrap().state(), CircuitState::Closed);
+/// ```
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    clock: Arc<dyn Clock>,
+    inner: Mutex<Inner>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(DEFAULT_FAILURE_THRESHOLD, DEFAULT_COOLDOWN)
+    }
+}
+
+impl CircuitBreaker {
+    /// Create a breaker opening after `threshold` consecutive failures, for `cooldown`
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            cooldown,
+            clock: Arc::new(SystemClock),
+            inner: Mutex::new(Inner {
+                failures: 0,
+                open_until: None,
+            }),
+        }
+    }
+
+    /// Time the cooldown with another clock (see [`MockClock`](crate::MockClock))
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns the current state
+    pub fn state(&self) -> CircuitState {
+        match self.inner.lock().unwrap().open_until {
+            None => CircuitState::Closed,
+            Some(until) if self.clock.now() < until => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Returns the number of consecutive failures
+    pub fn failures(&self) -> u32 {
+        self.inner.lock().unwrap().failures
+    }
+
+    /// Close the circuit and forget the failures
+    pub fn reset(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.failures = 0;
+        inner.open_until = None;
+    }
+
+    /// Checks whether a request may be sent
+    ///
+    /// When the cooldown is over, the caller becomes the probe and the circuit
+    /// is kept open for the others until the probe has an outcome.
+    pub(crate) fn acquire(&self) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.open_until {
+            None => Ok(()),
+            Some(until) if self.clock.now() < until => Err(FreesoundError::CircuitOpen(format!(
+                "{} consecutive upstream failures",
+                inner.failures
+            ))),
+            Some(_) => {
+                inner.open_until = Some(self.clock.now() + self.cooldown);
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn record_success(&self) {
+        self.reset();
+    }
+
+    pub(crate) fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.failures = inner.failures.saturating_add(1);
+        if inner.failures >= self.threshold {
+            inner.open_until = Some(self.clock.now() + self.cooldown);
+        }
+    }
+}
@@ -0,0 +1,367 @@
+use crate::client::FreesoundClient;
+use crate::environment::Environment;
+use crate::error::{FreesoundError, Result};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::time::Duration;
+
+/// User-Agent sent by default, e.g. `freesound-rs/0.2.0`
+pub const DEFAULT_USER_AGENT: &str = concat!("freesound-rs/", env!("CARGO_PKG_VERSION"));
+
+/// Default time allowed to establish a connection
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default time allowed for an API request, response included
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Builder for a [`FreesoundClient`] with custom HTTP settings
+///
+/// Settings not set here keep the defaults of reqwest.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{DEFAULT_BASE_URL, FreesoundClient};
+///
+/// use std::time::Duration;
+///
+/// let client = FreesoundClient::builder("API_KEY")
+///     .user_agent("my-sampler/1.0 (+https://example.org)")
+///     .default_header("X-Request-Id", "b7f3c2")
+///     .timeout(Some(Duration::from_secs(15)))
+///     .download_timeout(Some(Duration::from_secs(3600)))
+///     .pool_max_idle_per_host(32)
+///     .proxy("http://proxy.example.org:3128")
+///     .proxy_auth("alice", "secret")
+///     .no_proxy("localhost,.internal.example.org")
+///     .http2_keep_alive_interval(Some(Duration::from_secs(30)))
+///     .build()?;
+/// assert_eq!(client.base_url(), DEFAULT_BASE_URL);
+///
+/// assert!(FreesoundClient::builder("API_KEY").default_header("bad header", "x").build().is_err());
+/// assert!(FreesoundClient::builder("API_KEY").base_url("freesound.org").build().is_err());
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug)]
+pub struct FreesoundClientBuilder {
+    api_key: String,
+    environment: Environment,
+    user_agent: String,
+    headers: HeaderMap,
+    http: reqwest::ClientBuilder,
+    proxies: Vec<(ProxyScope, String)>,
+    proxy_auth: Option<(String, String)>,
+    no_proxy: Option<String>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    download_timeout: Option<Duration>,
+    #[cfg(feature = "gzip")]
+    gzip: bool,
+    #[cfg(feature = "deflate")]
+    deflate: bool,
+    #[cfg(feature = "brotli")]
+    brotli: bool,
+    error: Option<FreesoundError>,
+}
+
+impl FreesoundClientBuilder {
+    /// Create a builder using the given API key
+    pub fn new<S: Into<String>>(api_key: S) -> Self {
+        Self {
+            api_key: api_key.into(),
+            environment: Environment::Production,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            headers: HeaderMap::new(),
+            http: reqwest::Client::builder(),
+            proxies: Vec::new(),
+            proxy_auth: None,
+            no_proxy: None,
+            connect_timeout: Some(DEFAULT_CONNECT_TIMEOUT),
+            timeout: Some(DEFAULT_TIMEOUT),
+            download_timeout: None,
+            #[cfg(feature = "gzip")]
+            gzip: true,
+            #[cfg(feature = "deflate")]
+            deflate: true,
+            #[cfg(feature = "brotli")]
+            brotli: true,
+            error: None,
+        }
+    }
+
+    /// Set the instance of the API (defaults to [`Environment::Production`])
+    ///
+    /// An invalid custom URL makes [`build`](Self::build) fail.
+    pub fn environment(mut self, environment: Environment) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    /// Set the base URL of the API (defaults to [`DEFAULT_BASE_URL`](crate::DEFAULT_BASE_URL))
+    ///
+    /// Shorthand for [`environment`](Self::environment) with
+    /// [`Environment::custom`]. An invalid URL makes [`build`](Self::build) fail.
+    pub fn base_url<S: Into<String>>(mut self, base_url: S) -> Self {
+        match Environment::custom(&base_url.into()) {
+            Ok(environment) => self.environment = environment,
+            Err(e) => {
+                self.error.get_or_insert(e);
+            }
+        }
+        self
+    }
+
+    /// Set the User-Agent header (defaults to [`DEFAULT_USER_AGENT`])
+    pub fn user_agent<S: Into<String>>(mut self, user_agent: S) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Add a header sent with every request
+    ///
+    /// An invalid header name or value makes [`build`](Self::build) fail.
+    pub fn default_header(mut self, name: &str, value: &str) -> Self {
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            (Ok(name), Ok(value)) => {
+                self.headers.insert(name, value);
+            }
+            _ => {
+                self.error
+                    .get_or_insert(FreesoundError::InvalidRequest(format!(
+                        "invalid header: {name}: {value}"
+                    )));
+            }
+        }
+        self
+    }
+
+    /// Add headers sent with every request
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Set the time allowed to establish a connection, `None` for no limit
+    ///
+    /// Defaults to [`DEFAULT_CONNECT_TIMEOUT`]. Applies to every request,
+    /// downloads included.
+    pub fn connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Set the time allowed for an API request, `None` for no limit
+    ///
+    /// Defaults to [`DEFAULT_TIMEOUT`]. Covers the whole request, from connecting
+    /// to reading the response. Downloads use the
+    /// [`download_timeout`](Self::download_timeout) instead.
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the time allowed for a file download, `None` for no limit
+    ///
+    /// Defaults to no limit, so large files are not cut off. Applies to
+    /// previews, originals and images, and to uploads. A
+    /// [`RetryPolicy`](crate::RetryPolicy) with a timeout of its own overrides
+    /// it for each attempt.
+    pub fn download_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.download_timeout = timeout;
+        self
+    }
+
+    /// Set the maximum number of idle connections kept per host
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.http = self.http.pool_max_idle_per_host(max);
+        self
+    }
+
+    /// Set how long an idle connection is kept in the pool, `None` for ever
+    pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.http = self.http.pool_idle_timeout(timeout);
+        self
+    }
+
+    /// Set the interval of TCP keep-alive probes, `None` to disable them
+    pub fn tcp_keepalive(mut self, interval: Option<Duration>) -> Self {
+        self.http = self.http.tcp_keepalive(interval);
+        self
+    }
+
+    /// Only use HTTP/1.1
+    pub fn http1_only(mut self) -> Self {
+        self.http = self.http.http1_only();
+        self
+    }
+
+    /// Use HTTP/2 without negotiating it first
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.http = self.http.http2_prior_knowledge();
+        self
+    }
+
+    /// Set the interval of HTTP/2 keep-alive pings, `None` to disable them
+    pub fn http2_keep_alive_interval(mut self, interval: Option<Duration>) -> Self {
+        self.http = self.http.http2_keep_alive_interval(interval);
+        self
+    }
+
+    /// Set how long to wait for the answer to an HTTP/2 keep-alive ping
+    pub fn http2_keep_alive_timeout(mut self, timeout: Duration) -> Self {
+        self.http = self.http.http2_keep_alive_timeout(timeout);
+        self
+    }
+
+    /// Send HTTP/2 keep-alive pings even when no request is in flight
+    pub fn http2_keep_alive_while_idle(mut self, enable: bool) -> Self {
+        self.http = self.http.http2_keep_alive_while_idle(enable);
+        self
+    }
+
+    /// Let HTTP/2 flow control windows adapt to the bandwidth
+    pub fn http2_adaptive_window(mut self, enable: bool) -> Self {
+        self.http = self.http.http2_adaptive_window(enable);
+        self
+    }
+
+    /// Send all requests through a proxy
+    ///
+    /// Supports `http://`, `https://` and, with the `socks` feature, `socks5://`
+    /// and `socks5h://` URLs. Credentials can be part of the URL or set with
+    /// [`proxy_auth`](Self::proxy_auth).
+    ///
+    /// Without any proxy set here, the `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY`
+    /// and `NO_PROXY` environment variables are honored.
+    pub fn proxy<S: Into<String>>(mut self, url: S) -> Self {
+        self.proxies.push((ProxyScope::All, url.into()));
+        self
+    }
+
+    /// Send plain HTTP requests through a proxy
+    pub fn http_proxy<S: Into<String>>(mut self, url: S) -> Self {
+        self.proxies.push((ProxyScope::Http, url.into()));
+        self
+    }
+
+    /// Send HTTPS requests through a proxy
+    pub fn https_proxy<S: Into<String>>(mut self, url: S) -> Self {
+        self.proxies.push((ProxyScope::Https, url.into()));
+        self
+    }
+
+    /// Authenticate to the proxies with basic credentials
+    pub fn proxy_auth(mut self, username: &str, password: &str) -> Self {
+        self.proxy_auth = Some((username.to_string(), password.to_string()));
+        self
+    }
+
+    /// Set the hosts reached without the proxies, in the `NO_PROXY` format
+    ///
+    /// For instance `"localhost,.internal.example.org,10.0.0.0/8"`. Defaults to
+    /// the `NO_PROXY` environment variable.
+    pub fn no_proxy<S: Into<String>>(mut self, hosts: S) -> Self {
+        self.no_proxy = Some(hosts.into());
+        self
+    }
+
+    /// Ignore the proxies set in the environment
+    pub fn no_system_proxy(mut self) -> Self {
+        self.http = self.http.no_proxy();
+        self
+    }
+
+    /// Accept gzip-compressed responses (defaults to true)
+    ///
+    /// Requires the `gzip` feature. Responses are decompressed transparently.
+    #[cfg(feature = "gzip")]
+    pub fn gzip(mut self, enable: bool) -> Self {
+        self.gzip = enable;
+        self
+    }
+
+    /// Accept deflate-compressed responses (defaults to true)
+    ///
+    /// Requires the `deflate` feature. Responses are decompressed transparently.
+    #[cfg(feature = "deflate")]
+    pub fn deflate(mut self, enable: bool) -> Self {
+        self.deflate = enable;
+        self
+    }
+
+    /// Accept brotli-compressed responses (defaults to true)
+    ///
+    /// Requires the `brotli` feature. Responses are decompressed transparently.
+    #[cfg(feature = "brotli")]
+    pub fn brotli(mut self, enable: bool) -> Self {
+        self.brotli = enable;
+        self
+    }
+
+    /// Build the client
+    pub fn build(self) -> Result<FreesoundClient> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let base_url = self.environment.base_url()?;
+        let user_agent = HeaderValue::from_str(&self.user_agent).map_err(|_| {
+            FreesoundError::InvalidRequest(format!("invalid User-Agent: {}", self.user_agent))
+        })?;
+
+        let mut client = self
+            .http
+            .user_agent(user_agent)
+            .default_headers(self.headers);
+        let no_proxy = match &self.no_proxy {
+            Some(hosts) => reqwest::NoProxy::from_string(hosts),
+            None => reqwest::NoProxy::from_env(),
+        };
+        for (scope, url) in &self.proxies {
+            let proxy = match scope {
+                ProxyScope::All => reqwest::Proxy::all(url),
+                ProxyScope::Http => reqwest::Proxy::http(url),
+                ProxyScope::Https => reqwest::Proxy::https(url),
+            }
+            .map_err(|_| FreesoundError::InvalidRequest(format!("invalid proxy URL: {url}")))?;
+            let proxy = match &self.proxy_auth {
+                Some((username, password)) => proxy.basic_auth(username, password),
+                None => proxy,
+            };
+            client = client.proxy(proxy.no_proxy(no_proxy.clone()));
+        }
+        if let Some(timeout) = self.connect_timeout {
+            client = client.connect_timeout(timeout);
+        }
+        #[cfg(feature = "gzip")]
+        {
+            client = client.gzip(self.gzip);
+        }
+        #[cfg(feature = "deflate")]
+        {
+            client = client.deflate(self.deflate);
+        }
+        #[cfg(feature = "brotli")]
+        {
+            client = client.brotli(self.brotli);
+        }
+        let client = client.build().map_err(FreesoundError::from)?;
+
+        Ok(FreesoundClient::from_parts(
+            client,
+            self.api_key,
+            base_url,
+            self.timeout,
+            self.download_timeout,
+        ))
+    }
+}
+
+/// Which requests go through a proxy
+#[derive(Debug, Clone, Copy)]
+enum ProxyScope {
+    All,
+    Http,
+    Https,
+}
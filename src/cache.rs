@@ -0,0 +1,434 @@
+use crate::client::FreesoundClient;
+use crate::clock::{Clock, SystemClock};
+use crate::error::Result;
+use crate::models::{Page, ResponseMeta, canonical_query};
+use blocking::unblock;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Caches search result pages for a limited time
+///
+/// Pages are keyed by the endpoint and the query parameters, in any order, so
+/// going back to a page already seen does not issue the same request again.
+/// Both [`FreesoundClient::search`] and the pagination links of searches (see
+/// [`FreesoundClient::get_page`]) go through the cache. Clones of a client
+/// share its cache.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use freesound_rs::{FreesoundClient, SearchCache};
+///
+/// let client = FreesoundClient::new("API_KEY".to_string(), None)
+///     .with_search_cache(SearchCache::new(Duration::from_secs(300)));
+/// assert!(client.search_cache().unwrap().is_empty());
+/// ```
+#[derive(Debug)]
+pub struct SearchCache {
+    ttl: Duration,
+    clock: Arc<dyn Clock>,
+    entries: Mutex<HashMap<String, (Instant, serde_json::Value)>>,
+}
+
+impl SearchCache {
+    /// Create a cache keeping pages for `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            clock: Arc::new(SystemClock),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Measure the age of pages with another clock (see [`MockClock`](crate::MockClock))
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns how long pages are kept
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Returns the number of pages cached, including expired ones not purged yet
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    /// Returns true if no page is cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forget every cached page
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Forget the pages older than the TTL
+    pub fn purge_expired(&self) {
+        let ttl = self.ttl;
+        let now = self.clock.now();
+        self.entries
+            .lock()
+            .unwrap()
+            .retain(|_, (stored, _)| now.duration_since(*stored) < ttl);
+    }
+
+    fn get(&self, key: &str) -> Option<serde_json::Value> {
+        let now = self.clock.now();
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(key) {
+            Some((stored, value)) if now.duration_since(*stored) < self.ttl => Some(value.clone()),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, key: String, value: serde_json::Value) {
+        self.purge_expired();
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key, (self.clock.now(), value));
+    }
+}
+
+/// Keeps media files, such as previews, in a directory of limited size
+///
+/// Files are named after the digest of their URL. Once the files exceed the
+/// size budget, the least recently used ones are deleted. The time of last use
+/// is kept as the modification time of each file, so the order survives
+/// between sessions. [`FreesoundClient::fetch_preview`] and the other requests
+/// loading media into memory go through the cache; clones of a client share it.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{DiskCache, FreesoundClient};
+///
+/// let dir = std::env::temp_dir().join("freesound-rs-doctest-disk-cache");
+/// let cache = DiskCache::open(&dir, 500 * 1024 * 1024)?;
+/// cache.clear()?;
+///
+/// let client = FreesoundClient::new("API_KEY".to_string(), None).with_disk_cache(cache);
+/// let stats = client.disk_cache().unwrap().stats();
+/// assert_eq!((stats.files, stats.bytes), (0, 0));
+/// assert_eq!(stats.max_bytes, 500 * 1024 * 1024);
+/// # std::fs::remove_dir_all(&dir)?;
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug)]
+pub struct DiskCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    state: Mutex<DiskCacheState>,
+}
+
+#[derive(Debug, Default)]
+struct DiskCacheState {
+    files: HashMap<String, (u64, SystemTime)>,
+    bytes: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+/// Usage of a [`DiskCache`], returned by [`DiskCache::stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    /// Number of files in the cache
+    pub files: usize,
+    /// Size of the files in the cache
+    pub bytes: u64,
+    /// Size budget of the cache
+    pub max_bytes: u64,
+    /// Lookups answered by the cache since it was opened
+    pub hits: u64,
+    /// Lookups the cache could not answer since it was opened
+    pub misses: u64,
+    /// Files deleted to stay within the budget since the cache was opened
+    pub evictions: u64,
+}
+
+impl DiskCache {
+    /// Opens the cache in `dir`, keeping at most `max_bytes` of files
+    ///
+    /// The directory is created if needed. Files already in it count towards
+    /// the budget, and are evicted right away if they exceed it.
+    pub fn open(dir: impl AsRef<Path>, max_bytes: u64) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+        let mut state = DiskCacheState::default();
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !metadata.is_file() || name.ends_with(".part") {
+                continue;
+            }
+            let used = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            state.bytes += metadata.len();
+            state.files.insert(name, (metadata.len(), used));
+        }
+        let cache = Self {
+            dir,
+            max_bytes,
+            state: Mutex::new(state),
+        };
+        for path in cache.evict(&mut cache.state.lock().unwrap()) {
+            let _ = std::fs::remove_file(path);
+        }
+        Ok(cache)
+    }
+
+    /// Returns the directory of the cache
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the size budget of the cache
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    /// Returns the size and hit counts of the cache
+    pub fn stats(&self) -> CacheStats {
+        let state = self.state.lock().unwrap();
+        CacheStats {
+            files: state.files.len(),
+            bytes: state.bytes,
+            max_bytes: self.max_bytes,
+            hits: state.hits,
+            misses: state.misses,
+            evictions: state.evictions,
+        }
+    }
+
+    /// Delete every cached file
+    pub fn clear(&self) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        for name in std::mem::take(&mut state.files).into_keys() {
+            match std::fs::remove_file(self.dir.join(name)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        state.bytes = 0;
+        Ok(())
+    }
+
+    /// Returns the cached body of a URL, marking it as used
+    ///
+    /// The file is read on the blocking pool, without holding the lock.
+    pub(crate) async fn get(&self, url: &str) -> Option<Vec<u8>> {
+        let name = disk_cache_name(url);
+        if !self.state.lock().unwrap().files.contains_key(&name) {
+            self.state.lock().unwrap().misses += 1;
+            return None;
+        }
+
+        let path = self.dir.join(&name);
+        let now = SystemTime::now();
+        let bytes = unblock(move || {
+            let bytes = std::fs::read(&path)?;
+            if let Ok(file) = std::fs::File::options().append(true).open(&path) {
+                let _ = file.set_modified(now);
+            }
+            Ok::<_, std::io::Error>(bytes)
+        })
+        .await;
+
+        let mut state = self.state.lock().unwrap();
+        let Ok(bytes) = bytes else {
+            // The file may have been deleted behind the cache's back
+            if let Some((size, _)) = state.files.remove(&name) {
+                state.bytes -= size;
+            }
+            state.misses += 1;
+            return None;
+        };
+        // Unless it was evicted meanwhile
+        if let Some((_, used)) = state.files.get_mut(&name) {
+            *used = now;
+        }
+        state.hits += 1;
+        Some(bytes)
+    }
+
+    /// Stores the body of a URL, evicting older files to stay within the budget
+    ///
+    /// A body larger than the whole budget is not stored. Failing to write is
+    /// not an error, the body is just not cached. Files are written and
+    /// deleted on the blocking pool, without holding the lock; the body is
+    /// given back once written.
+    pub(crate) async fn insert(&self, url: &str, bytes: Vec<u8>) -> Vec<u8> {
+        if bytes.len() as u64 > self.max_bytes {
+            return bytes;
+        }
+        let name = disk_cache_name(url);
+        let path = self.dir.join(&name);
+        let part = self.dir.join(format!("{name}.part"));
+        let (bytes, written) = unblock(move || {
+            let written = std::fs::write(&part, &bytes).and_then(|_| std::fs::rename(&part, &path));
+            if written.is_err() {
+                let _ = std::fs::remove_file(&part);
+            }
+            (bytes, written)
+        })
+        .await;
+        if written.is_err() {
+            return bytes;
+        }
+
+        let evicted = {
+            let mut state = self.state.lock().unwrap();
+            let size = bytes.len() as u64;
+            if let Some((previous, _)) = state.files.insert(name, (size, SystemTime::now())) {
+                state.bytes -= previous;
+            }
+            state.bytes += size;
+            self.evict(&mut state)
+        };
+        if !evicted.is_empty() {
+            unblock(move || {
+                for path in evicted {
+                    let _ = std::fs::remove_file(path);
+                }
+            })
+            .await;
+        }
+        bytes
+    }
+
+    /// Forgets the least recently used files until the cache fits its budget,
+    /// returning their paths for the caller to delete
+    fn evict(&self, state: &mut DiskCacheState) -> Vec<PathBuf> {
+        let mut evicted = Vec::new();
+        if state.bytes <= self.max_bytes {
+            return evicted;
+        }
+        let mut files: Vec<(String, u64, SystemTime)> = state
+            .files
+            .iter()
+            .map(|(name, (size, used))| (name.clone(), *size, *used))
+            .collect();
+        files.sort_by_key(|(_, _, used)| *used);
+        for (name, size, _) in files {
+            if state.bytes <= self.max_bytes {
+                break;
+            }
+            state.files.remove(&name);
+            state.bytes -= size;
+            state.evictions += 1;
+            evicted.push(self.dir.join(name));
+        }
+        evicted
+    }
+}
+
+/// Names the cache file of a URL, keeping its extension for readability
+fn disk_cache_name(url: &str) -> String {
+    let digest = md5::compute(url.as_bytes());
+    let extension = url
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .and_then(|file| file.rsplit_once('.'))
+        .map(|(_, extension)| extension)
+        .filter(|extension| {
+            !extension.is_empty()
+                && extension.len() <= 5
+                && extension.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+    match extension {
+        Some(extension) => format!("{digest:x}.{extension}"),
+        None => format!("{digest:x}"),
+    }
+}
+
+/// Builds a cache key from an endpoint URL and its parameters, in any order
+///
+/// The API key is left out, and so is a trailing slash of the URL.
+fn cache_key(url: &str, query: &[(String, String)]) -> String {
+    format!("{}?{}", url.trim_end_matches('/'), canonical_query(query))
+}
+
+impl FreesoundClient {
+    /// Sends a GET request, through the search cache if there is one
+    pub(crate) async fn get_cached<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(String, String)],
+    ) -> Result<T> {
+        self.get_cached_meta(url, query)
+            .await
+            .map(|(value, _)| value)
+    }
+
+    /// Fetches a page of results, through the search cache if there is one
+    pub(crate) async fn get_page_cached<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(String, String)],
+    ) -> Result<Page<T>> {
+        let (mut page, meta): (Page<T>, _) = self.get_cached_meta(url, query).await?;
+        page.meta = meta;
+        Ok(page)
+    }
+
+    /// Sends a GET request, through the search cache if there is one
+    ///
+    /// The [`ResponseMeta`] is `None` for responses served by the cache.
+    async fn get_cached_meta<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        query: &[(String, String)],
+    ) -> Result<(T, Option<Box<ResponseMeta>>)> {
+        let request = self.request_url(reqwest::Method::GET, url).query(query);
+        let Some(cache) = self.search_cache() else {
+            return self.send_json_meta(request).await;
+        };
+
+        let key = cache_key(url, query);
+        let cached = cache.get(&key);
+        self.usage().record_cache_lookup(cached.is_some());
+        let (value, meta) = match cached {
+            Some(value) => (value, None),
+            None => {
+                let (value, meta): (serde_json::Value, _) = self.send_json_meta(request).await?;
+                cache.insert(key, value.clone());
+                (value, meta)
+            }
+        };
+        Ok((self.decode(url, value)?, meta))
+    }
+
+    /// Fetches a page from a pagination link, through the search cache for
+    /// search links
+    pub(crate) async fn get_link<T: DeserializeOwned>(&self, url: &str) -> Result<Page<T>> {
+        if self.search_cache().is_some()
+            && let Ok(parsed) = reqwest::Url::parse(url)
+            && parsed.path().contains("/search/")
+        {
+            let query: Vec<_> = parsed.query_pairs().into_owned().collect();
+            let mut endpoint = parsed.clone();
+            endpoint.set_query(None);
+            return self.get_page_cached(endpoint.as_str(), &query).await;
+        }
+        let (mut page, meta): (Page<T>, _) = self
+            .send_json_meta(self.request_url(reqwest::Method::GET, url))
+            .await?;
+        page.meta = meta;
+        Ok(page)
+    }
+}
@@ -1,9 +1,41 @@
-use crate::error::{FreesoundError, Result};
-use crate::models::{SearchResponse, Sound};
-use reqwest;
+use crate::breaker::CircuitBreaker;
+use crate::builder::FreesoundClientBuilder;
+use crate::cache::{DiskCache, SearchCache};
+use crate::clock::{Clock, Sleeper, SystemClock};
+use crate::error::{ErrorContext, FreesoundError, Result};
+use crate::journal::{JournalEntry, JournalSink};
+use crate::limiter::{Priority, RateLimiter};
+use crate::models::{
+    AvatarSize, BookmarkCategory, CombinedSearchResponse, Comment, Field, GetSoundOptions,
+    ImageKind, Pack, PackId, Page, PreviewQuality, ResponseMeta, SearchFields, SearchResponse,
+    SearchSound, Sound, SoundDetail, SoundListOptions, User, Username,
+};
+use crate::retry::RetryPolicy;
+#[cfg(feature = "schema-report")]
+use crate::schema::SchemaRecorder;
+use crate::strictness::Strictness;
+use crate::usage::{Usage, endpoint_name};
+use blocking::{Unblock, unblock};
+use futures::{AsyncSeekExt, AsyncWriteExt};
+use serde::de::DeserializeOwned;
+use std::fs::{File, OpenOptions};
+use std::io::SeekFrom;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
 pub const DEFAULT_BASE_URL: &str = "https://freesound.org/apiv2";
 
+/// How a client authenticates its requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthLevel {
+    /// API key only, enough for the public read-only endpoints
+    Token,
+    /// OAuth2 access token, needed by the endpoints acting on behalf of a user
+    OAuth2,
+}
+
 /// Client for interacting with the [Freesound API](https://freesound.org/docs/api/)
 ///
 /// # Examples
@@ -16,11 +48,41 @@ pub const DEFAULT_BASE_URL: &str = "https://freesound.org/apiv2";
 /// let client = FreesoundClient::new(api_key.clone(), None);
 /// assert_eq!(client.base_url(), DEFAULT_BASE_URL);
 /// ```
+///
+/// Cloning is cheap: clones share the HTTP connection pool, the settings, the
+/// [search cache](Self::with_search_cache), the [disk cache](Self::with_disk_cache)
+/// the [circuit breaker](Self::with_circuit_breaker) and the
+/// [rate limiter](Self::with_rate_limiter), so a client can be
+/// cloned into each task. The `with_*` methods only affect the client they are called on.
 #[derive(Debug, Clone)]
 pub struct FreesoundClient {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Clone)]
+struct Inner {
     client: reqwest::Client,
     api_key: String,
+    access_token: Option<String>,
     base_url: String,
+    search_cache: Option<Arc<SearchCache>>,
+    disk_cache: Option<Arc<DiskCache>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    priority: Priority,
+    journal: Option<Arc<dyn JournalSink>>,
+    usage: Arc<Usage>,
+    #[cfg(feature = "schema-report")]
+    schema: Arc<SchemaRecorder>,
+    clock: Arc<dyn Clock>,
+    sleeper: Arc<dyn Sleeper>,
+    strictness: Strictness,
+    timeout: Option<Duration>,
+    download_timeout: Option<Duration>,
+    max_response_bytes: Option<u64>,
+    response_meta: bool,
+    media_retry: RetryPolicy,
+    api_retry: RetryPolicy,
 }
 
 impl FreesoundClient {
@@ -31,6 +93,11 @@ impl FreesoundClient {
     /// * `api_key` - Your Freesound API key
     /// * `base_url` - Optional custom base URL for the API. If None, uses the default Freesound API URL.
     ///
+    /// A custom `base_url` is used as given, trailing slashes aside: a malformed
+    /// one only shows when requests fail. Build the client with
+    /// [`builder`](Self::builder) and [`base_url`](FreesoundClientBuilder::base_url)
+    /// to have it checked up front (see [`Environment`](crate::Environment)).
+    ///
     /// # Examples
     ///
     /// ```
@@ -43,14 +110,63 @@ impl FreesoundClient {
     /// assert_eq!(client.base_url(), DEFAULT_BASE_URL);
     ///
     /// // With custom URL
-    /// let client = FreesoundClient::new(api_key, Some("https://custom.api.url".to_string()));
+    /// let client = FreesoundClient::new(api_key.clone(), Some("https://custom.api.url".to_string()));
     /// assert_eq!(client.base_url(), "https://custom.api.url");
+    ///
+    /// // Only the builder checks it
+    /// let client = FreesoundClient::new(api_key.clone(), Some("custom.api.url".to_string()));
+    /// assert_eq!(client.base_url(), "custom.api.url");
+    /// assert!(FreesoundClient::builder(api_key).base_url("custom.api.url").build().is_err());
     /// ```
     pub fn new(api_key: String, base_url: Option<String>) -> Self {
+        let mut client = Self::builder(api_key)
+            .build()
+            .expect("Failed to create the HTTP client");
+        if let Some(base_url) = base_url {
+            Arc::make_mut(&mut client.inner).base_url = base_url.trim_end_matches('/').to_string();
+        }
+        client
+    }
+
+    /// Creates a builder, to customize the HTTP settings of the client
+    ///
+    /// See [`FreesoundClientBuilder`].
+    pub fn builder<S: Into<String>>(api_key: S) -> FreesoundClientBuilder {
+        FreesoundClientBuilder::new(api_key)
+    }
+
+    pub(crate) fn from_parts(
+        client: reqwest::Client,
+        api_key: String,
+        base_url: String,
+        timeout: Option<Duration>,
+        download_timeout: Option<Duration>,
+    ) -> Self {
         Self {
-            client: reqwest::Client::new(),
-            api_key,
-            base_url: base_url.unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+            inner: Arc::new(Inner {
+                client,
+                api_key,
+                access_token: None,
+                base_url,
+                search_cache: None,
+                disk_cache: None,
+                circuit_breaker: None,
+                rate_limiter: None,
+                priority: Priority::default(),
+                journal: None,
+                usage: Arc::default(),
+                #[cfg(feature = "schema-report")]
+                schema: Arc::default(),
+                clock: Arc::new(SystemClock),
+                sleeper: Arc::new(SystemClock),
+                strictness: Strictness::default(),
+                timeout,
+                download_timeout,
+                max_response_bytes: None,
+                response_meta: false,
+                media_retry: RetryPolicy::media(),
+                api_retry: RetryPolicy::api(),
+            }),
         }
     }
 
@@ -67,7 +183,370 @@ impl FreesoundClient {
     /// assert_eq!(client.api_key(), api_key);
     /// ```
     pub fn api_key(&self) -> &str {
-        &self.api_key
+        &self.inner.api_key
+    }
+
+    /// Use an OAuth2 access token to authenticate requests
+    ///
+    /// OAuth2 is required by the endpoints acting on behalf of a user, such as
+    /// bookmarking, rating, commenting or downloading original files. When set,
+    /// the access token is sent instead of the API key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use freesound_rs::FreesoundClient;
+    /// let client = FreesoundClient::new("API_KEY".to_string(), None)
+    ///     .with_access_token("ACCESS_TOKEN".to_string());
+    /// assert_eq!(client.access_token(), Some("ACCESS_TOKEN"));
+    /// ```
+    pub fn with_access_token(mut self, access_token: String) -> Self {
+        Arc::make_mut(&mut self.inner).access_token = Some(access_token);
+        self
+    }
+
+    /// Returns the OAuth2 access token used by the client, if any
+    pub fn access_token(&self) -> Option<&str> {
+        self.inner.access_token.as_deref()
+    }
+
+    /// Returns how the client authenticates its requests
+    ///
+    /// With [`AuthLevel::Token`], the endpoints requiring OAuth2 fail right away
+    /// with [`RequiresOAuth`](FreesoundError::RequiresOAuth), without sending a
+    /// request.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::{AuthLevel, FreesoundClient};
+    ///
+    /// let client = FreesoundClient::new("API_KEY".to_string(), None);
+    /// assert_eq!(client.auth_level(), AuthLevel::Token);
+    /// let client = client.with_access_token("ACCESS_TOKEN".to_string());
+    /// assert_eq!(client.auth_level(), AuthLevel::OAuth2);
+    /// ```
+    pub fn auth_level(&self) -> AuthLevel {
+        match self.inner.access_token {
+            Some(_) => AuthLevel::OAuth2,
+            None => AuthLevel::Token,
+        }
+    }
+
+    /// Fails with [`RequiresOAuth`](FreesoundError::RequiresOAuth) unless the client has an access token
+    pub(crate) fn require_oauth(&self, operation: &str) -> Result<()> {
+        match self.auth_level() {
+            AuthLevel::OAuth2 => Ok(()),
+            AuthLevel::Token => Err(FreesoundError::RequiresOAuth(format!(
+                "{operation} needs an OAuth2 access token"
+            ))),
+        }
+    }
+
+    /// Cache search result pages (see [`SearchCache`])
+    pub fn with_search_cache(mut self, cache: SearchCache) -> Self {
+        Arc::make_mut(&mut self.inner).search_cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Returns the search cache, if any
+    pub fn search_cache(&self) -> Option<&SearchCache> {
+        self.inner.search_cache.as_deref()
+    }
+
+    /// Keep previews and other media files on disk (see [`DiskCache`])
+    pub fn with_disk_cache(mut self, cache: DiskCache) -> Self {
+        Arc::make_mut(&mut self.inner).disk_cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// Returns the disk cache, if any
+    pub fn disk_cache(&self) -> Option<&DiskCache> {
+        self.inner.disk_cache.as_deref()
+    }
+
+    /// Stop sending requests while Freesound keeps failing (see [`CircuitBreaker`])
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        Arc::make_mut(&mut self.inner).circuit_breaker = Some(Arc::new(breaker));
+        self
+    }
+
+    /// Returns the circuit breaker, if any
+    pub fn circuit_breaker(&self) -> Option<&CircuitBreaker> {
+        self.inner.circuit_breaker.as_deref()
+    }
+
+    /// Space the requests to stay under the API quota (see [`RateLimiter`])
+    pub fn with_rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        Arc::make_mut(&mut self.inner).rate_limiter = Some(Arc::new(limiter));
+        self
+    }
+
+    /// Returns the rate limiter, if any
+    pub fn rate_limiter(&self) -> Option<&RateLimiter> {
+        self.inner.rate_limiter.as_deref()
+    }
+
+    /// Set the priority of the requests for the rate limiter (see [`Priority`])
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::{FreesoundClient, Priority, RateLimiter};
+    ///
+    /// let client = FreesoundClient::new("API_KEY".to_string(), None)
+    ///     .with_rate_limiter(RateLimiter::default());
+    /// // A sync running on this clone yields to the requests of `client`
+    /// let background = client.clone().with_priority(Priority::Background);
+    /// assert_eq!(background.priority(), Priority::Background);
+    /// assert_eq!(client.priority(), Priority::Interactive);
+    /// ```
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        Arc::make_mut(&mut self.inner).priority = priority;
+        self
+    }
+
+    /// Returns the priority of the requests for the rate limiter
+    pub fn priority(&self) -> Priority {
+        self.inner.priority
+    }
+
+    /// Record every API call in a journal (see [`JournalSink`])
+    ///
+    /// Clones of the client share the journal. The API key is left out of the
+    /// recorded parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use freesound_rs::{FreesoundClient, MemoryJournal};
+    ///
+    /// let journal = Arc::new(MemoryJournal::default());
+    /// let client = FreesoundClient::new("API_KEY".to_string(), None).with_journal(journal.clone());
+    /// assert!(journal.entries().is_empty());
+    /// ```
+    pub fn with_journal(mut self, journal: impl JournalSink + 'static) -> Self {
+        Arc::make_mut(&mut self.inner).journal = Some(Arc::new(journal));
+        self
+    }
+
+    /// Refuse response bodies larger than `bytes` read into memory
+    ///
+    /// Applies to JSON responses, error messages and media fetched into memory
+    /// (see [`fetch_preview`](Self::fetch_preview)); downloads written to files
+    /// are not limited. A body announced larger is refused before being read,
+    /// and the others are read until they go over, so a misbehaving proxy or a
+    /// huge analysis cannot exhaust the memory. They fail with
+    /// [`ResponseTooLarge`](FreesoundError::ResponseTooLarge). Unlimited by
+    /// default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::FreesoundClient;
+    ///
+    /// let client = FreesoundClient::new("API_KEY".to_string(), None).with_max_response_size(16 << 20);
+    /// assert_eq!(client.max_response_size(), Some(16 << 20));
+    /// ```
+    pub fn with_max_response_size(mut self, bytes: u64) -> Self {
+        Arc::make_mut(&mut self.inner).max_response_bytes = Some(bytes);
+        self
+    }
+
+    /// Returns the largest response body read into memory, if limited
+    pub fn max_response_size(&self) -> Option<u64> {
+        self.inner.max_response_bytes
+    }
+
+    /// Retry the downloads of previews and images as `policy` says
+    ///
+    /// Applies to the files served by media hosts rather than the API, whose
+    /// transient failures are common. Defaults to [`RetryPolicy::media`].
+    pub fn with_media_retry(mut self, policy: RetryPolicy) -> Self {
+        Arc::make_mut(&mut self.inner).media_retry = policy;
+        self
+    }
+
+    /// Returns the retry policy of the downloads from media hosts
+    pub fn media_retry(&self) -> RetryPolicy {
+        self.inner.media_retry
+    }
+
+    /// Retry the downloads of original files as `policy` says
+    ///
+    /// Applies to the files served by the API itself. Defaults to
+    /// [`RetryPolicy::api`].
+    pub fn with_api_retry(mut self, policy: RetryPolicy) -> Self {
+        Arc::make_mut(&mut self.inner).api_retry = policy;
+        self
+    }
+
+    /// Returns the retry policy of the downloads from the API
+    pub fn api_retry(&self) -> RetryPolicy {
+        self.inner.api_retry
+    }
+
+    /// Attach a [`ResponseMeta`](crate::ResponseMeta) to searches and sounds
+    ///
+    /// The results of [`search`](Self::search), its following
+    /// [pages](Self::next_page) and [`get_sound`](Self::get_sound) then carry
+    /// the URL that was requested, with the API key redacted, the status, the
+    /// time taken and the rate limit headers, to see exactly what a query
+    /// sent. Results served by the [search cache](Self::with_search_cache)
+    /// carry none. Off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FreesoundClient, SearchQueryBuilder};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None).with_response_meta(true);
+    /// let query = SearchQueryBuilder::new().query("rain").filter("duration:[1 TO 5]").build();
+    /// let results = client.search(&query).await?;
+    /// if let Some(meta) = &results.meta {
+    ///     println!("{} ({}) in {:?}", meta.final_url, meta.status, meta.elapsed);
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn with_response_meta(mut self, enabled: bool) -> Self {
+        Arc::make_mut(&mut self.inner).response_meta = enabled;
+        self
+    }
+
+    /// Returns true if searches and sounds carry a [`ResponseMeta`](crate::ResponseMeta)
+    pub fn response_meta(&self) -> bool {
+        self.inner.response_meta
+    }
+
+    /// Wait between retries and polls with another sleeper
+    ///
+    /// Affects the retries of [`combined_batches`](Self::combined_batches) and of
+    /// downloads (see [`RetryPolicy`]), and the
+    /// intervals of [`watch`](Self::watch) and [`track_upload`](Self::track_upload).
+    /// A [`MockClock`](crate::MockClock) makes them return at once in tests.
+    pub fn with_sleeper(mut self, sleeper: Arc<dyn Sleeper>) -> Self {
+        Arc::make_mut(&mut self.inner).sleeper = sleeper;
+        self
+    }
+
+    /// Returns the sleeper used between retries and polls
+    pub(crate) fn sleeper(&self) -> &dyn Sleeper {
+        self.inner.sleeper.as_ref()
+    }
+
+    /// Read the time from another clock
+    ///
+    /// Used to pace the operations of a [`batch`](crate::batch::execute). Set
+    /// the same [`MockClock`](crate::MockClock) as the
+    /// [sleeper](Self::with_sleeper) to test pacing without waiting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use freesound_rs::batch::{self, Limits, Operation};
+    /// use freesound_rs::{FreesoundClient, MockClock, PreviewQuality, Sound};
+    ///
+    /// let clock = Arc::new(MockClock::new());
+    /// let client = FreesoundClient::new("API_KEY".to_string(), None)
+    ///     .with_clock(clock.clone())
+    ///     .with_sleeper(clock.clone());
+    /// let ops = (1..=3).map(|id| Operation::DownloadPreview {
+    ///     sound: Box::new(Sound { id, ..Default::default() }),
+    ///     quality: PreviewQuality::HqMp3,
+    ///     path: format!("{id}.mp3").into(),
+    /// });
+    /// let limits = Limits::new().concurrency(1).per_minute(60);
+    /// futures::executor::block_on(batch::execute(&client, ops, limits));
+    /// // The three operations started a second apart
+    /// assert_eq!(clock.elapsed(), Duration::from_secs(2));
+    /// ```
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        Arc::make_mut(&mut self.inner).clock = clock;
+        self
+    }
+
+    /// Returns the clock of the client
+    pub(crate) fn clock(&self) -> &dyn Clock {
+        self.inner.clock.as_ref()
+    }
+
+    /// Set how tolerant the client is of unexpected responses (see [`Strictness`])
+    pub fn with_strictness(mut self, strictness: Strictness) -> Self {
+        Arc::make_mut(&mut self.inner).strictness = strictness;
+        self
+    }
+
+    /// Returns how tolerant the client is of unexpected responses
+    pub fn strictness(&self) -> Strictness {
+        self.inner.strictness
+    }
+
+    /// Deserializes a JSON response from `url` with the strictness of the client
+    ///
+    /// With the `schema-report` feature, the mismatches with the models are recorded.
+    pub(crate) fn decode<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        value: serde_json::Value,
+    ) -> Result<T> {
+        #[cfg(feature = "schema-report")]
+        let decoded = {
+            let endpoint = match reqwest::Url::parse(url) {
+                Ok(parsed) => endpoint_name(&self.inner.base_url, &parsed),
+                Err(_) => url.to_string(),
+            };
+            self.inner
+                .schema
+                .decode(&endpoint, self.inner.strictness, value)
+        };
+        #[cfg(not(feature = "schema-report"))]
+        let decoded = {
+            let _ = url;
+            self.inner.strictness.decode(value)
+        };
+        decoded.map_err(|e| {
+            let error = FreesoundError::from(e);
+            match reqwest::Url::parse(url) {
+                Ok(url) => error.with_context(self.error_context(&url)),
+                Err(_) => error,
+            }
+        })
+    }
+
+    /// Describes a request to `url`, for its errors
+    pub(crate) fn error_context(&self, url: &reqwest::Url) -> ErrorContext {
+        ErrorContext::new(&self.inner.base_url, url)
+    }
+
+    /// Returns the schema mismatches, shared by the clones of the client
+    #[cfg(feature = "schema-report")]
+    pub(crate) fn schema(&self) -> &SchemaRecorder {
+        &self.inner.schema
+    }
+
+    /// Returns the usage counters, shared by the clones of the client
+    pub(crate) fn usage(&self) -> &Usage {
+        &self.inner.usage
+    }
+
+    /// Returns the underlying HTTP client
+    pub(crate) fn http_client(&self) -> &reqwest::Client {
+        &self.inner.client
+    }
+
+    /// Returns a clone of the client that does not use the search cache
+    pub(crate) fn without_search_cache(&self) -> Self {
+        let mut client = self.clone();
+        Arc::make_mut(&mut client.inner).search_cache = None;
+        client
     }
 
     /// Returns the base URL used by the client
@@ -83,12 +562,13 @@ impl FreesoundClient {
     /// assert_eq!(client.base_url(), DEFAULT_BASE_URL);
     /// ```
     pub fn base_url(&self) -> &str {
-        &self.base_url
+        &self.inner.base_url
     }
 
     /// Creates a new authenticated request to the Freesound API
     ///
-    /// This method adds the API key as a query parameter to requests
+    /// This method adds the API key as a query parameter to requests, or the
+    /// OAuth2 access token as a bearer token when one is set
     ///
     /// # Arguments
     ///
@@ -97,7 +577,7 @@ impl FreesoundClient {
     ///
     /// # Returns
     ///
-    /// A reqwest RequestBuilder with the API key included
+    /// A reqwest RequestBuilder with the credentials included
     ///
     /// # Examples
     ///
@@ -111,10 +591,49 @@ impl FreesoundClient {
     /// let request = client.request(Method::GET, "sounds/1234");
     /// ```
     pub fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
-        let url = format!("{}/{}", self.base_url, path.trim_start_matches('/'));
-        self.client
-            .request(method, url)
-            .query(&[("token", &self.api_key)])
+        let url = format!("{}/{}", self.inner.base_url, path.trim_start_matches('/'));
+        self.request_url(method, &url)
+    }
+
+    /// Creates a new authenticated request to an absolute API URL, such as a pagination link
+    pub(crate) fn request_url(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+    ) -> reqwest::RequestBuilder {
+        let request = self.authorize(self.inner.client.request(method, url));
+        match self.inner.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    /// Adds the credentials to a request
+    pub(crate) fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.inner.access_token {
+            Some(token) => request.bearer_auth(token),
+            None => request.query(&[("token", &self.inner.api_key)]),
+        }
+    }
+
+    /// Applies the download timeout to a request
+    pub(crate) fn download_request(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> reqwest::RequestBuilder {
+        self.retried_request(request, RetryPolicy::none())
+    }
+
+    /// Applies the timeout of a retry policy, or the download timeout, to a request
+    fn retried_request(
+        &self,
+        request: reqwest::RequestBuilder,
+        policy: RetryPolicy,
+    ) -> reqwest::RequestBuilder {
+        match policy.timeout.or(self.inner.download_timeout) {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
     }
 
     /// Performs a test request to verify the API key is valid
@@ -149,10 +668,8 @@ impl FreesoundClient {
     /// ```
     pub async fn test_api_key(&self) -> Result<()> {
         let response = self
-            .request(reqwest::Method::GET, "sounds/794253")
-            .send()
-            .await
-            .map_err(FreesoundError::from)?;
+            .send(self.request(reqwest::Method::GET, "sounds/794253"))
+            .await?;
 
         let status = response.status();
 
@@ -220,86 +737,1133 @@ impl FreesoundClient {
     ///  }
     /// ```
     pub async fn search(&self, query: &[(String, String)]) -> Result<SearchResponse> {
-        let response = self
-            .request(reqwest::Method::GET, "search/text")
-            .query(query)
-            .send()
+        self.get_page_cached(&format!("{}/search/text", self.inner.base_url), query)
             .await
-            .map_err(FreesoundError::from)?;
+    }
 
-        let status = response.status();
-        if !status.is_success() {
-            let body = response.text().await.map_err(FreesoundError::from)?;
-            return Err(FreesoundError::ApiError(format!(
-                "API request failed: {status} - {body}"
-            )));
-        }
+    /// Search for sounds, keeping only the fields that were returned
+    ///
+    /// Works like [`search`](Self::search), but the results are
+    /// [`SearchSound`](crate::SearchSound)s: a field left out by the `fields`
+    /// parameter is `None` instead of a default value.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FreesoundClient, SearchQueryBuilder};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let query = SearchQueryBuilder::new()
+    ///     .query("rain")
+    ///     .fields(["id", "name", "duration"])
+    ///     .build();
+    /// for sound in client.search_lean(&query).await? {
+    ///     match sound.duration {
+    ///         Some(duration) => println!("{:?}: {duration}s", sound.name),
+    ///         None => println!("{:?}: duration unknown", sound.name),
+    ///     }
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn search_lean(&self, query: &[(String, String)]) -> Result<Page<SearchSound>> {
+        self.get_page_cached(&format!("{}/search/text", self.inner.base_url), query)
+            .await
+    }
+
+    /// Search for sounds, reading the results into a type of your own
+    ///
+    /// The `fields` parameter is set to the [`FIELDS`](SearchFields::FIELDS)
+    /// of the type, replacing any given in `query`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{Field, FreesoundClient, SearchFields, SearchQueryBuilder};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Hit {
+    ///     id: i32,
+    ///     duration: f32,
+    /// }
+    ///
+    /// impl SearchFields for Hit {
+    ///     const FIELDS: &'static [Field] = &[Field::Id, Field::Duration];
+    /// }
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let query = SearchQueryBuilder::new().query("rain").build();
+    /// for hit in client.search_as::<Hit>(&query).await?.results {
+    ///     println!("{}: {}s", hit.id, hit.duration);
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn search_as<T: SearchFields>(&self, query: &[(String, String)]) -> Result<Page<T>> {
+        let fields: Vec<&str> = T::FIELDS.iter().map(Field::as_str).collect();
+        let mut query: Vec<_> = query
+            .iter()
+            .filter(|(k, _)| k != "fields")
+            .cloned()
+            .collect();
+        query.push(("fields".to_string(), fields.join(",")));
+        self.get_page_cached(&format!("{}/search/text", self.inner.base_url), &query)
+            .await
+    }
+
+    /// Search for sounds, returning the raw JSON body of the page
+    ///
+    /// Parse it with [`SoundRef::page`](crate::SoundRef::page) to read the
+    /// results without copying their text, or with any other parser. The body
+    /// is read within the [maximum response size](Self::with_max_response_size),
+    /// but bypasses the search cache and the schema checks.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FreesoundClient, SearchQueryBuilder, SoundRef};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let query = SearchQueryBuilder::new()
+    ///     .query("rain")
+    ///     .fields(["id", "name", "tags"])
+    ///     .page_size(150)
+    ///     .build();
+    /// let body = client.search_bytes(&query).await?;
+    /// for sound in SoundRef::page(&body)?.results {
+    ///     println!("{:?}: {:?}", sound.id, sound.name);
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn search_bytes(&self, query: &[(String, String)]) -> Result<Vec<u8>> {
+        let request = self
+            .request_url(
+                reqwest::Method::GET,
+                &format!("{}/search/text", self.inner.base_url),
+            )
+            .query(query);
+        let response = self.send(request).await?;
+        self.read_body(self.check_status(response).await?).await
+    }
 
-        response
-            .json::<SearchResponse>()
+    /// Search for sounds matching both a text query and a descriptor target
+    ///
+    /// Build the parameters with [`CombinedSearchBuilder`](crate::CombinedSearchBuilder).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{CombinedSearchBuilder, FreesoundClient};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let query = CombinedSearchBuilder::new()
+    ///     .query("kick")
+    ///     .target("lowlevel.pitch.mean:60")
+    ///     .weights(0.5, 0.5)
+    ///     .build()?;
+    /// let results = client.combined_search(&query).await?;
+    /// println!("Found {} sounds", results.results.len());
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn combined_search(
+        &self,
+        query: &[(String, String)],
+    ) -> Result<CombinedSearchResponse> {
+        self.get_cached(&format!("{}/search/combined", self.inner.base_url), query)
             .await
-            .map_err(FreesoundError::from)
     }
 
     /// Get detailed information about a specific sound
     ///
+    /// A deleted sound fails with [`SoundDeleted`](FreesoundError::SoundDeleted),
+    /// and one uploaded but not processed or moderated yet with
+    /// [`SoundProcessing`](FreesoundError::SoundProcessing).
+    /// [`get_sound_detail`](Self::get_sound_detail) and the download of the original
+    /// file fail the same way; other endpoints, such as similar sounds, and
+    /// previews keep their 404s as [`ApiError`](FreesoundError::ApiError)s.
+    ///
     /// # Arguments
     ///
     /// * `sound_id` - The unique identifier of the sound
-    /// * `descriptors` - Optional list of descriptors to include in the response
-    /// * `normalized` - Whether to normalize the descriptor values (only applies if descriptors are requested)
+    /// * `options` - Fields and descriptors to include in the response (see [`GetSoundOptions`](crate::GetSoundOptions))
     ///
     /// # Examples
     ///
     /// ```
     /// use std::env;
-    /// use freesound_rs::FreesoundClient;
+    /// use freesound_rs::{FreesoundClient, GetSoundOptions};
     /// #[tokio::main]
     /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
     ///     dotenvy::dotenv().ok();
     ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
     ///     let client = FreesoundClient::new(api_key, None);
     /// // Get basic sound information
-    /// let sound = client.get_sound(1234, None, None).await?;
+    /// let sound = client.get_sound(1234, &GetSoundOptions::new()).await?;
     /// println!("Sound name: {}", sound.name);
     ///
     /// // Get sound with specific descriptors
-    /// let sound = client
-    ///     .get_sound(
-    ///         1234,
-    ///         Some(&["lowlevel.mfcc", "rhythm.bpm"]),
-    ///         Some(true)
-    ///     )
-    ///     .await?;
+    /// let options = GetSoundOptions::new()
+    ///     .descriptors(["lowlevel.mfcc", "rhythm.bpm"])
+    ///     .normalized(true);
+    /// let sound = client.get_sound(1234, &options).await?;
     ///      Ok(())
     ///  }
     /// ```
-    pub async fn get_sound(
+    pub async fn get_sound(&self, sound_id: i32, options: &GetSoundOptions) -> Result<Sound> {
+        let (mut sound, meta): (Sound, _) = self
+            .send_json_meta(
+                self.request(reqwest::Method::GET, &format!("sounds/{}", sound_id))
+                    .query(&options.build()),
+            )
+            .await
+            .map_err(|e| e.for_sound(sound_id))?;
+        sound.meta = meta;
+        Ok(sound)
+    }
+
+    /// Get the full description of a sound, failing on incomplete responses
+    ///
+    /// Works like [`get_sound`](Self::get_sound), but returns a
+    /// [`SoundDetail`](crate::SoundDetail) whose fields are all required, so a
+    /// response missing one of them is reported as an error instead of being
+    /// filled with defaults. The `fields` option must not be restricted.
+    pub async fn get_sound_detail(
         &self,
         sound_id: i32,
-        descriptors: Option<&[&str]>,
-        normalized: Option<bool>,
-    ) -> Result<Sound> {
-        let mut request = self.request(reqwest::Method::GET, &format!("sounds/{}", sound_id));
-
-        if let Some(desc) = descriptors {
-            request = request.query(&[("descriptors", desc.join(","))]);
-        }
-
-        if let Some(norm) = normalized {
-            request = request.query(&[("normalized", if norm { "1" } else { "0" })]);
-        }
-
-        let response = request.send().await.map_err(FreesoundError::from)?;
+        options: &GetSoundOptions,
+    ) -> Result<SoundDetail> {
+        self.send_json(
+            self.request(reqwest::Method::GET, &format!("sounds/{}", sound_id))
+                .query(&options.build()),
+        )
+        .await
+        .map_err(|e| e.for_sound(sound_id))
+    }
 
-        let status = response.status();
-        if !status.is_success() {
-            let body = response.text().await.map_err(FreesoundError::from)?;
+    /// List the sounds most similar to a sound
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FreesoundClient, SoundListOptions};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let options = SoundListOptions::new().page_size(10).fields(["id", "name"]);
+    /// for sound in client.get_similar_sounds(1234, &options).await? {
+    ///     println!("{} (#{})", sound.name, sound.id);
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn get_similar_sounds(
+        &self,
+        sound_id: i32,
+        options: &SoundListOptions,
+    ) -> Result<SearchResponse> {
+        self.send_json(
+            self.request(
+                reqwest::Method::GET,
+                &format!("sounds/{}/similar", sound_id),
+            )
+            .query(&options.build()),
+        )
+        .await
+    }
+
+    /// List the comments of a sound, newest first
+    ///
+    /// The query parameters accepted are `page` and `page_size`.
+    pub async fn get_sound_comments(
+        &self,
+        sound_id: i32,
+        query: &[(String, String)],
+    ) -> Result<Page<Comment>> {
+        self.send_json(
+            self.request(
+                reqwest::Method::GET,
+                &format!("sounds/{}/comments", sound_id),
+            )
+            .query(query),
+        )
+        .await
+    }
+
+    /// Get detailed information about a specific pack
+    ///
+    /// # Arguments
+    ///
+    /// * `pack_id` - The unique identifier of the pack
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FreesoundClient, PackId};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let pack = client.get_pack(PackId(9678)).await?;
+    /// println!("Pack {} contains {} sounds", pack.name, pack.num_sounds);
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn get_pack(&self, pack_id: PackId) -> Result<Pack> {
+        self.send_json(self.request(reqwest::Method::GET, &format!("packs/{}", pack_id)))
+            .await
+    }
+
+    /// List the sounds of a pack
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FreesoundClient, PackId, SoundListOptions};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let options = SoundListOptions::new().page_size(50);
+    /// let sounds = client.get_pack_sounds(PackId(9678), &options).await?;
+    /// println!("Fetched {} of {} sounds", sounds.results.len(), sounds.count);
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn get_pack_sounds(
+        &self,
+        pack_id: PackId,
+        options: &SoundListOptions,
+    ) -> Result<SearchResponse> {
+        self.send_json(
+            self.request(reqwest::Method::GET, &format!("packs/{}/sounds", pack_id))
+                .query(&options.build()),
+        )
+        .await
+    }
+
+    /// Get information about a user
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::FreesoundClient;
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let user = client.get_user(&"Jovica".parse()?).await?;
+    /// println!("{} uploaded {} sounds", user.username, user.num_sounds);
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn get_user(&self, username: &Username) -> Result<User> {
+        self.send_json(self.request(
+            reqwest::Method::GET,
+            &format!("users/{}", username.path_segment()?),
+        ))
+        .await
+    }
+
+    /// List the sounds uploaded by a user
+    pub async fn get_user_sounds(
+        &self,
+        username: &Username,
+        options: &SoundListOptions,
+    ) -> Result<SearchResponse> {
+        self.send_json(
+            self.request(
+                reqwest::Method::GET,
+                &format!("users/{}/sounds", username.path_segment()?),
+            )
+            .query(&options.build()),
+        )
+        .await
+    }
+
+    /// Get the profile of the authenticated user
+    ///
+    /// Requires an OAuth2 access token (see [`with_access_token`](Self::with_access_token)).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::FreesoundClient;
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let access_token = env::var("FREESOUND_ACCESS_TOKEN")?;
+    ///     let client = FreesoundClient::new(api_key, None).with_access_token(access_token);
+    /// let me = client.get_me().await?;
+    /// println!("Logged in as {}", me.username);
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn get_me(&self) -> Result<User> {
+        self.require_oauth("getting the authenticated user")?;
+        self.send_json(self.request(reqwest::Method::GET, "me/"))
+            .await
+    }
+
+    /// List the bookmark categories of the authenticated user
+    ///
+    /// Requires an OAuth2 access token (see [`with_access_token`](Self::with_access_token)).
+    pub async fn get_bookmark_categories(&self) -> Result<Page<BookmarkCategory>> {
+        self.require_oauth("listing bookmark categories")?;
+        self.send_json(self.request(reqwest::Method::GET, "me/bookmark_categories"))
+            .await
+    }
+
+    /// List the sounds of a bookmark category of the authenticated user
+    ///
+    /// The query parameters accepted are the same as for [`search`](Self::search)
+    /// (page, page_size, fields, descriptors, normalized).
+    pub async fn get_bookmark_category_sounds(
+        &self,
+        category_id: i32,
+        query: &[(String, String)],
+    ) -> Result<SearchResponse> {
+        self.require_oauth("listing bookmarked sounds")?;
+        self.send_json(
+            self.request(
+                reqwest::Method::GET,
+                &format!("me/bookmark_categories/{}/sounds", category_id),
+            )
+            .query(query),
+        )
+        .await
+    }
+
+    /// Download the preview of a sound to a local file
+    ///
+    /// Previews do not require OAuth2 authentication, unlike original files.
+    /// The sound must have been fetched with its `previews` field.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FreesoundClient, PreviewQuality};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let sound = client.get_sound(1234, &Default::default()).await?;
+    /// let bytes = client
+    ///     .download_preview(&sound, PreviewQuality::HqOgg, "1234.ogg")
+    ///     .await?;
+    /// println!("Wrote {bytes} bytes");
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn download_preview(
+        &self,
+        sound: &Sound,
+        quality: PreviewQuality,
+        path: impl AsRef<Path>,
+    ) -> Result<u64> {
+        let previews = sound.previews.as_ref().ok_or_else(|| {
+            FreesoundError::ApiError(format!("Sound {} has no previews", sound.id))
+        })?;
+
+        let response = self.get_media(previews.url(quality)).await?;
+        self.write_body(response, path.as_ref(), |_| {}).await
+    }
+
+    /// Fetch the preview of a sound into memory
+    ///
+    /// The sound must have been fetched with its `previews` field. The preview
+    /// is read from the [disk cache](Self::with_disk_cache) if it holds it.
+    pub async fn fetch_preview(&self, sound: &Sound, quality: PreviewQuality) -> Result<Vec<u8>> {
+        let previews = sound.previews.as_ref().ok_or_else(|| {
+            FreesoundError::ApiError(format!("Sound {} has no previews", sound.id))
+        })?;
+
+        self.fetch_media(previews.url(quality)).await
+    }
+
+    /// Fetch the analysis frames of a sound, read into `T`
+    ///
+    /// The sound must have been fetched with its `analysis_frames` field. The
+    /// frames, which can weigh tens of megabytes, are parsed straight into `T`
+    /// without an intermediate JSON tree: a `T` holding only the descriptors
+    /// needed skips the others without allocating them. The body is subject to
+    /// the [maximum response size](Self::with_max_response_size).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{Field, FreesoundClient, GetSoundOptions};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Frames {
+    ///     lowlevel: Lowlevel,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Lowlevel {
+    ///     loudness: Vec<f32>,
+    /// }
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None).with_max_response_size(64 << 20);
+    /// let options = GetSoundOptions::new().fields([Field::Id, Field::AnalysisFrames]);
+    /// let sound = client.get_sound(1234, &options).await?;
+    /// let frames: Frames = client.get_analysis_frames(&sound).await?;
+    /// println!("{} loudness frames", frames.lowlevel.loudness.len());
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn get_analysis_frames<T: DeserializeOwned>(&self, sound: &Sound) -> Result<T> {
+        if sound.analysis_frames.is_empty() {
             return Err(FreesoundError::ApiError(format!(
-                "API request failed: {status} - {body}"
+                "Sound {} has no analysis frames",
+                sound.id
             )));
         }
+        let response = self.get_media(&sound.analysis_frames).await?;
+        let context = self.error_context(response.url());
+        let body = self.read_body(response).await?;
+        serde_json::from_slice(&body).map_err(|e| FreesoundError::from(e).with_context(context))
+    }
+
+    /// Fetches a media file into memory, through the disk cache if there is one
+    pub(crate) async fn fetch_media(&self, url: &str) -> Result<Vec<u8>> {
+        let cache = self.disk_cache();
+        if let Some(cache) = cache
+            && let Some(bytes) = cache.get(url).await
+        {
+            return Ok(bytes);
+        }
+        let response = self.get_media(url).await?;
+        let bytes = self.read_body(response).await?;
+        self.usage().record_bytes(bytes.len() as u64);
+        match cache {
+            Some(cache) => Ok(cache.insert(url, bytes).await),
+            None => Ok(bytes),
+        }
+    }
+
+    /// Download an image of a sound to a local file
+    ///
+    /// The sound must have been fetched with its `images` field.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written
+    pub async fn download_image(
+        &self,
+        sound: &Sound,
+        kind: ImageKind,
+        path: impl AsRef<Path>,
+    ) -> Result<u64> {
+        let images = sound
+            .images
+            .as_ref()
+            .ok_or_else(|| FreesoundError::ApiError(format!("Sound {} has no images", sound.id)))?;
+
+        let response = self.get_media(images.url(kind)).await?;
+        self.write_body(response, path.as_ref(), |_| {}).await
+    }
+
+    /// Download the avatar of a user to a local file
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{AvatarSize, FreesoundClient};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let user = client.get_user(&"Jovica".parse()?).await?;
+    /// client
+    ///     .download_avatar(&user, AvatarSize::Large, "Jovica.jpg")
+    ///     .await?;
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn download_avatar(
+        &self,
+        user: &User,
+        size: AvatarSize,
+        path: impl AsRef<Path>,
+    ) -> Result<u64> {
+        let url = user
+            .avatar
+            .as_ref()
+            .and_then(|avatar| avatar.url(size))
+            .ok_or_else(|| {
+                FreesoundError::ApiError(format!("User {} has no avatar", user.username))
+            })?;
+
+        let response = self.get_media(url).await?;
+        self.write_body(response, path.as_ref(), |_| {}).await
+    }
+
+    /// Download the original file of a sound
+    ///
+    /// This endpoint requires OAuth2 (see [`with_access_token`](Self::with_access_token)).
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes written
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::FreesoundClient;
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let access_token = env::var("FREESOUND_ACCESS_TOKEN")?;
+    ///     let client = FreesoundClient::new(api_key, None).with_access_token(access_token);
+    /// let sound = client.get_sound(1234, &Default::default()).await?;
+    /// client
+    ///     .download_original(1234, format!("1234.{}", sound.sound_type))
+    ///     .await?;
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn download_original(&self, sound_id: i32, path: impl AsRef<Path>) -> Result<u64> {
+        let response = self
+            .get_download(&self.original_download_url(sound_id))
+            .await
+            .map_err(|e| e.for_sound(sound_id))?;
+        self.write_body(response, path.as_ref(), |_| {}).await
+    }
+
+    /// Returns the API URL serving the original file of a sound
+    pub(crate) fn original_download_url(&self, sound_id: i32) -> String {
+        format!("{}/sounds/{}/download", self.inner.base_url, sound_id)
+    }
+
+    /// Fetches a file from the API, with credentials
+    ///
+    /// Fails with [`RequiresOAuth`](FreesoundError::RequiresOAuth) without an
+    /// access token; a refused authorization is reported as an
+    /// [`AuthError`](FreesoundError::AuthError).
+    pub(crate) async fn get_download(&self, url: &str) -> Result<reqwest::Response> {
+        self.get_download_range(url, None).await
+    }
+
+    /// Fetches a file, or a range of its bytes, from the API, with credentials
+    pub(crate) async fn get_download_range(
+        &self,
+        url: &str,
+        range: Option<Range<u64>>,
+    ) -> Result<reqwest::Response> {
+        self.require_oauth("downloading original files")?;
+        let policy = self.inner.api_retry;
+        let request = self.retried_request(self.authorize(self.inner.client.get(url)), policy);
+        let response = self
+            .send_with_retries(with_range(request, range), policy)
+            .await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err(
+                FreesoundError::AuthError(format!("Download refused: {status}"))
+                    .with_context(self.error_context(response.url())),
+            );
+        }
+        self.check_status(response).await
+    }
+
+    /// Fetches a media file (preview, image...) served outside of the API
+    pub(crate) async fn get_media(&self, url: &str) -> Result<reqwest::Response> {
+        self.get_media_range(url, None).await
+    }
+
+    /// Fetches a media file, or a range of its bytes, served outside of the API
+    pub(crate) async fn get_media_range(
+        &self,
+        url: &str,
+        range: Option<Range<u64>>,
+    ) -> Result<reqwest::Response> {
+        let policy = self.inner.media_retry;
+        let request = self.retried_request(self.inner.client.get(url), policy);
+        let response = self
+            .send_with_retries(with_range(request, range), policy)
+            .await?;
+        self.check_status(response).await
+    }
+
+    /// Streams a response body to a file, calling `on_chunk` with the size of each chunk written
+    pub(crate) async fn write_body(
+        &self,
+        response: reqwest::Response,
+        path: &Path,
+        on_chunk: impl FnMut(u64),
+    ) -> Result<u64> {
+        let (written, _) = self
+            .write_body_checked(response, path, None, on_chunk)
+            .await?;
+        Ok(written)
+    }
+
+    /// Streams a response body to a file, checking its MD5 digest if one is given
+    ///
+    /// Returns the number of bytes written and the MD5 digest of the file.
+    ///
+    /// The body is written to a `.part` file next to `path`, renamed to `path`
+    /// once complete and checked, so `path` never holds a partial file. The
+    /// `.part` file is removed if the download fails or the future is dropped.
+    /// File operations run on the thread pool of the `blocking` crate, so they
+    /// do not stall the executor, whatever the runtime.
+    pub(crate) async fn write_body_checked(
+        &self,
+        mut response: reqwest::Response,
+        path: &Path,
+        expected_md5: Option<&str>,
+        mut on_chunk: impl FnMut(u64),
+    ) -> Result<(u64, String)> {
+        let part = PartialFile {
+            path: part_path(path),
+            keep: false,
+        };
+        let mut file = open_file(part.path.clone(), |path| File::create(path)).await?;
+        let mut digest = md5::Context::new();
+        let mut written = 0u64;
+        while let Some(chunk) = response.chunk().await.map_err(FreesoundError::from)? {
+            file.write_all(&chunk).await?;
+            digest.consume(&chunk);
+            written += chunk.len() as u64;
+            self.usage().record_bytes(chunk.len() as u64);
+            on_chunk(chunk.len() as u64);
+        }
+        file.flush().await?;
+        file.with_mut(|file| file.sync_all()).await?;
+        drop(file);
+
+        let actual = format!("{:x}", digest.finalize());
+        if let Some(expected) = expected_md5
+            && !actual.eq_ignore_ascii_case(expected)
+        {
+            return Err(FreesoundError::ChecksumMismatch {
+                path: path.to_path_buf(),
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+        rename(&part.path, path).await?;
+        part.keep();
+
+        Ok((written, actual))
+    }
+
+    /// Downloads a file of `size` bytes as `chunks` ranges fetched at once
+    ///
+    /// The first range is read from `first`, the response of the whole file,
+    /// which is dropped once it was read; `fetch` requests the others. Each
+    /// range is written at its offset in a `.part` file, which is checked
+    /// against `expected_md5` once complete and renamed to `path`, as with
+    /// [`write_body_checked`](Self::write_body_checked). The ranges arrive out
+    /// of order, so the digest is computed from the file once it is complete,
+    /// on a thread of its own. Returns the size and the digest of the file.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn write_ranges<F, Fut>(
+        &self,
+        first: reqwest::Response,
+        fetch: F,
+        path: &Path,
+        size: u64,
+        chunks: usize,
+        expected_md5: Option<&str>,
+        on_chunk: impl Fn(u64),
+    ) -> Result<(u64, String)>
+    where
+        F: Fn(Range<u64>) -> Fut,
+        Fut: Future<Output = Result<reqwest::Response>>,
+    {
+        let part = PartialFile {
+            path: part_path(path),
+            keep: false,
+        };
+        let created = part.path.clone();
+        unblock(move || File::create(created)?.set_len(size)).await?;
+
+        let chunk_size = size.div_ceil(chunks.max(1) as u64).max(1);
+        let ranges = (0..size)
+            .step_by(chunk_size as usize)
+            .map(|start| start..(start + chunk_size).min(size));
+        let first = std::sync::Mutex::new(Some(first));
+        futures::future::try_join_all(ranges.map(|range| {
+            let (fetch, part, on_chunk) = (&fetch, &part, &on_chunk);
+            let first = (range.start == 0)
+                .then(|| first.lock().unwrap().take())
+                .flatten();
+            async move {
+                let mut response = match first {
+                    Some(response) => response,
+                    None => fetch(range.clone()).await?,
+                };
+                if range.start > 0 && response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+                    return Err(FreesoundError::ApiError(format!(
+                        "Range {}-{} of {} was not served",
+                        range.start,
+                        range.end - 1,
+                        path.display()
+                    )));
+                }
+                let mut file = open_file(part.path.clone(), |path| {
+                    OpenOptions::new().write(true).open(path)
+                })
+                .await?;
+                file.seek(SeekFrom::Start(range.start)).await?;
+                let mut remaining = range.end - range.start;
+                while remaining > 0
+                    && let Some(chunk) = response.chunk().await.map_err(FreesoundError::from)?
+                {
+                    let chunk = &chunk[..chunk.len().min(remaining as usize)];
+                    file.write_all(chunk).await?;
+                    remaining -= chunk.len() as u64;
+                    self.usage().record_bytes(chunk.len() as u64);
+                    on_chunk(chunk.len() as u64);
+                }
+                if remaining > 0 {
+                    return Err(FreesoundError::ApiError(format!(
+                        "Range {}-{} of {} ended early",
+                        range.start,
+                        range.end - 1,
+                        path.display()
+                    )));
+                }
+                file.flush().await?;
+                file.with_mut(|file| file.sync_all()).await?;
+                Ok(())
+            }
+        }))
+        .await?;
+
+        let complete = part.path.clone();
+        let actual = unblock(move || {
+            let mut digest = md5::Context::new();
+            let file = File::open(complete)?;
+            std::io::copy(&mut std::io::BufReader::new(file), &mut digest)?;
+            Ok::<_, std::io::Error>(format!("{:x}", digest.finalize()))
+        })
+        .await?;
+        if let Some(expected) = expected_md5
+            && !actual.eq_ignore_ascii_case(expected)
+        {
+            return Err(FreesoundError::ChecksumMismatch {
+                path: path.to_path_buf(),
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+        rename(&part.path, path).await?;
+        part.keep();
+
+        Ok((size, actual))
+    }
+
+    /// Sends a request, through the rate limiter and the circuit breaker if there are
+    pub(crate) async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let request = request.build().map_err(FreesoundError::from)?;
+        self.execute_request(request).await
+    }
+
+    /// Sends a built request, through the rate limiter and the circuit breaker if there are
+    pub(crate) async fn execute_request(
+        &self,
+        request: reqwest::Request,
+    ) -> Result<reqwest::Response> {
+        if let Some(limiter) = &self.inner.rate_limiter {
+            limiter.acquire(self.inner.priority).await;
+        }
+        if let Some(breaker) = &self.inner.circuit_breaker {
+            breaker.acquire()?;
+        }
+        let endpoint = format!(
+            "{} {}",
+            request.method(),
+            endpoint_name(&self.inner.base_url, request.url())
+        );
+
+        let (method, url) = (request.method().clone(), request.url().clone());
+        let (timestamp, started) = (unix_millis(), std::time::Instant::now());
 
-        response.json::<Sound>().await.map_err(FreesoundError::from)
+        let mut result = self.inner.client.execute(request).await;
+        if self.inner.response_meta
+            && let Ok(response) = &mut result
+        {
+            let meta = ResponseMeta::new(response, started.elapsed());
+            response.extensions_mut().insert(meta);
+        }
+        if let Some(journal) = &self.inner.journal {
+            journal.record(&JournalEntry {
+                timestamp,
+                method: method.to_string(),
+                endpoint: endpoint.split_once(' ').map_or("", |(_, e)| e).to_string(),
+                path: url.path().to_string(),
+                params: url
+                    .query_pairs()
+                    .filter(|(k, _)| k != "token")
+                    .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                    .collect(),
+                status: result.as_ref().ok().map(|r| r.status().as_u16()),
+                duration_ms: started.elapsed().as_millis() as u64,
+                // reqwest errors quote the URL, API key included
+                error: result.as_ref().err().map(|e| {
+                    let error = e.to_string();
+                    match self.inner.api_key.is_empty() {
+                        true => error,
+                        false => error.replace(&self.inner.api_key, crate::REDACTED),
+                    }
+                }),
+            });
+        }
+        self.usage()
+            .record_request(endpoint, result.as_ref().ok().map(|r| r.status()));
+        if let Some(breaker) = &self.inner.circuit_breaker {
+            match &result {
+                Ok(response) if response.status().is_server_error() => breaker.record_failure(),
+                Err(e) if e.is_timeout() || e.is_connect() => breaker.record_failure(),
+                _ => breaker.record_success(),
+            }
+        }
+        result.map_err(|e| FreesoundError::from(e).with_context(self.error_context(&url)))
+    }
+
+    /// Sends a request and deserializes its JSON response
+    pub(crate) async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T> {
+        self.send_json_meta(request).await.map(|(value, _)| value)
+    }
+
+    /// Sends a request and deserializes its JSON response, with its [`ResponseMeta`]
+    /// if the client keeps track
+    pub(crate) async fn send_json_meta<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<(T, Option<Box<ResponseMeta>>)> {
+        let mut response = self.send(request).await?;
+        let meta = response
+            .extensions_mut()
+            .remove::<ResponseMeta>()
+            .map(Box::new);
+        let value = self.read_json(self.check_status(response).await?).await?;
+        Ok((value, meta))
+    }
+
+    /// Reads a JSON response, marking its sounds as normalized if requested
+    ///
+    /// Sounds fetched with `normalized=1` get `analysis_normalized` set, so
+    /// their descriptors are not mistaken for raw values.
+    pub(crate) async fn read_json<T: DeserializeOwned>(
+        &self,
+        response: reqwest::Response,
+    ) -> Result<T> {
+        let url = response.url().clone();
+        let normalized = url
+            .query_pairs()
+            .any(|(k, v)| k == "normalized" && v == "1");
+        let body = self.read_body(response).await?;
+        let mut value: serde_json::Value = serde_json::from_slice(&body)
+            .map_err(|e| FreesoundError::from(e).with_context(self.error_context(&url)))?;
+        drop(body);
+        if !normalized {
+            return self.decode(url.as_str(), value);
+        }
+
+        let sounds = match value.get_mut("results") {
+            Some(serde_json::Value::Array(results)) => results.iter_mut().collect(),
+            _ => vec![&mut value],
+        };
+        for sound in sounds {
+            if let Some(sound) = sound.as_object_mut() {
+                sound.insert("analysis_normalized".to_string(), true.into());
+            }
+        }
+        self.decode(url.as_str(), value)
+    }
+
+    /// Reads a response body into memory, within the maximum response size
+    pub(crate) async fn read_body(&self, mut response: reqwest::Response) -> Result<Vec<u8>> {
+        let context = self.error_context(response.url());
+        let too_large =
+            |limit| FreesoundError::ResponseTooLarge { limit }.with_context(context.clone());
+        let limit = self.inner.max_response_bytes;
+        if let Some(limit) = limit
+            && response
+                .content_length()
+                .is_some_and(|length| length > limit)
+        {
+            return Err(too_large(limit));
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(FreesoundError::from)? {
+            if let Some(limit) = limit
+                && (body.len() + chunk.len()) as u64 > limit
+            {
+                return Err(too_large(limit));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    /// Turns an unsuccessful HTTP status into an API error
+    ///
+    /// A page refused because it is too deep is reported as a
+    /// [`PaginationLimit`](FreesoundError::PaginationLimit): that is a 400 or
+    /// 404 answer to a page above the first, whose body is exactly Freesound's
+    /// `{"detail": "Invalid page."}`, with or without the period. Other errors
+    /// on such pages are left as
+    /// [`ApiError`](FreesoundError::ApiError)s.
+    pub(crate) async fn check_status(
+        &self,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response> {
+        let status = response.status();
+        if !status.is_success() {
+            let context = self.error_context(response.url());
+            let page = response
+                .url()
+                .query_pairs()
+                .find(|(k, _)| k == "page")
+                .and_then(|(_, v)| v.parse::<i32>().ok());
+            let body = String::from_utf8_lossy(&self.read_body(response).await?).into_owned();
+            if let Some(page) = page
+                && page > 1
+                && (status == reqwest::StatusCode::BAD_REQUEST
+                    || status == reqwest::StatusCode::NOT_FOUND)
+                && is_page_cap(&body)
+            {
+                return Err(FreesoundError::PaginationLimit {
+                    max_reachable: page - 1,
+                }
+                .with_context(context));
+            }
+            return Err(
+                FreesoundError::ApiError(format!("API request failed: {status} - {body}"))
+                    .with_context(context),
+            );
+        }
+        Ok(response)
+    }
+}
+
+/// Returns true if an error body is the one Freesound sends for a page past the last
+fn is_page_cap(body: &str) -> bool {
+    let Ok(serde_json::Value::Object(body)) = serde_json::from_str(body) else {
+        return false;
+    };
+    body.len() == 1
+        && matches!(
+            body.get("detail").and_then(|detail| detail.as_str()),
+            Some("Invalid page." | "Invalid page")
+        )
+}
+
+/// Asks for a range of bytes only, if one is given
+fn with_range(
+    request: reqwest::RequestBuilder,
+    range: Option<Range<u64>>,
+) -> reqwest::RequestBuilder {
+    match range {
+        Some(range) => request.header(
+            reqwest::header::RANGE,
+            format!("bytes={}-{}", range.start, range.end - 1),
+        ),
+        None => request,
+    }
+}
+
+/// Returns the current time in milliseconds since the Unix epoch
+fn unix_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
+/// Returns the temporary path a download to `path` is written to
+fn part_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+/// Runs `open` on `path` off the executor, returning the file for async use
+async fn open_file(
+    path: PathBuf,
+    open: impl FnOnce(&Path) -> std::io::Result<File> + Send + 'static,
+) -> Result<Unblock<File>> {
+    Ok(Unblock::new(unblock(move || open(&path)).await?))
+}
+
+/// Renames a file off the executor
+async fn rename(from: &Path, to: &Path) -> Result<()> {
+    let (from, to) = (from.to_path_buf(), to.to_path_buf());
+    Ok(unblock(move || std::fs::rename(from, to)).await?)
+}
+
+/// A file being written, deleted when dropped unless kept
+struct PartialFile {
+    path: PathBuf,
+    keep: bool,
+}
+
+impl PartialFile {
+    fn keep(mut self) {
+        self.keep = true;
+    }
+}
+
+impl Drop for PartialFile {
+    fn drop(&mut self) {
+        if !self.keep {
+            let _ = std::fs::remove_file(&self.path);
+        }
     }
 }
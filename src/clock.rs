@@ -0,0 +1,111 @@
+use futures::future::{self, BoxFuture, FutureExt};
+use futures_timer::Delay;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Source of the current time
+///
+/// Used for the TTL of the [`SearchCache`](crate::SearchCache), the cooldown
+/// of the [`CircuitBreaker`](crate::CircuitBreaker) and the pacing of
+/// [batches](crate::batch::execute). Defaults to [`SystemClock`];
+/// [`MockClock`] lets tests move time forward by hand.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Returns the current instant
+    fn now(&self) -> Instant;
+}
+
+/// Waits for some time to pass
+///
+/// Used by the client between retries and polls (see
+/// [`FreesoundClient::with_sleeper`](crate::FreesoundClient::with_sleeper)).
+/// Defaults to [`SystemClock`]; [`MockClock`] returns at once.
+pub trait Sleeper: fmt::Debug + Send + Sync {
+    /// Returns a future completing after `duration`
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()>;
+}
+
+/// The real time, with timers that do not depend on an async runtime
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+impl Sleeper for SystemClock {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        Delay::new(duration).boxed()
+    }
+}
+
+/// A clock only moving when told to, for deterministic tests
+///
+/// Sleeping on it moves it forward by the requested duration and returns at
+/// once, so retry delays and polling intervals take no real time while still
+/// being visible in [`elapsed`](Self::elapsed).
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use freesound_rs::{Clock, FreesoundClient, MockClock, SearchCache, Sleeper};
+///
+/// let clock = Arc::new(MockClock::new());
+/// let client = FreesoundClient::new("API_KEY".to_string(), None)
+///     .with_search_cache(SearchCache::new(Duration::from_secs(300)).with_clock(clock.clone()))
+///     .with_sleeper(clock.clone());
+///
+/// let start = clock.now();
+/// futures::executor::block_on(clock.sleep(Duration::from_secs(5)));
+/// clock.advance(Duration::from_secs(55));
+/// assert_eq!(clock.now() - start, Duration::from_secs(60));
+/// assert_eq!(clock.elapsed(), Duration::from_secs(60));
+/// ```
+#[derive(Debug)]
+pub struct MockClock {
+    start: Instant,
+    elapsed: Mutex<Duration>,
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockClock {
+    /// Create a clock stopped at the current instant
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+        }
+    }
+
+    /// Move the clock forward
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+    }
+
+    /// Returns how far the clock was moved since its creation
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+}
+
+impl Sleeper for MockClock {
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        self.advance(duration);
+        future::ready(()).boxed()
+    }
+}
@@ -0,0 +1,85 @@
+//! Conversion of previews to formats other tools ingest
+//!
+//! Requires the `decode` feature.
+
+use crate::client::FreesoundClient;
+use crate::decode::Pcm;
+use crate::error::{FreesoundError, Result};
+use crate::models::{PreviewQuality, Sound};
+use std::path::Path;
+
+/// Writes decoded audio to a 16-bit PCM WAV file
+///
+/// Samples beyond full scale are clipped. An existing file is replaced.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::Pcm;
+/// use freesound_rs::convert::write_wav;
+///
+/// let pcm = Pcm {
+///     sample_rate: 44_100,
+///     channels: 2,
+///     samples: vec![0.0, 0.5, -0.5, 1.0],
+/// };
+/// let path = std::env::temp_dir().join("freesound-rs-write-wav.wav");
+/// write_wav(&pcm, &path)?;
+/// // A 44 bytes header, then 2 bytes per sample
+/// assert_eq!(std::fs::metadata(&path)?.len(), 44 + 2 * 4);
+/// # std::fs::remove_file(&path)?;
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+pub fn write_wav(pcm: &Pcm, dest: impl AsRef<Path>) -> Result<()> {
+    let spec = hound::WavSpec {
+        channels: pcm.channels,
+        sample_rate: pcm.sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(dest, spec).map_err(wav_error)?;
+    for &sample in &pcm.samples {
+        let sample = (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+        writer.write_sample(sample).map_err(wav_error)?;
+    }
+    writer.finalize().map_err(wav_error)
+}
+
+/// Downloads the preview of a sound and writes it as a 16-bit PCM WAV file
+///
+/// Many samplers and game engines only read WAV files. The preview keeps its
+/// sample rate and channels. The sound must have been fetched with its
+/// `previews` field.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::env;
+/// use freesound_rs::{FreesoundClient, PreviewQuality};
+/// use freesound_rs::convert::preview_to_wav;
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     dotenvy::dotenv().ok();
+///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+///     let client = FreesoundClient::new(api_key, None);
+/// let sound = client.get_sound(1234, &Default::default()).await?;
+/// preview_to_wav(&client, &sound, PreviewQuality::HqOgg, "1234.wav").await?;
+///      Ok(())
+///  }
+/// ```
+pub async fn preview_to_wav(
+    client: &FreesoundClient,
+    sound: &Sound,
+    quality: PreviewQuality,
+    dest: impl AsRef<Path>,
+) -> Result<()> {
+    let pcm = client.decode_preview(sound, quality).await?;
+    write_wav(&pcm, dest)
+}
+
+fn wav_error(e: hound::Error) -> FreesoundError {
+    match e {
+        hound::Error::IoError(e) => e.into(),
+        e => FreesoundError::DecodeError(e.to_string()),
+    }
+}
@@ -0,0 +1,133 @@
+use crate::client::FreesoundClient;
+use crate::error::{FreesoundError, Result};
+use crate::models::{PreviewQuality, Sound};
+use std::time::Duration;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_NULL, DecoderOptions};
+use symphonia::core::errors::Error;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Decoded audio, as interleaved samples between -1 and 1
+///
+/// Requires the `decode` feature, which can read the MP3 and OGG previews, and
+/// WAV, AIFF and FLAC originals.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use freesound_rs::Pcm;
+///
+/// let pcm = Pcm {
+///     sample_rate: 4,
+///     channels: 2,
+///     samples: vec![0.0, 0.5, 1.0, -1.0, 0.25, 0.25, 0.0, 0.0],
+/// };
+/// assert_eq!(pcm.frames(), 4);
+/// assert_eq!(pcm.duration(), Duration::from_secs(1));
+/// assert_eq!(pcm.mono().collect::<Vec<_>>(), [0.25, 0.0, 0.25, 0.0]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pcm {
+    /// Frames per second
+    pub sample_rate: u32,
+    /// Number of channels
+    pub channels: u16,
+    /// Samples of every channel, frame after frame
+    pub samples: Vec<f32>,
+}
+
+impl Pcm {
+    /// Decodes an MP3, OGG, WAV, AIFF or FLAC file held in memory
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let source = MediaSourceStream::new(
+            Box::new(std::io::Cursor::new(bytes.to_vec())),
+            Default::default(),
+        );
+        let probed = symphonia::default::get_probe()
+            .format(
+                &Hint::new(),
+                source,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )
+            .map_err(decode_error)?;
+        let mut format = probed.format;
+        let track = format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or_else(|| FreesoundError::DecodeError("no audio track".to_string()))?;
+        let track_id = track.id;
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .map_err(decode_error)?;
+
+        let mut pcm = Self::default();
+        loop {
+            let packet = match format.next_packet() {
+                Ok(packet) => packet,
+                Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(decode_error(e)),
+            };
+            if packet.track_id() != track_id {
+                continue;
+            }
+            let decoded = match decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // A corrupted packet is skipped, like players do
+                Err(Error::DecodeError(_)) => continue,
+                Err(e) => return Err(decode_error(e)),
+            };
+            let spec = *decoded.spec();
+            pcm.sample_rate = spec.rate;
+            pcm.channels = spec.channels.count() as u16;
+            let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+            buffer.copy_interleaved_ref(decoded);
+            pcm.samples.extend_from_slice(buffer.samples());
+        }
+        Ok(pcm)
+    }
+
+    /// Returns the number of frames, i.e. of samples per channel
+    pub fn frames(&self) -> usize {
+        match self.channels {
+            0 => 0,
+            channels => self.samples.len() / channels as usize,
+        }
+    }
+
+    /// Returns the duration of the audio
+    pub fn duration(&self) -> Duration {
+        match self.sample_rate {
+            0 => Duration::ZERO,
+            rate => Duration::from_secs_f64(self.frames() as f64 / rate as f64),
+        }
+    }
+
+    /// Returns the frames mixed down to one channel
+    pub fn mono(&self) -> impl Iterator<Item = f32> + '_ {
+        let channels = self.channels.max(1) as usize;
+        self.samples
+            .chunks_exact(channels)
+            .map(move |frame| frame.iter().sum::<f32>() / channels as f32)
+    }
+}
+
+impl FreesoundClient {
+    /// Fetch and decode the preview of a sound
+    ///
+    /// Requires the `decode` feature. The sound must have been fetched with its
+    /// `previews` field.
+    pub async fn decode_preview(&self, sound: &Sound, quality: PreviewQuality) -> Result<Pcm> {
+        let bytes = self.fetch_preview(sound, quality).await?;
+        Pcm::decode(&bytes)
+    }
+}
+
+fn decode_error(e: Error) -> FreesoundError {
+    FreesoundError::DecodeError(e.to_string())
+}
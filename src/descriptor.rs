@@ -0,0 +1,342 @@
+//! Names of the Freesound audio descriptors
+//!
+//! Typed names avoid typos that the API silently ignores, such as a trailing
+//! space in `"lowlevel.mfcc "`. They convert to `String`, so they can be given
+//! to the `descriptors` and `fields` parameters of the query builders, and to
+//! [`Sound::descriptor`](crate::Sound::descriptor) and [`Target`](crate::Target).
+//!
+//! The catalog follows the descriptors of the Freesound extractor listed in the
+//! [API documentation](https://freesound.org/docs/api/analysis_docs.html).
+//!
+//! # Examples
+//!
+//! ```
+//! use freesound_rs::SearchQueryBuilder;
+//! use freesound_rs::descriptor::{Lowlevel, Name, Rhythm, Stat};
+//!
+//! let query = SearchQueryBuilder::new()
+//!     .query("kick")
+//!     .descriptors([Lowlevel::Mfcc, Lowlevel::SpectralCentroid])
+//!     .build();
+//! assert_eq!(query[1].1, "lowlevel.mfcc,lowlevel.spectral_centroid");
+//!
+//! assert_eq!(Rhythm::Bpm.as_str(), "rhythm.bpm");
+//! assert_eq!(Lowlevel::Pitch.stat(Stat::Mean), "lowlevel.pitch.mean");
+//! assert_eq!("tonal.key_key".parse(), Ok(Name::from(freesound_rs::descriptor::Tonal::KeyKey)));
+//! assert!("lowlevel.mfcc ".parse::<Name>().is_err());
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A descriptor value in the normalized space
+///
+/// Freesound scales descriptor values to `[0, 1]` when `normalized` is
+/// requested. Wrapping them keeps such values from being mixed with raw ones
+/// (see [`Sound::normalized_descriptor`](crate::Sound::normalized_descriptor)).
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Normalized<T>(pub T);
+
+impl<T> Normalized<T> {
+    /// Returns the wrapped value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+
+    /// Transforms the wrapped value, keeping it marked as normalized
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Normalized<U> {
+        Normalized(f(self.0))
+    }
+}
+
+/// A statistic computed over the frames of a descriptor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stat {
+    /// Mean
+    Mean,
+    /// Variance
+    Var,
+    /// Minimum
+    Min,
+    /// Maximum
+    Max,
+    /// Mean of the derivative
+    Dmean,
+    /// Variance of the derivative
+    Dvar,
+    /// Mean of the second derivative
+    Dmean2,
+    /// Variance of the second derivative
+    Dvar2,
+}
+
+impl Stat {
+    /// Returns the name of the statistic in analysis data
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Mean => "mean",
+            Self::Var => "var",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Dmean => "dmean",
+            Self::Dvar => "dvar",
+            Self::Dmean2 => "dmean2",
+            Self::Dvar2 => "dvar2",
+        }
+    }
+}
+
+/// A descriptor name, from any namespace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Name {
+    /// Low-level spectral and temporal descriptors
+    Lowlevel(Lowlevel),
+    /// Rhythm descriptors
+    Rhythm(Rhythm),
+    /// Tonal descriptors
+    Tonal(Tonal),
+    /// Descriptors of sound effects
+    Sfx(Sfx),
+}
+
+impl Name {
+    /// Returns the dotted name, such as `"rhythm.bpm"`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Lowlevel(name) => name.as_str(),
+            Self::Rhythm(name) => name.as_str(),
+            Self::Tonal(name) => name.as_str(),
+            Self::Sfx(name) => name.as_str(),
+        }
+    }
+
+    /// Returns the name of a statistic of the descriptor, such as `"lowlevel.pitch.mean"`
+    pub fn stat(&self, stat: Stat) -> String {
+        format!("{}.{}", self.as_str(), stat.as_str())
+    }
+
+    /// Returns every known descriptor
+    pub fn all() -> impl Iterator<Item = Name> {
+        Lowlevel::ALL
+            .iter()
+            .map(|&name| Self::from(name))
+            .chain(Rhythm::ALL.iter().map(|&name| Self::from(name)))
+            .chain(Tonal::ALL.iter().map(|&name| Self::from(name)))
+            .chain(Sfx::ALL.iter().map(|&name| Self::from(name)))
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Name {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .find(|name| name.as_str() == s)
+            .ok_or_else(|| format!("unknown descriptor: {s}"))
+    }
+}
+
+impl AsRef<str> for Name {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<Name> for String {
+    fn from(name: Name) -> Self {
+        name.as_str().to_string()
+    }
+}
+
+macro_rules! namespace {
+    ($(#[$doc:meta])* $namespace:ident, $prefix:literal { $($variant:ident => $name:literal,)* }) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum $namespace {
+            $(
+                #[doc = concat!("`", $prefix, ".", $name, "`")]
+                $variant,
+            )*
+        }
+
+        impl $namespace {
+            /// Every descriptor of the namespace
+            pub const ALL: &'static [Self] = &[$(Self::$variant,)*];
+
+            /// Returns the dotted name of the descriptor
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => concat!($prefix, ".", $name),)*
+                }
+            }
+
+            /// Returns the name of a statistic of the descriptor
+            pub fn stat(&self, stat: Stat) -> String {
+                Name::from(*self).stat(stat)
+            }
+        }
+
+        impl From<$namespace> for Name {
+            fn from(name: $namespace) -> Self {
+                Self::$namespace(name)
+            }
+        }
+
+        impl fmt::Display for $namespace {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl AsRef<str> for $namespace {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl From<$namespace> for String {
+            fn from(name: $namespace) -> Self {
+                name.as_str().to_string()
+            }
+        }
+    };
+}
+
+namespace! {
+    /// Low-level descriptors (`lowlevel.*`)
+    Lowlevel, "lowlevel" {
+        AverageLoudness => "average_loudness",
+        Barkbands => "barkbands",
+        BarkbandsCrest => "barkbands_crest",
+        BarkbandsFlatnessDb => "barkbands_flatness_db",
+        BarkbandsKurtosis => "barkbands_kurtosis",
+        BarkbandsSkewness => "barkbands_skewness",
+        BarkbandsSpread => "barkbands_spread",
+        Dissonance => "dissonance",
+        DynamicComplexity => "dynamic_complexity",
+        Erbbands => "erbbands",
+        ErbbandsCrest => "erbbands_crest",
+        ErbbandsFlatnessDb => "erbbands_flatness_db",
+        ErbbandsKurtosis => "erbbands_kurtosis",
+        ErbbandsSkewness => "erbbands_skewness",
+        ErbbandsSpread => "erbbands_spread",
+        Gfcc => "gfcc",
+        Hfc => "hfc",
+        Melbands => "melbands",
+        MelbandsCrest => "melbands_crest",
+        MelbandsFlatnessDb => "melbands_flatness_db",
+        MelbandsKurtosis => "melbands_kurtosis",
+        MelbandsSkewness => "melbands_skewness",
+        MelbandsSpread => "melbands_spread",
+        Mfcc => "mfcc",
+        Pitch => "pitch",
+        PitchInstantaneousConfidence => "pitch_instantaneous_confidence",
+        PitchMax => "pitch_max",
+        PitchMin => "pitch_min",
+        PitchSalience => "pitch_salience",
+        SilenceRate20Db => "silence_rate_20dB",
+        SilenceRate30Db => "silence_rate_30dB",
+        SilenceRate60Db => "silence_rate_60dB",
+        SpectralCentroid => "spectral_centroid",
+        SpectralComplexity => "spectral_complexity",
+        SpectralContrast => "spectral_contrast",
+        SpectralDecrease => "spectral_decrease",
+        SpectralEnergy => "spectral_energy",
+        SpectralEnergybandHigh => "spectral_energyband_high",
+        SpectralEnergybandLow => "spectral_energyband_low",
+        SpectralEnergybandMiddleHigh => "spectral_energyband_middle_high",
+        SpectralEnergybandMiddleLow => "spectral_energyband_middle_low",
+        SpectralEntropy => "spectral_entropy",
+        SpectralFlux => "spectral_flux",
+        SpectralKurtosis => "spectral_kurtosis",
+        SpectralRms => "spectral_rms",
+        SpectralRolloff => "spectral_rolloff",
+        SpectralSkewness => "spectral_skewness",
+        SpectralSpread => "spectral_spread",
+        SpectralStrongpeak => "spectral_strongpeak",
+        SpectralValleys => "spectral_valleys",
+        StartFrame => "startFrame",
+        StopFrame => "stopFrame",
+        Zerocrossingrate => "zerocrossingrate",
+    }
+}
+
+namespace! {
+    /// Rhythm descriptors (`rhythm.*`)
+    Rhythm, "rhythm" {
+        BeatsCount => "beats_count",
+        BeatsLoudness => "beats_loudness",
+        BeatsLoudnessBandRatio => "beats_loudness_band_ratio",
+        BeatsPosition => "beats_position",
+        Bpm => "bpm",
+        BpmHistogramFirstPeakBpm => "bpm_histogram_first_peak_bpm",
+        BpmHistogramFirstPeakSpread => "bpm_histogram_first_peak_spread",
+        BpmHistogramFirstPeakWeight => "bpm_histogram_first_peak_weight",
+        BpmHistogramSecondPeakBpm => "bpm_histogram_second_peak_bpm",
+        BpmHistogramSecondPeakSpread => "bpm_histogram_second_peak_spread",
+        BpmHistogramSecondPeakWeight => "bpm_histogram_second_peak_weight",
+        BpmIntervals => "bpm_intervals",
+        Danceability => "danceability",
+        OnsetCount => "onset_count",
+        OnsetRate => "onset_rate",
+        OnsetTimes => "onset_times",
+    }
+}
+
+namespace! {
+    /// Tonal descriptors (`tonal.*`)
+    Tonal, "tonal" {
+        ChordsChangesRate => "chords_changes_rate",
+        ChordsHistogram => "chords_histogram",
+        ChordsKey => "chords_key",
+        ChordsNumberRate => "chords_number_rate",
+        ChordsProgression => "chords_progression",
+        ChordsScale => "chords_scale",
+        ChordsStrength => "chords_strength",
+        Hpcp => "hpcp",
+        HpcpCrest => "hpcp_crest",
+        HpcpEntropy => "hpcp_entropy",
+        HpcpPeakCount => "hpcp_peak_count",
+        KeyKey => "key_key",
+        KeyScale => "key_scale",
+        KeyStrength => "key_strength",
+        Thpcp => "thpcp",
+        TuningDiatonicStrength => "tuning_diatonic_strength",
+        TuningEqualTemperedDeviation => "tuning_equal_tempered_deviation",
+        TuningFrequency => "tuning_frequency",
+        TuningNontemperedEnergyRatio => "tuning_nontempered_energy_ratio",
+    }
+}
+
+namespace! {
+    /// Sound effect descriptors (`sfx.*`)
+    Sfx, "sfx" {
+        DerAvAfterMax => "der_av_after_max",
+        Duration => "duration",
+        EffectiveDuration => "effective_duration",
+        Flatness => "flatness",
+        Inharmonicity => "inharmonicity",
+        Logattacktime => "logattacktime",
+        MaxDerBeforeMax => "max_der_before_max",
+        MaxToTotal => "max_to_total",
+        MinToTotal => "min_to_total",
+        Oddtoevenharmonicenergyratio => "oddtoevenharmonicenergyratio",
+        PitchAfterMaxToBeforeMaxEnergyRatio => "pitch_after_max_to_before_max_energy_ratio",
+        PitchCentroid => "pitch_centroid",
+        PitchMaxToTotal => "pitch_max_to_total",
+        PitchMinToTotal => "pitch_min_to_total",
+        Strongdecay => "strongdecay",
+        TemporalCentroid => "temporal_centroid",
+        TemporalDecrease => "temporal_decrease",
+        TemporalKurtosis => "temporal_kurtosis",
+        TemporalSkewness => "temporal_skewness",
+        TemporalSpread => "temporal_spread",
+        Tristimulus => "tristimulus",
+    }
+}
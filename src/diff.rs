@@ -0,0 +1,72 @@
+//! Comparing result sets
+//!
+//! [`compare`] matches two sets of sounds, such as two runs of the same
+//! search, and reports the sounds added, removed and whose file changed in a
+//! [`ResultDiff`], for monitoring tools reporting what changed between runs.
+
+use crate::models::Sound;
+use std::collections::HashMap;
+
+/// Differences between two sets of results, returned by [`compare`]
+#[derive(Debug, Default)]
+pub struct ResultDiff<'a> {
+    /// Sounds only in the new results
+    pub added: Vec<&'a Sound>,
+    /// Sounds only in the old results
+    pub removed: Vec<&'a Sound>,
+    /// Sounds in both results whose file changed, as `(old, new)`
+    pub changed: Vec<(&'a Sound, &'a Sound)>,
+}
+
+impl ResultDiff<'_> {
+    /// Returns true if both result sets hold the same sounds and files
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Compares two sets of results, such as two runs of the same search
+///
+/// Sounds are matched by id. A sound found in both sets is changed when its
+/// `md5` differs; sounds fetched without the `md5` field are never reported as
+/// changed. Added and changed sounds are listed in the order of `new`, removed
+/// sounds in the order of `old`.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{Sound, diff};
+///
+/// let sound = |id, md5: &str| Sound { id, md5: md5.to_string(), ..Default::default() };
+/// let yesterday = [sound(1, "aa"), sound(2, "bb"), sound(3, "cc")];
+/// let today = [sound(2, "bb"), sound(3, "dd"), sound(4, "ee")];
+///
+/// let changes = diff::compare(&yesterday, &today);
+/// assert_eq!(changes.added.iter().map(|s| s.id).collect::<Vec<_>>(), [4]);
+/// assert_eq!(changes.removed.iter().map(|s| s.id).collect::<Vec<_>>(), [1]);
+/// assert_eq!(changes.changed.iter().map(|(_, s)| s.id).collect::<Vec<_>>(), [3]);
+/// ```
+pub fn compare<'a>(old: &'a [Sound], new: &'a [Sound]) -> ResultDiff<'a> {
+    let old_by_id: HashMap<i32, &Sound> = old.iter().map(|s| (s.id, s)).collect();
+    let new_by_id: HashMap<i32, &Sound> = new.iter().map(|s| (s.id, s)).collect();
+
+    let mut diff = ResultDiff::default();
+    for sound in new {
+        match old_by_id.get(&sound.id) {
+            None => diff.added.push(sound),
+            Some(previous)
+                if !previous.md5.is_empty()
+                    && !sound.md5.is_empty()
+                    && previous.md5 != sound.md5 =>
+            {
+                diff.changed.push((previous, sound))
+            }
+            Some(_) => {}
+        }
+    }
+    diff.removed = old
+        .iter()
+        .filter(|s| !new_by_id.contains_key(&s.id))
+        .collect();
+    diff
+}
@@ -0,0 +1,172 @@
+use super::{DownloadJob, ProgressSink, TransferStats};
+use crate::error::Result;
+use futures::channel::mpsc::{UnboundedReceiver, unbounded};
+use std::fmt;
+use std::path::PathBuf;
+
+/// What happened to a job of the [`DownloadManager`](super::DownloadManager)
+#[derive(Debug, Clone, PartialEq)]
+pub enum DownloadEvent {
+    /// The job was added to the queue
+    JobQueued {
+        /// The job, as queued
+        job: DownloadJob,
+    },
+    /// The server answered and the transfer begins
+    JobStarted {
+        /// The job, with the path the file is written to
+        job: DownloadJob,
+        /// The size announced by the server, if any
+        total_bytes: Option<u64>,
+    },
+    /// A chunk of the file of a sound was written to disk
+    ChunkWritten {
+        /// The sound the file belongs to
+        sound_id: i32,
+        /// The size of the chunk
+        bytes: u64,
+    },
+    /// The progress of a transfer, updated after each chunk
+    Progress {
+        /// The sound the file belongs to
+        sound_id: i32,
+        /// The progress of the transfer, last chunk included
+        stats: TransferStats,
+    },
+    /// The job failed, or its outcome could not be recorded
+    JobFailed {
+        /// The job, as last attempted
+        job: DownloadJob,
+        /// The message of the error that stopped the job
+        error: String,
+    },
+    /// The file is complete under its final name, or was already there
+    JobCompleted {
+        /// The job, with its final destination
+        job: DownloadJob,
+        /// The final path of the file
+        path: PathBuf,
+        /// The MD5 digest of the file
+        ///
+        /// A file already there outside of a [`ContentStore`](crate::ContentStore)
+        /// is not read again: its digest is the one expected by the job, if any.
+        md5: Option<String>,
+        /// The size of the file
+        bytes: u64,
+    },
+}
+
+impl DownloadEvent {
+    /// Returns the sound of the job the event is about
+    pub fn sound_id(&self) -> i32 {
+        match self {
+            Self::JobQueued { job }
+            | Self::JobStarted { job, .. }
+            | Self::JobFailed { job, .. }
+            | Self::JobCompleted { job, .. } => job.sound_id,
+            Self::ChunkWritten { sound_id, .. } | Self::Progress { sound_id, .. } => *sound_id,
+        }
+    }
+}
+
+/// A [`ProgressSink`] turning the notifications into [`DownloadEvent`]s
+///
+/// The events go to a callback, or to a channel, for interfaces that keep one
+/// state per job rather than parsing log lines. The callback is called on the
+/// tasks running the downloads, so it should return quickly.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{DownloadEvent, DownloadJob, DownloadSource, EventSink, ProgressSink};
+///
+/// let (sink, mut events) = EventSink::channel();
+/// let job = DownloadJob {
+///     sound_id: 1234,
+///     url: "https://cdn.freesound.org/previews/1234-hq.mp3".to_string(),
+///     destination: "1234.mp3".into(),
+///     source: DownloadSource::default(),
+///     preview_url: None,
+///     md5: None,
+/// };
+/// sink.job_queued(&job);
+/// sink.job_finished(&job, &Ok(5000));
+///
+/// assert_eq!(events.try_next()?, Some(DownloadEvent::JobQueued { job: job.clone() }));
+/// assert!(matches!(
+///     events.try_next()?,
+///     Some(DownloadEvent::JobCompleted { bytes: 5000, .. })
+/// ));
+/// # Ok::<(), futures::channel::mpsc::TryRecvError>(())
+/// ```
+pub struct EventSink {
+    emit: Box<dyn Fn(DownloadEvent) + Send + Sync>,
+}
+
+impl EventSink {
+    /// Creates a sink passing each event to `callback`
+    pub fn new(callback: impl Fn(DownloadEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            emit: Box::new(callback),
+        }
+    }
+
+    /// Creates a sink sending the events to the returned receiver
+    ///
+    /// Events sent after the receiver is dropped are discarded.
+    pub fn channel() -> (Self, UnboundedReceiver<DownloadEvent>) {
+        let (sender, receiver) = unbounded();
+        let sink = Self::new(move |event| {
+            let _ = sender.unbounded_send(event);
+        });
+        (sink, receiver)
+    }
+}
+
+impl fmt::Debug for EventSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventSink").finish_non_exhaustive()
+    }
+}
+
+impl ProgressSink for EventSink {
+    fn job_queued(&self, job: &DownloadJob) {
+        (self.emit)(DownloadEvent::JobQueued { job: job.clone() });
+    }
+
+    fn job_started(&self, job: &DownloadJob, total_bytes: Option<u64>) {
+        (self.emit)(DownloadEvent::JobStarted {
+            job: job.clone(),
+            total_bytes,
+        });
+    }
+
+    fn bytes_advanced(&self, job: &DownloadJob, bytes: u64) {
+        (self.emit)(DownloadEvent::ChunkWritten {
+            sound_id: job.sound_id,
+            bytes,
+        });
+    }
+
+    fn stats_updated(&self, job: &DownloadJob, stats: &TransferStats) {
+        (self.emit)(DownloadEvent::Progress {
+            sound_id: job.sound_id,
+            stats: *stats,
+        });
+    }
+
+    fn job_finished(&self, job: &DownloadJob, result: &Result<u64>) {
+        (self.emit)(match result {
+            Ok(bytes) => DownloadEvent::JobCompleted {
+                job: job.clone(),
+                path: job.destination.clone(),
+                md5: job.md5.clone(),
+                bytes: *bytes,
+            },
+            Err(e) => DownloadEvent::JobFailed {
+                job: job.clone(),
+                error: e.to_string(),
+            },
+        });
+    }
+}
@@ -0,0 +1,99 @@
+use super::{DownloadJob, ProgressSink};
+use crate::error::Result;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// A [`ProgressSink`] drawing one [indicatif] progress bar per running job
+///
+/// Bars are told apart by the destination of their job, so jobs fetching the
+/// same URL to different files each get their own.
+///
+/// # Examples
+///
+/// ```no_run
+/// use freesound_rs::{DownloadManager, FreesoundClient, IndicatifProgress};
+///
+/// let client = FreesoundClient::new("YOUR_API_KEY".to_string(), None);
+/// let manager = DownloadManager::new(client).progress(IndicatifProgress::new());
+/// ```
+pub struct IndicatifProgress {
+    multi: MultiProgress,
+    style: ProgressStyle,
+    /// Running bars by destination, with the URL they fetch
+    bars: Mutex<HashMap<PathBuf, (String, ProgressBar)>>,
+}
+
+impl IndicatifProgress {
+    /// Creates a sink drawing to a new `MultiProgress`
+    pub fn new() -> Self {
+        Self::with_multi_progress(MultiProgress::new())
+    }
+
+    /// Creates a sink adding its bars to an existing `MultiProgress`
+    pub fn with_multi_progress(multi: MultiProgress) -> Self {
+        let style = ProgressStyle::with_template(
+            "{msg:30!} [{bar:30}] {bytes:>10}/{total_bytes:10} {bytes_per_sec:>12}",
+        )
+        .expect("valid progress template")
+        .progress_chars("=> ");
+
+        Self {
+            multi,
+            style,
+            bars: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the underlying `MultiProgress`
+    pub fn multi_progress(&self) -> &MultiProgress {
+        &self.multi
+    }
+}
+
+impl Default for IndicatifProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProgressSink for IndicatifProgress {
+    fn job_started(&self, job: &DownloadJob, total_bytes: Option<u64>) {
+        let bar = self
+            .multi
+            .add(ProgressBar::new(total_bytes.unwrap_or(0)))
+            .with_style(self.style.clone())
+            .with_message(job.destination.display().to_string());
+        self.bars
+            .lock()
+            .unwrap()
+            .insert(job.destination.clone(), (job.url.clone(), bar));
+    }
+
+    fn bytes_advanced(&self, job: &DownloadJob, bytes: u64) {
+        if let Some((_, bar)) = self.bars.lock().unwrap().get(&job.destination) {
+            bar.inc(bytes);
+        }
+    }
+
+    fn job_finished(&self, job: &DownloadJob, result: &Result<u64>) {
+        let mut bars = self.bars.lock().unwrap();
+        // Jobs of a content store are written to a temporary file and finish
+        // under the path of their object, which holds the same content for
+        // every job of the URL
+        let key = match bars.contains_key(&job.destination) {
+            true => Some(job.destination.clone()),
+            false => bars
+                .iter()
+                .find(|(_, (url, _))| *url == job.url)
+                .map(|(path, _)| path.clone()),
+        };
+        if let Some((_, bar)) = key.and_then(|key| bars.remove(&key)) {
+            match result {
+                Ok(_) => bar.finish(),
+                Err(e) => bar.abandon_with_message(format!("{}: {e}", job.destination.display())),
+            }
+        }
+    }
+}
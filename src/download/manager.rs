@@ -0,0 +1,976 @@
+use super::{
+    ContentStore, DownloadItem, DownloadReport, DownloadStatus, JobStatus, Manifest, ManifestEntry,
+    NoProgress, OverwritePolicy, ProgressSink, ShutdownHandle, StoreEntry, TransferRate,
+    check_free_space,
+};
+use crate::client::FreesoundClient;
+#[cfg(feature = "decode")]
+use crate::decode::Pcm;
+use crate::error::{FreesoundError, Result};
+use crate::limiter::Priority;
+use crate::loudness::Loudness;
+#[cfg(feature = "decode")]
+use crate::loudness::LoudnessCheck;
+use crate::models::{PackId, PreviewQuality, Sound, SoundListOptions};
+use blocking::unblock;
+use futures::future::{AbortHandle, Abortable, Aborted, FutureExt};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::task::Poll;
+use std::time::Instant;
+
+/// Default number of downloads running at the same time
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Which file of a sound to download
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadSource {
+    /// The original file, which requires OAuth2
+    Original,
+    /// A preview, which only requires an API key
+    Preview(PreviewQuality),
+}
+
+impl Default for DownloadSource {
+    fn default() -> Self {
+        Self::Preview(PreviewQuality::default())
+    }
+}
+
+/// A file to download
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DownloadJob {
+    /// The identifier of the sound being downloaded
+    pub sound_id: i32,
+    /// The URL of the file
+    pub url: String,
+    /// Where the file is written
+    pub destination: PathBuf,
+    /// Which file of the sound is downloaded
+    #[serde(default)]
+    pub source: DownloadSource,
+    /// The URL of the HQ MP3 preview, used if an original cannot be downloaded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview_url: Option<String>,
+    /// The expected MD5 digest of the file, checked before it is kept
+    ///
+    /// The jobs reported once downloaded hold the digest of the file written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub md5: Option<String>,
+}
+
+impl DownloadJob {
+    /// Creates a job downloading the preview of a sound into `dir`
+    ///
+    /// The file is named after the sound id and the preview format, e.g. `1234.mp3`.
+    /// The sound must have been fetched with its `previews` field.
+    pub fn preview(sound: &Sound, quality: PreviewQuality, dir: impl AsRef<Path>) -> Result<Self> {
+        let previews = sound.previews.as_ref().ok_or_else(|| {
+            FreesoundError::ApiError(format!("Sound {} has no previews", sound.id))
+        })?;
+
+        Ok(Self {
+            sound_id: sound.id,
+            url: previews.url(quality).to_string(),
+            destination: dir
+                .as_ref()
+                .join(format!("{}.{}", sound.id, quality.extension())),
+            source: DownloadSource::Preview(quality),
+            preview_url: None,
+            md5: None,
+        })
+    }
+
+    /// Creates a job downloading the original file of a sound into `dir`
+    ///
+    /// The file is named after the sound id and its type, e.g. `1234.wav`. If the
+    /// sound was fetched with its `previews` field, the job can fall back to the
+    /// HQ MP3 preview (see [`DownloadManager::preview_fallback`]). If it was
+    /// fetched with its `md5` field, the file is checked against it.
+    pub fn original(client: &FreesoundClient, sound: &Sound, dir: impl AsRef<Path>) -> Self {
+        let file_name = if sound.sound_type.is_empty() {
+            sound.id.to_string()
+        } else {
+            format!("{}.{}", sound.id, sound.sound_type)
+        };
+
+        Self {
+            sound_id: sound.id,
+            url: client.original_download_url(sound.id),
+            destination: dir.as_ref().join(file_name),
+            source: DownloadSource::Original,
+            preview_url: sound
+                .previews
+                .as_ref()
+                .map(|p| p.url(PreviewQuality::HqMp3).to_string()),
+            md5: (!sound.md5.is_empty()).then(|| sound.md5.clone()),
+        }
+    }
+
+    /// Creates a job downloading a sound from the given source into `dir`
+    pub fn new(
+        client: &FreesoundClient,
+        sound: &Sound,
+        source: DownloadSource,
+        dir: impl AsRef<Path>,
+    ) -> Result<Self> {
+        match source {
+            DownloadSource::Original => Ok(Self::original(client, sound, dir)),
+            DownloadSource::Preview(quality) => Self::preview(sound, quality, dir),
+        }
+    }
+
+    /// Returns the job downloading the HQ MP3 preview instead of the original
+    ///
+    /// The file is written next to the original one, with the `mp3` extension.
+    /// Returns `None` for preview jobs and for originals without a preview URL.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::{DownloadJob, DownloadSource};
+    /// use std::path::Path;
+    ///
+    /// let job = DownloadJob {
+    ///     sound_id: 1234,
+    ///     url: "https://freesound.org/apiv2/sounds/1234/download".to_string(),
+    ///     destination: "sounds/1234.wav".into(),
+    ///     source: DownloadSource::Original,
+    ///     preview_url: Some("https://cdn.freesound.org/previews/1234-hq.mp3".to_string()),
+    ///     md5: None,
+    /// };
+    /// let fallback = job.fallback().unwrap();
+    /// assert_eq!(fallback.destination, Path::new("sounds/1234.mp3"));
+    /// assert!(fallback.fallback().is_none());
+    /// ```
+    pub fn fallback(&self) -> Option<DownloadJob> {
+        let url = match self.source {
+            DownloadSource::Original => self.preview_url.clone()?,
+            DownloadSource::Preview(_) => return None,
+        };
+        let quality = PreviewQuality::HqMp3;
+        Some(Self {
+            sound_id: self.sound_id,
+            url,
+            destination: self.destination.with_extension(quality.extension()),
+            source: DownloadSource::Preview(quality),
+            preview_url: None,
+            md5: None,
+        })
+    }
+}
+
+/// Downloads batches of files concurrently, reporting progress to a [`ProgressSink`]
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::env;
+/// use freesound_rs::{DownloadManager, FreesoundClient, PreviewQuality, SearchQueryBuilder};
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     dotenvy::dotenv().ok();
+///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+///     let client = FreesoundClient::new(api_key, None);
+/// let results = client
+///     .search(&SearchQueryBuilder::new().query("rain").build())
+///     .await?;
+///
+/// let mut manager = DownloadManager::new(client).concurrency(8);
+/// for sound in &results.results {
+///     manager.enqueue_preview(sound, PreviewQuality::HqOgg, "downloads")?;
+/// }
+/// let report = manager.run().await;
+/// for item in report.failed() {
+///     eprintln!("{} failed: {}", item.job.sound_id, item.error.as_deref().unwrap_or_default());
+/// }
+/// std::fs::write("downloads/report.json", serde_json::to_string_pretty(&report)?)?;
+///      Ok(())
+///  }
+/// ```
+pub struct DownloadManager {
+    client: FreesoundClient,
+    concurrency: usize,
+    progress: Arc<dyn ProgressSink>,
+    manifest: Option<Manifest>,
+    preview_fallback: bool,
+    overwrite: OverwritePolicy,
+    min_free_space: Option<u64>,
+    chunked: Option<(u64, usize)>,
+    store: Option<Arc<ContentStore>>,
+    #[cfg(feature = "decode")]
+    loudness: Option<LoudnessCheck>,
+    shutdown: ShutdownHandle,
+    queue: Vec<DownloadJob>,
+}
+
+impl DownloadManager {
+    /// Creates a download manager using the given client
+    pub fn new(client: FreesoundClient) -> Self {
+        Self {
+            client,
+            concurrency: DEFAULT_CONCURRENCY,
+            progress: Arc::new(NoProgress),
+            manifest: None,
+            preview_fallback: false,
+            overwrite: OverwritePolicy::default(),
+            min_free_space: None,
+            chunked: None,
+            store: None,
+            #[cfg(feature = "decode")]
+            loudness: None,
+            shutdown: ShutdownHandle::default(),
+            queue: Vec::new(),
+        }
+    }
+
+    /// Set the maximum number of downloads running at the same time
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Set the priority of the downloads for the rate limiter of the client
+    ///
+    /// A bulk download set to [`Priority::Background`] leaves the quota to the
+    /// interactive requests of the other clones of the client (see
+    /// [`RateLimiter`](crate::RateLimiter)).
+    pub fn priority(mut self, priority: Priority) -> Self {
+        self.client = self.client.with_priority(priority);
+        self
+    }
+
+    /// Set the sink receiving progress notifications
+    ///
+    /// An [`EventSink`](super::EventSink) turns them into typed events, sent
+    /// to a callback or a channel.
+    pub fn progress(mut self, sink: impl ProgressSink + 'static) -> Self {
+        self.progress = Arc::new(sink);
+        self
+    }
+
+    /// Download the HQ MP3 preview when an original cannot be downloaded
+    ///
+    /// Originals fall back to their preview when the client has no access token
+    /// or when the API refuses the download, instead of failing. The manifest
+    /// records which file was fetched. Defaults to false.
+    pub fn preview_fallback(mut self, enabled: bool) -> Self {
+        self.preview_fallback = enabled;
+        self
+    }
+
+    /// Set what to do when the file of a job already exists
+    ///
+    /// Skipped jobs complete with the size of the existing file, without any
+    /// request. Renamed jobs are returned, and recorded in the manifest, with
+    /// the name they were written to. Defaults to [`OverwritePolicy::Overwrite`].
+    pub fn overwrite(mut self, policy: OverwritePolicy) -> Self {
+        self.overwrite = policy;
+        self
+    }
+
+    /// Keep at least `bytes` free on the disk receiving the files
+    ///
+    /// Before writing a file, the manager checks that the file system holding it
+    /// keeps `bytes` free once the file is written, when its size is known. Jobs
+    /// that would go below fail with an
+    /// [`InsufficientSpace`](FreesoundError::InsufficientSpace) error, before
+    /// anything is written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{DownloadManager, FreesoundClient, OverwritePolicy};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let mut manager = DownloadManager::new(client)
+    ///     .overwrite(OverwritePolicy::Skip)
+    ///     .min_free_space(1 << 30);
+    /// manager.resume("downloads/manifest.jsonl")?;
+    /// let report = manager.run().await;
+    /// println!(
+    ///     "{} downloaded, {} already there, {} failed",
+    ///     report.downloaded().count(),
+    ///     report.skipped().count(),
+    ///     report.failed().count()
+    /// );
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn min_free_space(mut self, bytes: u64) -> Self {
+        self.min_free_space = Some(bytes);
+        self
+    }
+
+    /// Download files of at least `min_size` bytes as `chunks` ranges fetched at once
+    ///
+    /// Large originals download faster this way on high-latency links. The
+    /// ranges are written in place and the file is checked against the MD5
+    /// digest of the job once complete, as other files are. Servers that do not
+    /// announce range support get a single request. Disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{DownloadJob, DownloadManager, FreesoundClient};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let access_token = env::var("FREESOUND_ACCESS_TOKEN")?;
+    ///     let client = FreesoundClient::new(api_key, None).with_access_token(access_token);
+    /// let sound = client.get_sound(1234, &Default::default()).await?;
+    /// let mut manager = DownloadManager::new(client.clone()).chunked(100 << 20, 8);
+    /// manager.enqueue(DownloadJob::original(&client, &sound, "originals"))?;
+    /// manager.run().await;
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn chunked(mut self, min_size: u64, chunks: usize) -> Self {
+        self.chunked = Some((min_size, chunks.max(1)));
+        self
+    }
+
+    /// Download into a [`ContentStore`] instead of the destinations of the jobs
+    ///
+    /// Jobs for a file the store already holds, or whose MD5 digest it holds,
+    /// are skipped without any request. Other files are moved into the store
+    /// once downloaded. Jobs are reported, and recorded in the manifest, with
+    /// the path of their file in the store. The [overwrite
+    /// policy](Self::overwrite) does not apply.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{ContentStore, DownloadManager, FreesoundClient, PreviewQuality};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let store = ContentStore::open("/var/cache/freesound")?;
+    /// let mut manager = DownloadManager::new(client.clone()).store(store);
+    /// for query in ["rain", "rain on roof"] {
+    ///     let results = client.search(&[("query".to_string(), query.to_string())]).await?;
+    ///     for sound in &results.results {
+    ///         // The directory is ignored: files go to the store
+    ///         manager.enqueue_preview(sound, PreviewQuality::HqOgg, "")?;
+    ///     }
+    /// }
+    /// // Sounds matching both queries are only downloaded once
+    /// for item in manager.run().await.items {
+    ///     println!("{}: {}", item.job.sound_id, item.job.destination.display());
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn store(mut self, store: ContentStore) -> Self {
+        self.store = Some(Arc::new(store));
+        self
+    }
+
+    /// Measure the loudness of every downloaded file
+    ///
+    /// Each file is decoded once written, and its [`Loudness`] is recorded in
+    /// the report and the manifest. Files out of the limits of `check` are
+    /// logged, or deleted and failed with a [`Rejected`](FreesoundError::Rejected)
+    /// error if the check [rejects](LoudnessCheck::reject) them. Files that
+    /// cannot be decoded are kept without a measurement. Decoding runs on a
+    /// blocking thread pool, so it does not hold up the other downloads.
+    /// Requires the `decode` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{DownloadManager, FreesoundClient, LoudnessCheck};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let check = LoudnessCheck::new().max_true_peak(-1.0).reject(true);
+    /// let mut manager = DownloadManager::new(client).loudness_check(check);
+    /// manager.resume("downloads/manifest.jsonl")?;
+    ///
+    /// for item in manager.run().await.items {
+    ///     if let Some(loudness) = item.loudness {
+    ///         println!("{}: {:?} LUFS", item.job.destination.display(), loudness.integrated_lufs);
+    ///     }
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    #[cfg(feature = "decode")]
+    pub fn loudness_check(mut self, check: LoudnessCheck) -> Self {
+        self.loudness = Some(check);
+        self
+    }
+
+    /// Record every job in a manifest file, so an interrupted batch can be resumed
+    ///
+    /// See [`resume`](Self::resume).
+    pub fn manifest(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        self.manifest = Some(Manifest::open(path)?);
+        Ok(self)
+    }
+
+    /// Keep the queue in a manifest file, so it survives restarts
+    ///
+    /// On the first run the file is created, as with
+    /// [`manifest`](Self::manifest); on the next ones, the jobs the previous
+    /// runs left unfinished are [resumed](Self::resume). Jobs queued but not
+    /// started when the process stops are run by the next one.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{DownloadManager, FreesoundClient, PreviewQuality};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let mut manager = DownloadManager::new(client.clone()).persistent_queue("mirror/queue.jsonl")?;
+    /// if manager.queued().is_empty() {
+    ///     let page = client.search(&[("query".to_string(), "rain".to_string())]).await?;
+    ///     for sound in &page.results {
+    ///         manager.enqueue_preview(sound, PreviewQuality::HqOgg, "mirror")?;
+    ///     }
+    /// }
+    /// manager.run().await;
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn persistent_queue(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if path.exists() {
+            self.resume(path)?;
+            Ok(self)
+        } else {
+            self.manifest(path)
+        }
+    }
+
+    /// Re-queue the unfinished jobs of a manifest written by a previous run
+    ///
+    /// Completed jobs whose file still exists are skipped; queued and failed jobs
+    /// are queued again. The manifest is [compacted](Manifest::compact) and
+    /// keeps being written by this manager.
+    ///
+    /// # Returns
+    ///
+    /// The number of jobs queued
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{DownloadManager, FreesoundClient};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let mut manager = DownloadManager::new(client);
+    /// let remaining = manager.resume("downloads/manifest.jsonl")?;
+    /// println!("Resuming {remaining} downloads");
+    /// manager.run().await;
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn resume(&mut self, path: impl AsRef<Path>) -> Result<usize> {
+        let entries = Manifest::compact(&path)?;
+        self.manifest = Some(Manifest::open(&path)?);
+
+        let before = self.queue.len();
+        for entry in entries {
+            if entry.status != JobStatus::Completed || !entry.file().exists() {
+                self.progress.job_queued(&entry.job);
+                self.queue.push(entry.job);
+            }
+        }
+        Ok(self.queue.len() - before)
+    }
+
+    /// Returns a handle to shut the manager down while it runs
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{DownloadManager, Drain, FreesoundClient};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let mut manager = DownloadManager::new(client).manifest("downloads/manifest.jsonl")?;
+    /// manager.resume("downloads/manifest.jsonl")?;
+    ///
+    /// let handle = manager.shutdown_handle();
+    /// tokio::spawn(async move {
+    ///     tokio::signal::ctrl_c().await.ok();
+    ///     handle.shutdown(Drain::FinishCurrent);
+    /// });
+    ///
+    /// let report = manager.run().await;
+    /// println!(
+    ///     "{} jobs finished, {} left for next time",
+    ///     report.items.len(),
+    ///     manager.queued().len()
+    /// );
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn shutdown_handle(&self) -> ShutdownHandle {
+        self.shutdown.clone()
+    }
+
+    /// Add a job to the queue
+    ///
+    /// Fails if the manager was shut down, or if the job cannot be recorded in
+    /// the manifest.
+    pub fn enqueue(&mut self, job: DownloadJob) -> Result<()> {
+        if self.shutdown.is_shutdown() {
+            return Err(FreesoundError::InvalidRequest(
+                "the download manager was shut down".to_string(),
+            ));
+        }
+        self.record(&ManifestEntry {
+            job: job.clone(),
+            status: JobStatus::Queued,
+            bytes: None,
+            error: None,
+            fetched: None,
+            loudness: None,
+        })?;
+        self.progress.job_queued(&job);
+        self.queue.push(job);
+        Ok(())
+    }
+
+    /// Queue the download of a sound preview into `dir`
+    pub fn enqueue_preview(
+        &mut self,
+        sound: &Sound,
+        quality: PreviewQuality,
+        dir: impl AsRef<Path>,
+    ) -> Result<()> {
+        self.enqueue(DownloadJob::preview(sound, quality, dir)?)
+    }
+
+    /// Queue every sound of the given packs, one directory per pack
+    ///
+    /// Each pack is downloaded into `dir/<pack id> - <pack name>`. Sounds that
+    /// are already queued, or whose file already exists, are skipped, so a sound
+    /// shared by several packs is only fetched once.
+    ///
+    /// # Returns
+    ///
+    /// The number of jobs queued
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{DownloadManager, DownloadSource, FreesoundClient, PackId, PreviewQuality};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let mut manager = DownloadManager::new(client);
+    /// let source = DownloadSource::Preview(PreviewQuality::HqOgg);
+    /// manager.enqueue_packs(&[PackId(9678), PackId(1234)], source, "packs").await?;
+    /// manager.run().await;
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn enqueue_packs(
+        &mut self,
+        pack_ids: &[PackId],
+        source: DownloadSource,
+        dir: impl AsRef<Path>,
+    ) -> Result<usize> {
+        let options = SoundListOptions::new()
+            .page_size(150)
+            .fields(["id", "name", "type", "previews"]);
+        let mut queued = 0;
+
+        for &pack_id in pack_ids {
+            let pack = self.client.get_pack(pack_id).await?;
+            let pack_dir = dir
+                .as_ref()
+                .join(sanitize_file_name(&format!("{} - {}", pack.id, pack.name)));
+
+            let first = self.client.get_pack_sounds(pack_id, &options).await?;
+            let mut sounds = Box::pin(self.client.items(first));
+            while let Some(sound) = sounds.try_next().await? {
+                if self.queue.iter().any(|job| job.sound_id == sound.id) {
+                    continue;
+                }
+                let job = DownloadJob::new(&self.client, &sound, source, &pack_dir)?;
+                if job.destination.exists() {
+                    continue;
+                }
+                self.enqueue(job)?;
+                queued += 1;
+            }
+        }
+
+        Ok(queued)
+    }
+
+    /// Returns the jobs waiting to be run
+    pub fn queued(&self) -> &[DownloadJob] {
+        &self.queue
+    }
+
+    /// Run every queued job and return a report of their outcomes
+    ///
+    /// Each [item](DownloadItem) of the report holds the number of bytes
+    /// written or the error that stopped the job, and the time it took; the
+    /// typed errors are passed to the [`ProgressSink`]. A failing job does not
+    /// stop the others. A job whose outcome cannot be recorded in the manifest
+    /// is reported as failed. Jobs that fell back to the preview are reported
+    /// as their [`fallback`](DownloadJob::fallback).
+    ///
+    /// Files are written next to their destination with a `.part` suffix and
+    /// only renamed once complete and, for jobs with an MD5 digest, checked, so
+    /// an interrupted run never leaves a truncated file under its final name.
+    /// Files failing the check are deleted and their job fails with a
+    /// [`ChecksumMismatch`](FreesoundError::ChecksumMismatch) error. File
+    /// operations run on the thread pool of the `blocking` crate, except for
+    /// the manifest appends.
+    ///
+    /// After a [shutdown](Self::shutdown_handle), no new job is started and the
+    /// jobs left stay [queued](Self::queued). Aborted jobs fail with an
+    /// [`Interrupted`](std::io::ErrorKind::Interrupted) I/O error.
+    pub async fn run(&mut self) -> DownloadReport {
+        let started = Instant::now();
+        let pending = Mutex::new(VecDeque::from(std::mem::take(&mut self.queue)));
+        let this = &*self;
+        let jobs = stream::poll_fn(|_| {
+            Poll::Ready(match this.shutdown.is_shutdown() {
+                true => None,
+                false => pending.lock().unwrap().pop_front(),
+            })
+        });
+
+        let items = jobs
+            .map(|job| async move {
+                let job_started = Instant::now();
+                let (abort, registration) = AbortHandle::new_pair();
+                this.shutdown.register(abort);
+                let (fetched, outcome) = match Abortable::new(this.fetch(&job), registration).await
+                {
+                    Ok(outcome) => outcome,
+                    Err(Aborted) => (job.clone(), Err(aborted())),
+                };
+                let skipped = matches!(outcome, Ok((_, DownloadStatus::Skipped)));
+                let mut result = outcome.map(|(bytes, _)| bytes);
+                let loudness = this.check_loudness(&fetched, &mut result).await;
+                // A renamed file is recorded under its new name
+                let job = match fetched.source == job.source {
+                    true => fetched.clone(),
+                    false => job,
+                };
+                let recorded = match &result {
+                    Ok(bytes) => this.record(&ManifestEntry {
+                        job,
+                        status: JobStatus::Completed,
+                        bytes: Some(*bytes),
+                        error: None,
+                        fetched: Some(fetched.source),
+                        loudness,
+                    }),
+                    Err(e) => this.record(&ManifestEntry {
+                        job,
+                        status: JobStatus::Failed,
+                        bytes: None,
+                        error: Some(e.to_string()),
+                        fetched: None,
+                        loudness,
+                    }),
+                };
+                if let Err(e) = recorded {
+                    result = Err(e);
+                }
+                this.progress.job_finished(&fetched, &result);
+
+                let (status, bytes, error) = match result {
+                    Ok(bytes) if skipped => (DownloadStatus::Skipped, bytes, None),
+                    Ok(bytes) => (DownloadStatus::Downloaded, bytes, None),
+                    Err(e) => (DownloadStatus::Failed, 0, Some(e.to_string())),
+                };
+                DownloadItem {
+                    job: fetched,
+                    status,
+                    bytes,
+                    duration: job_started.elapsed(),
+                    error,
+                    loudness,
+                }
+            })
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+
+        self.queue = pending.into_inner().unwrap().into();
+        DownloadReport {
+            items,
+            duration: started.elapsed(),
+        }
+    }
+
+    /// Measures a downloaded file, rejecting it if it fails the loudness check
+    ///
+    /// Decoding takes a while, so it runs on the blocking pool.
+    #[cfg(feature = "decode")]
+    async fn check_loudness(
+        &self,
+        job: &DownloadJob,
+        result: &mut Result<u64>,
+    ) -> Option<Loudness> {
+        let check = self.loudness?;
+        result.as_ref().ok()?;
+
+        let path = job.destination.clone();
+        let measured = unblock(move || {
+            let bytes = std::fs::read(&path)?;
+            Pcm::decode(&bytes).map(|pcm| Loudness::measure(&pcm))
+        })
+        .await;
+        let loudness = match measured {
+            Ok(loudness) => loudness,
+            Err(e) => {
+                log::warn!(
+                    "Cannot measure the loudness of {}: {e}",
+                    job.destination.display()
+                );
+                return None;
+            }
+        };
+
+        let problems = check.problems(&loudness);
+        if !problems.is_empty() {
+            let problems = format!("{}: {}", job.destination.display(), problems.join(", "));
+            if check.rejects() {
+                let _ = remove_file(&job.destination).await;
+                *result = Err(FreesoundError::Rejected(problems));
+            } else {
+                log::warn!("{problems}");
+            }
+        }
+        Some(loudness)
+    }
+
+    #[cfg(not(feature = "decode"))]
+    async fn check_loudness(&self, _: &DownloadJob, _: &mut Result<u64>) -> Option<Loudness> {
+        None
+    }
+
+    fn record(&self, entry: &ManifestEntry) -> Result<()> {
+        match &self.manifest {
+            Some(manifest) => manifest.record(entry),
+            None => Ok(()),
+        }
+    }
+
+    /// Downloads a job, or its fallback if the original is refused
+    async fn fetch(&self, job: &DownloadJob) -> (DownloadJob, Result<(u64, DownloadStatus)>) {
+        let fallback = if self.preview_fallback {
+            job.fallback()
+        } else {
+            None
+        };
+
+        match (self.download(job).await, fallback) {
+            ((_, Err(e)), Some(fallback))
+                if matches!(
+                    e.root(),
+                    FreesoundError::AuthError(_) | FreesoundError::RequiresOAuth(_)
+                ) =>
+            {
+                self.download(&fallback).await
+            }
+            (outcome, _) => outcome,
+        }
+    }
+
+    /// Downloads a job, to the name given by the overwrite policy
+    async fn download(&self, job: &DownloadJob) -> (DownloadJob, Result<(u64, DownloadStatus)>) {
+        if let Some(store) = &self.store {
+            return self.download_to_store(store, job).await;
+        }
+        let (overwrite, path) = (self.overwrite, job.destination.clone());
+        let destination = match unblock(move || overwrite.resolve(&path)).await {
+            Ok(Some(destination)) => destination,
+            Ok(None) => {
+                let path = job.destination.clone();
+                let result = unblock(move || std::fs::metadata(path))
+                    .await
+                    .map(|m| (m.len(), DownloadStatus::Skipped))
+                    .map_err(FreesoundError::from);
+                return (job.clone(), result);
+            }
+            Err(e) => return (job.clone(), Err(e)),
+        };
+        let mut job = DownloadJob {
+            destination,
+            ..job.clone()
+        };
+        match self.write(&job).await {
+            Ok((bytes, md5)) => {
+                job.md5 = Some(md5);
+                (job, Ok((bytes, DownloadStatus::Downloaded)))
+            }
+            Err(e) => {
+                if self.overwrite == OverwritePolicy::RenameSuffix {
+                    // Free the name reserved by the policy
+                    let _ = remove_file(&job.destination).await;
+                }
+                (job, Err(e))
+            }
+        }
+    }
+
+    /// Downloads a job into the store, unless the store already holds its file
+    async fn download_to_store(
+        &self,
+        store: &Arc<ContentStore>,
+        job: &DownloadJob,
+    ) -> (DownloadJob, Result<(u64, DownloadStatus)>) {
+        let stored = |entry: StoreEntry, status| {
+            let job = DownloadJob {
+                destination: store.object_path(&entry.md5, &entry.extension),
+                md5: Some(entry.md5),
+                ..job.clone()
+            };
+            (job, Ok((entry.bytes, status)))
+        };
+        let (lookup_store, lookup_job) = (store.clone(), job.clone());
+        match unblock(move || lookup_store.lookup(&lookup_job)).await {
+            Ok(Some(entry)) => return stored(entry, DownloadStatus::Skipped),
+            Ok(None) => {}
+            Err(e) => return (job.clone(), Err(e)),
+        }
+
+        let incoming = DownloadJob {
+            destination: store.incoming_path(job),
+            ..job.clone()
+        };
+        let result = match self.write(&incoming).await {
+            Ok(_) => {
+                let (store, path) = (store.clone(), incoming.destination.clone());
+                let (sound_id, source) = (job.sound_id, job.source);
+                unblock(move || store.insert(sound_id, source, &path)).await
+            }
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(entry) => stored(entry, DownloadStatus::Downloaded),
+            Err(e) => (job.clone(), Err(e)),
+        }
+    }
+
+    /// Downloads a job to its destination, returning its size and MD5 digest
+    async fn write(&self, job: &DownloadJob) -> Result<(u64, String)> {
+        let response = match job.source {
+            DownloadSource::Original => self.client.get_download(&job.url).await,
+            DownloadSource::Preview(_) => self.client.get_media(&job.url).await,
+        }
+        .map_err(|e| e.for_sound(job.sound_id))?;
+        if let Some(min_free) = self.min_free_space {
+            let size = response.content_length().unwrap_or(0);
+            let path = job.destination.clone();
+            unblock(move || check_free_space(&path, size, min_free)).await?;
+        }
+        self.progress.job_started(job, response.content_length());
+        let rate = Mutex::new(TransferRate::new(response.content_length()));
+        let advanced = |bytes| {
+            self.progress.bytes_advanced(job, bytes);
+            let stats = rate.lock().unwrap().advance(bytes);
+            self.progress.stats_updated(job, &stats);
+        };
+
+        if let Some(parent) = job.destination.parent() {
+            let parent = parent.to_path_buf();
+            unblock(move || std::fs::create_dir_all(parent)).await?;
+        }
+
+        let accepts_ranges = response
+            .headers()
+            .get(reqwest::header::ACCEPT_RANGES)
+            .is_some_and(|value| value == "bytes");
+        if let Some((min_size, chunks)) = self.chunked
+            && let Some(size) = response.content_length()
+            && size >= min_size
+            && chunks > 1
+            && accepts_ranges
+        {
+            // Ranges go where the file was served from: once redirected off
+            // the API, originals are on a media host that needs no credentials
+            let url = response.url().to_string();
+            let redirected = reqwest::Url::parse(&job.url).ok().as_ref() != Some(response.url());
+            let fetch = |range| match job.source {
+                DownloadSource::Original if !redirected => {
+                    self.client.get_download_range(&url, Some(range)).boxed()
+                }
+                _ => self.client.get_media_range(&url, Some(range)).boxed(),
+            };
+            return self
+                .client
+                .write_ranges(
+                    response,
+                    fetch,
+                    &job.destination,
+                    size,
+                    chunks,
+                    job.md5.as_deref(),
+                    advanced,
+                )
+                .await;
+        }
+
+        self.client
+            .write_body_checked(response, &job.destination, job.md5.as_deref(), advanced)
+            .await
+    }
+}
+
+/// Removes a file off the executor
+async fn remove_file(path: &Path) -> std::io::Result<()> {
+    let path = path.to_path_buf();
+    unblock(move || std::fs::remove_file(path)).await
+}
+
+/// Returns the error of a job aborted by a shutdown
+fn aborted() -> FreesoundError {
+    std::io::Error::new(std::io::ErrorKind::Interrupted, "download aborted").into()
+}
+
+/// Replaces characters that are not allowed in file names on common platforms
+pub(crate) fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
@@ -0,0 +1,254 @@
+use super::{DownloadJob, DownloadSource};
+use crate::error::Result;
+use crate::loudness::Loudness;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// State of a job recorded in a manifest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// The job was queued but has not finished yet
+    Queued,
+    /// The file was fully downloaded
+    Completed,
+    /// The download failed
+    Failed,
+}
+
+/// A line of a download manifest
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ManifestEntry {
+    /// The job this entry is about
+    #[serde(flatten)]
+    pub job: DownloadJob,
+    /// The state of the job when the entry was written
+    pub status: JobStatus,
+    /// Number of bytes written, for completed jobs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+    /// Error message, for failed jobs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The file actually downloaded, for completed jobs
+    ///
+    /// Differs from the source of the job when an original fell back to its preview.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetched: Option<DownloadSource>,
+    /// Loudness of the file, when the manager checks it
+    ///
+    /// See [`DownloadManager::loudness_check`](crate::DownloadManager::loudness_check).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loudness: Option<Loudness>,
+}
+
+impl ManifestEntry {
+    /// Returns the path of the file written for this entry
+    ///
+    /// This is the destination of the job, unless it fell back to the preview.
+    pub fn file(&self) -> PathBuf {
+        match (self.fetched, self.job.fallback()) {
+            (Some(fetched), Some(fallback)) if fetched == fallback.source => fallback.destination,
+            _ => self.job.destination.clone(),
+        }
+    }
+}
+
+/// An append-only journal of download jobs, stored as JSON lines
+///
+/// Every state change of a job appends a line, so the file stays usable if
+/// the process is killed midway. The last entry of a job gives its state.
+///
+/// Entries are [`ManifestEntry`] by default; the journal of a
+/// [`BulkUpload`](crate::BulkUpload) records
+/// [`UploadJournalEntry`](crate::UploadJournalEntry) lines the same way.
+#[derive(Debug)]
+pub struct Manifest<E = ManifestEntry> {
+    path: PathBuf,
+    file: Mutex<File>,
+    entries: PhantomData<fn(&E)>,
+}
+
+impl<E: Serialize> Manifest<E> {
+    /// Opens a manifest for appending, creating the file if needed
+    ///
+    /// If the file ends with a truncated line, as left by a crash, a line
+    /// break is added first so the next entry starts on a line of its own.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+        let len = file.metadata()?.len();
+        if len > 0 {
+            let mut last = [0];
+            file.seek(SeekFrom::Start(len - 1))?;
+            file.read_exact(&mut last)?;
+            if last != *b"\n" {
+                file.write_all(b"\n")?;
+            }
+        }
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            entries: PhantomData,
+        })
+    }
+
+    /// Returns the path of the manifest file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends an entry to the manifest
+    pub fn record(&self, entry: &E) -> Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+        let mut file = self.file.lock().unwrap();
+        file.write_all(line.as_bytes())?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+impl Manifest {
+    /// Reads every entry of a manifest file
+    ///
+    /// Lines that are not valid entries, such as a line truncated by a crash,
+    /// are skipped with a warning logged; they stay in the file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Vec<ManifestEntry>> {
+        read_entries(path)
+    }
+
+    /// Returns the latest entry of each job of a manifest file, in first-seen order
+    ///
+    /// Jobs are identified by their destination.
+    pub fn latest(path: impl AsRef<Path>) -> Result<Vec<ManifestEntry>> {
+        Ok(latest_entries(Self::load(path)?))
+    }
+
+    /// Rewrites a manifest file with the latest entry of each job, returning them
+    ///
+    /// A manifest gains a line at each state change, so one kept across many
+    /// runs grows with the history of its jobs; compacting it keeps one line
+    /// per job. Lines that are not valid entries are kept as they are, at the
+    /// start of the file, for someone to look at. The new file replaces the
+    /// old one once fully written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::{DownloadJob, DownloadSource, JobStatus, Manifest, ManifestEntry};
+    /// use std::io::Write;
+    ///
+    /// let path = std::env::temp_dir().join("freesound-rs-doctest-compact.jsonl");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let manifest = Manifest::open(&path)?;
+    /// let mut entry = ManifestEntry {
+    ///     job: DownloadJob {
+    ///         sound_id: 1234,
+    ///         url: "https://cdn.freesound.org/previews/1234-hq.mp3".to_string(),
+    ///         destination: "1234.mp3".into(),
+    ///         source: DownloadSource::default(),
+    ///         preview_url: None,
+    ///         md5: None,
+    ///     },
+    ///     status: JobStatus::Queued,
+    ///     bytes: None,
+    ///     error: None,
+    ///     fetched: None,
+    ///     loudness: None,
+    /// };
+    /// manifest.record(&entry)?;
+    /// // A line cut short by a crash
+    /// std::fs::OpenOptions::new().append(true).open(&path)?.write_all(b"{\"sound_id")?;
+    /// let manifest = Manifest::open(&path)?;
+    /// entry.status = JobStatus::Failed;
+    /// manifest.record(&entry)?;
+    ///
+    /// let entries = Manifest::compact(&path)?;
+    /// assert_eq!(entries.len(), 1);
+    /// assert_eq!(Manifest::load(&path)?[0].status, JobStatus::Failed);
+    /// assert!(std::fs::read_to_string(&path)?.starts_with("{\"sound_id\n"));
+    /// # std::fs::remove_file(&path)?;
+    /// # Ok::<(), freesound_rs::FreesoundError>(())
+    /// ```
+    pub fn compact(path: impl AsRef<Path>) -> Result<Vec<ManifestEntry>> {
+        let path = path.as_ref();
+        let (entries, malformed) = read_lines(path)?;
+        let entries = latest_entries(entries);
+        let mut content = String::new();
+        for line in &malformed {
+            content.push_str(line);
+            content.push('\n');
+        }
+        for entry in &entries {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(format!(".{}.tmp", std::process::id()));
+        std::fs::write(&temporary, content)?;
+        std::fs::rename(&temporary, path)?;
+        Ok(entries)
+    }
+}
+
+/// Returns the latest of `entries` for each job, in first-seen order
+fn latest_entries(entries: Vec<ManifestEntry>) -> Vec<ManifestEntry> {
+    let mut order = Vec::new();
+    let mut latest: HashMap<PathBuf, ManifestEntry> = HashMap::new();
+    for entry in entries {
+        let key = entry.job.destination.clone();
+        if !latest.contains_key(&key) {
+            order.push(key.clone());
+        }
+        latest.insert(key, entry);
+    }
+    order
+        .into_iter()
+        .filter_map(|key| latest.remove(&key))
+        .collect()
+}
+
+/// Reads every entry of a manifest file of any entry type
+///
+/// Malformed lines are skipped with a warning.
+pub(crate) fn read_entries<E: DeserializeOwned>(path: impl AsRef<Path>) -> Result<Vec<E>> {
+    let path = path.as_ref();
+    let (entries, malformed) = read_lines(path)?;
+    if !malformed.is_empty() {
+        log::warn!(
+            "Skipped {} malformed line(s) of {}",
+            malformed.len(),
+            path.display()
+        );
+    }
+    Ok(entries)
+}
+
+/// Reads the entries of a manifest file, and the non-empty lines that are not entries
+fn read_lines<E: DeserializeOwned>(path: &Path) -> Result<(Vec<E>, Vec<String>)> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut entries = Vec::new();
+    let mut malformed = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(entry) => entries.push(entry),
+            Err(_) => malformed.push(line),
+        }
+    }
+    Ok((entries, malformed))
+}
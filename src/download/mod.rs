@@ -0,0 +1,28 @@
+mod events;
+mod manager;
+mod manifest;
+mod policy;
+mod progress;
+mod report;
+mod shutdown;
+mod store;
+
+#[cfg(feature = "indicatif")]
+mod indicatif;
+
+pub use events::{DownloadEvent, EventSink};
+pub(crate) use manager::sanitize_file_name;
+pub use manager::{DownloadJob, DownloadManager, DownloadSource};
+pub(crate) use manifest::read_entries;
+pub use manifest::{JobStatus, Manifest, ManifestEntry};
+pub use policy::OverwritePolicy;
+pub(crate) use policy::check_free_space;
+pub use progress::{
+    NoProgress, ProgressSink, RATE_SAMPLE_INTERVAL, RATE_SMOOTHING, TransferRate, TransferStats,
+};
+pub use report::{DownloadItem, DownloadReport, DownloadStatus};
+pub use shutdown::{Drain, ShutdownHandle};
+pub use store::{ContentStore, STORE_INDEX_FILE, StoreEntry};
+
+#[cfg(feature = "indicatif")]
+pub use self::indicatif::IndicatifProgress;
@@ -0,0 +1,133 @@
+use crate::error::{FreesoundError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// What to do when the destination of a download already exists
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverwritePolicy {
+    /// Keep the existing file and do not download anything
+    Skip,
+    /// Replace the existing file
+    #[default]
+    Overwrite,
+    /// Write to a free name, e.g. `1234 (1).mp3`, and keep the existing file
+    RenameSuffix,
+}
+
+impl OverwritePolicy {
+    /// Returns where to write a file meant for `destination`, or `None` to skip it
+    ///
+    /// With [`RenameSuffix`](Self::RenameSuffix), the name returned is reserved
+    /// by creating it as an empty file, which fails if it exists: concurrent
+    /// downloads never get the same name. The download then replaces the empty
+    /// file, or removes it if it fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::OverwritePolicy;
+    /// use std::path::Path;
+    ///
+    /// let dir = std::env::temp_dir().join("freesound-rs-overwrite-policy");
+    /// std::fs::create_dir_all(&dir)?;
+    /// let existing = dir.join("1234.mp3");
+    /// std::fs::write(&existing, b"")?;
+    ///
+    /// assert_eq!(OverwritePolicy::Skip.resolve(&existing)?, None);
+    /// assert_eq!(OverwritePolicy::Overwrite.resolve(&existing)?, Some(existing.clone()));
+    /// assert_eq!(
+    ///     OverwritePolicy::RenameSuffix.resolve(&existing)?,
+    ///     Some(dir.join("1234 (1).mp3"))
+    /// );
+    /// // The first name is now taken
+    /// assert_eq!(
+    ///     OverwritePolicy::RenameSuffix.resolve(&existing)?,
+    ///     Some(dir.join("1234 (2).mp3"))
+    /// );
+    /// # std::fs::remove_dir_all(&dir)?;
+    /// # Ok::<(), freesound_rs::FreesoundError>(())
+    /// ```
+    pub fn resolve(&self, destination: &Path) -> Result<Option<PathBuf>> {
+        match self {
+            Self::Skip if destination.exists() => Ok(None),
+            Self::Skip | Self::Overwrite => Ok(Some(destination.to_path_buf())),
+            Self::RenameSuffix => {
+                if let Some(parent) = destination.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let stem = destination
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy();
+                let extension = destination
+                    .extension()
+                    .map(|e| format!(".{}", e.to_string_lossy()))
+                    .unwrap_or_default();
+                let candidates = std::iter::once(destination.to_path_buf()).chain(
+                    (1..).map(|n| destination.with_file_name(format!("{stem} ({n}){extension}"))),
+                );
+                for path in candidates {
+                    match OpenOptions::new().write(true).create_new(true).open(&path) {
+                        Ok(_) => return Ok(Some(path)),
+                        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                unreachable!("the candidate names are endless")
+            }
+        }
+    }
+}
+
+impl fmt::Display for OverwritePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Skip => "skip",
+            Self::Overwrite => "overwrite",
+            Self::RenameSuffix => "rename-suffix",
+        })
+    }
+}
+
+impl FromStr for OverwritePolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(Self::Skip),
+            "overwrite" => Ok(Self::Overwrite),
+            "rename-suffix" => Ok(Self::RenameSuffix),
+            other => Err(format!("unknown overwrite policy: {other}")),
+        }
+    }
+}
+
+/// Fails if writing `size` bytes to `path` would leave less than `min_free` bytes
+///
+/// The space is measured on the file system of the closest existing ancestor
+/// of `path`, so its directories do not need to exist yet.
+pub(crate) fn check_free_space(path: &Path, size: u64, min_free: u64) -> Result<()> {
+    let existing = path
+        .ancestors()
+        .skip(1)
+        .map(|dir| match dir.as_os_str().is_empty() {
+            true => Path::new("."),
+            false => dir,
+        })
+        .find(|dir| dir.exists())
+        .unwrap_or(Path::new("."));
+    let available = fs4::available_space(existing)?;
+    let required = size.saturating_add(min_free);
+    if available < required {
+        return Err(FreesoundError::InsufficientSpace {
+            path: existing.to_path_buf(),
+            available,
+            required,
+        });
+    }
+    Ok(())
+}
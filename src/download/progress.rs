@@ -0,0 +1,183 @@
+use super::DownloadJob;
+use crate::clock::{Clock, SystemClock};
+use crate::error::Result;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Default weight of the latest sample in the speed of a [`TransferRate`]
+pub const RATE_SMOOTHING: f64 = 0.3;
+
+/// Shortest time over which a [`TransferRate`] measures the speed, so that
+/// bursts of small chunks do not make it jump
+pub const RATE_SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Receives progress notifications from the [`DownloadManager`](super::DownloadManager)
+///
+/// All methods have empty default implementations, so implementors only need to
+/// override the events they care about. Notifications for concurrent jobs may
+/// be interleaved.
+pub trait ProgressSink: Send + Sync {
+    /// Called when a job is added to the queue, or taken back from a manifest
+    fn job_queued(&self, _job: &DownloadJob) {}
+
+    /// Called when the server answered and the transfer of a job begins
+    ///
+    /// `total_bytes` is the size announced by the server, if any.
+    fn job_started(&self, _job: &DownloadJob, _total_bytes: Option<u64>) {}
+
+    /// Called each time a chunk of `bytes` has been written to disk
+    fn bytes_advanced(&self, _job: &DownloadJob, _bytes: u64) {}
+
+    /// Called after [`bytes_advanced`](Self::bytes_advanced), with the
+    /// progress, speed and estimated time left of the job
+    fn stats_updated(&self, _job: &DownloadJob, _stats: &TransferStats) {}
+
+    /// Called once a job is over, with the number of bytes written or the error
+    fn job_finished(&self, _job: &DownloadJob, _result: &Result<u64>) {}
+}
+
+/// A [`ProgressSink`] ignoring every notification
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {}
+
+/// The progress of a transfer, as measured by a [`TransferRate`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransferStats {
+    /// Bytes transferred so far
+    pub bytes: u64,
+    /// Size of the whole transfer, if known
+    pub total_bytes: Option<u64>,
+    /// Time since the transfer started
+    pub elapsed: Duration,
+    /// Smoothed speed, in bytes per second, once measured
+    pub bytes_per_sec: Option<f64>,
+    /// Estimated time left, once the speed is measured and if the size is known
+    pub eta: Option<Duration>,
+}
+
+impl TransferStats {
+    /// Returns the fraction transferred, between 0 and 1, if the size is known
+    pub fn fraction(&self) -> Option<f64> {
+        self.total_bytes
+            .filter(|&total| total > 0)
+            .map(|total| (self.bytes as f64 / total as f64).min(1.0))
+    }
+}
+
+/// Measures the speed and the time left of a transfer
+///
+/// The speed is an exponentially weighted moving average of the speeds
+/// measured over successive periods of at least [`RATE_SAMPLE_INTERVAL`], each
+/// new period weighing [`RATE_SMOOTHING`]. The
+/// [`DownloadManager`](super::DownloadManager) keeps one per job and passes its
+/// stats to [`ProgressSink::stats_updated`].
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use freesound_rs::{MockClock, TransferRate};
+///
+/// let clock = Arc::new(MockClock::new());
+/// let mut rate = TransferRate::new(Some(5000)).with_clock(clock.clone());
+///
+/// clock.advance(Duration::from_secs(1));
+/// let stats = rate.advance(1000);
+/// assert_eq!(stats.bytes_per_sec, Some(1000.0));
+/// assert_eq!(stats.eta, Some(Duration::from_secs(4)));
+///
+/// // A faster second only moves the speed part of the way
+/// clock.advance(Duration::from_secs(1));
+/// let stats = rate.advance(2000);
+/// assert_eq!(stats.bytes_per_sec, Some(1300.0));
+/// assert_eq!(stats.fraction(), Some(0.6));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TransferRate {
+    clock: Arc<dyn Clock>,
+    smoothing: f64,
+    total_bytes: Option<u64>,
+    bytes: u64,
+    started: Instant,
+    sample_start: Instant,
+    sample_bytes: u64,
+    bytes_per_sec: Option<f64>,
+}
+
+impl TransferRate {
+    /// Start measuring a transfer of `total_bytes`, if known
+    pub fn new(total_bytes: Option<u64>) -> Self {
+        Self::started_at(Arc::new(SystemClock), total_bytes)
+    }
+
+    fn started_at(clock: Arc<dyn Clock>, total_bytes: Option<u64>) -> Self {
+        let now = clock.now();
+        Self {
+            clock,
+            smoothing: RATE_SMOOTHING,
+            total_bytes,
+            bytes: 0,
+            started: now,
+            sample_start: now,
+            sample_bytes: 0,
+            bytes_per_sec: None,
+        }
+    }
+
+    /// Read the time from another clock (see [`MockClock`](crate::MockClock))
+    ///
+    /// The transfer starts over from the current time of the clock.
+    pub fn with_clock(self, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            smoothing: self.smoothing,
+            ..Self::started_at(clock, self.total_bytes)
+        }
+    }
+
+    /// Set the weight of the latest sample in the speed, between 0 and 1
+    ///
+    /// Higher values follow changes faster, lower values give steadier
+    /// estimates.
+    pub fn with_smoothing(mut self, smoothing: f64) -> Self {
+        self.smoothing = smoothing.clamp(f64::EPSILON, 1.0);
+        self
+    }
+
+    /// Count `bytes` more transferred and return the updated stats
+    pub fn advance(&mut self, bytes: u64) -> TransferStats {
+        self.bytes += bytes;
+        self.sample_bytes += bytes;
+        let now = self.clock.now();
+        let period = now.saturating_duration_since(self.sample_start);
+        if period >= RATE_SAMPLE_INTERVAL {
+            let speed = self.sample_bytes as f64 / period.as_secs_f64();
+            self.bytes_per_sec = Some(match self.bytes_per_sec {
+                Some(previous) => self.smoothing * speed + (1.0 - self.smoothing) * previous,
+                None => speed,
+            });
+            self.sample_start = now;
+            self.sample_bytes = 0;
+        }
+        self.stats()
+    }
+
+    /// Returns the stats of the transfer so far
+    pub fn stats(&self) -> TransferStats {
+        let eta = match (self.total_bytes, self.bytes_per_sec) {
+            (Some(total), Some(speed)) if speed > 0.0 => Some(Duration::from_secs_f64(
+                total.saturating_sub(self.bytes) as f64 / speed,
+            )),
+            _ => None,
+        };
+        TransferStats {
+            bytes: self.bytes,
+            total_bytes: self.total_bytes,
+            elapsed: self.clock.now().saturating_duration_since(self.started),
+            bytes_per_sec: self.bytes_per_sec,
+            eta,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use super::DownloadJob;
+use crate::loudness::Loudness;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How a job of a [`DownloadReport`] ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadStatus {
+    /// The file was downloaded
+    Downloaded,
+    /// The file already existed and was kept, see [`OverwritePolicy::Skip`](crate::OverwritePolicy::Skip)
+    Skipped,
+    /// The job failed
+    Failed,
+}
+
+/// A job of a [`DownloadReport`]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct DownloadItem {
+    /// The job as run, i.e. its fallback if it fell back to the preview, and
+    /// with the name the file was written to
+    #[serde(flatten)]
+    pub job: DownloadJob,
+    /// How the job ended
+    pub status: DownloadStatus,
+    /// Number of bytes written, or size of the existing file for skipped jobs
+    pub bytes: u64,
+    /// Time spent on the job
+    pub duration: Duration,
+    /// Why the job failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Loudness of the file, when the manager checks it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loudness: Option<Loudness>,
+}
+
+/// Outcome of a [`DownloadManager::run`](crate::DownloadManager::run)
+///
+/// The report can be serialized, e.g. to archive the log of an asset fetch.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use freesound_rs::{
+///     DownloadItem, DownloadJob, DownloadReport, DownloadSource, DownloadStatus, PreviewQuality,
+/// };
+///
+/// let item = |sound_id, status, bytes| DownloadItem {
+///     job: DownloadJob {
+///         sound_id,
+///         url: format!("https://cdn.freesound.org/previews/{sound_id}-hq.mp3"),
+///         destination: format!("{sound_id}.mp3").into(),
+///         source: DownloadSource::Preview(PreviewQuality::HqMp3),
+///         preview_url: None,
+///         md5: None,
+///     },
+///     status,
+///     bytes,
+///     duration: Duration::from_millis(300),
+///     error: (status == DownloadStatus::Failed).then(|| "HTTP 404".to_string()),
+///     loudness: None,
+/// };
+/// let report = DownloadReport {
+///     items: vec![
+///         item(1, DownloadStatus::Downloaded, 1000),
+///         item(2, DownloadStatus::Skipped, 500),
+///         item(3, DownloadStatus::Failed, 0),
+///     ],
+///     duration: Duration::from_millis(600),
+/// };
+/// assert_eq!(report.downloaded().count(), 1);
+/// assert_eq!(report.bytes(), 1000);
+/// assert!(!report.is_success());
+///
+/// let json = serde_json::to_string(&report).unwrap();
+/// assert_eq!(serde_json::from_str::<DownloadReport>(&json).unwrap(), report);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct DownloadReport {
+    /// Every job run, in completion order
+    pub items: Vec<DownloadItem>,
+    /// Time spent on the whole run
+    pub duration: Duration,
+}
+
+impl DownloadReport {
+    /// Returns the jobs whose file was downloaded
+    pub fn downloaded(&self) -> impl Iterator<Item = &DownloadItem> {
+        self.with_status(DownloadStatus::Downloaded)
+    }
+
+    /// Returns the jobs skipped because their file already existed
+    pub fn skipped(&self) -> impl Iterator<Item = &DownloadItem> {
+        self.with_status(DownloadStatus::Skipped)
+    }
+
+    /// Returns the jobs that failed
+    pub fn failed(&self) -> impl Iterator<Item = &DownloadItem> {
+        self.with_status(DownloadStatus::Failed)
+    }
+
+    /// Returns the number of bytes downloaded, skipped files excluded
+    pub fn bytes(&self) -> u64 {
+        self.downloaded().map(|item| item.bytes).sum()
+    }
+
+    /// Returns whether no job failed
+    pub fn is_success(&self) -> bool {
+        self.failed().next().is_none()
+    }
+
+    fn with_status(&self, status: DownloadStatus) -> impl Iterator<Item = &DownloadItem> {
+        self.items.iter().filter(move |item| item.status == status)
+    }
+}
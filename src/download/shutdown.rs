@@ -0,0 +1,87 @@
+use futures::future::AbortHandle;
+use std::sync::{Arc, Mutex};
+
+/// What to do with the running downloads when a [`DownloadManager`](super::DownloadManager) shuts down
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Drain {
+    /// Let the running downloads finish
+    FinishCurrent,
+    /// Abort the running downloads and remove their partial files
+    Abort,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    drain: Option<Drain>,
+    running: Vec<AbortHandle>,
+}
+
+/// Shuts a [`DownloadManager`](super::DownloadManager) down from another task
+///
+/// Once shut down, the manager starts no new job and accepts no new one. Jobs
+/// that were not started stay queued, and remain queued in the manifest so a
+/// later run can [`resume`](super::DownloadManager::resume) them. The outcomes
+/// of the finished (or aborted) jobs are still returned by
+/// [`run`](super::DownloadManager::run).
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownHandle {
+    state: Arc<Mutex<State>>,
+}
+
+impl ShutdownHandle {
+    /// Stop the manager
+    ///
+    /// Calling it again with [`Drain::Abort`] aborts the downloads that were
+    /// left to finish.
+    pub fn shutdown(&self, drain: Drain) {
+        let mut state = self.state.lock().unwrap();
+        if state.drain != Some(Drain::Abort) {
+            state.drain = Some(drain);
+        }
+        if drain == Drain::Abort {
+            for running in state.running.drain(..) {
+                running.abort();
+            }
+        }
+    }
+
+    /// Returns how the manager was shut down, if it was
+    pub fn drain(&self) -> Option<Drain> {
+        self.state.lock().unwrap().drain
+    }
+
+    /// Returns true once the manager was shut down
+    pub fn is_shutdown(&self) -> bool {
+        self.drain().is_some()
+    }
+
+    /// Stop the manager on Ctrl-C, letting the running downloads finish
+    ///
+    /// A second Ctrl-C aborts them. Needs the `tokio` feature and a running
+    /// tokio runtime, on which the signal is awaited; other runtimes can call
+    /// [`shutdown`](Self::shutdown) from their own signal handler.
+    #[cfg(feature = "tokio")]
+    pub fn shutdown_on_ctrl_c(&self) {
+        let handle = self.clone();
+        tokio::spawn(async move {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                if handle.is_shutdown() {
+                    handle.shutdown(Drain::Abort);
+                    break;
+                }
+                handle.shutdown(Drain::FinishCurrent);
+            }
+        });
+    }
+
+    /// Tracks a running download, aborting it right away if the manager is aborting
+    pub(crate) fn register(&self, handle: AbortHandle) {
+        let mut state = self.state.lock().unwrap();
+        if state.drain == Some(Drain::Abort) {
+            handle.abort();
+        } else {
+            state.running.retain(|h| !h.is_aborted());
+            state.running.push(handle);
+        }
+    }
+}
@@ -0,0 +1,259 @@
+use super::{DownloadJob, DownloadSource};
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Name of the index file of a [`ContentStore`], in its directory
+pub const STORE_INDEX_FILE: &str = "index.json";
+
+/// Directory of a [`ContentStore`] where files are written until they are hashed
+const INCOMING_DIR: &str = ".incoming";
+
+/// Lock file of the index, held while it is read, merged and replaced
+const INDEX_LOCK_FILE: &str = "index.json.lock";
+
+/// Number of incoming files named by this process, to keep their names unique
+static INCOMING_COUNT: AtomicU64 = AtomicU64::new(0);
+
+/// A file of a sound held by a [`ContentStore`]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct StoreEntry {
+    /// The identifier of the sound
+    pub sound_id: i32,
+    /// Which file of the sound this is
+    pub source: DownloadSource,
+    /// The MD5 digest of the file, naming it in the store
+    pub md5: String,
+    /// The extension of the file, such as `wav` or `mp3`, possibly empty
+    #[serde(default)]
+    pub extension: String,
+    /// The size of the file
+    pub bytes: u64,
+}
+
+/// A download directory where files are named after their MD5 digest
+///
+/// Each file is stored once as `<dir>/<first two digits>/<md5>.<extension>`,
+/// however many sounds or queries it was downloaded for, and the
+/// [`STORE_INDEX_FILE`] sidecar maps each sound and [`DownloadSource`] to its
+/// digest. Set on a [`DownloadManager`](super::DownloadManager) with
+/// [`store`](super::DownloadManager::store), it replaces the destinations of
+/// the jobs: files already in the store are not downloaded again.
+///
+/// Several processes can share a store directory: files are written under a
+/// temporary name and renamed into place, and the index is merged with the
+/// entries other processes added before it is replaced, under a lock on
+/// `index.json.lock`.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{ContentStore, DownloadSource, PreviewQuality};
+///
+/// let dir = std::env::temp_dir().join("freesound-rs-doctest-store");
+/// # let _ = std::fs::remove_dir_all(&dir);
+/// let store = ContentStore::open(&dir)?;
+///
+/// let file = dir.join("rain.mp3");
+/// std::fs::write(&file, b"not really an mp3")?;
+/// let source = DownloadSource::Preview(PreviewQuality::HqMp3);
+/// let entry = store.insert(1234, source, &file)?;
+/// assert_eq!(entry.md5, "4bcab6e211cce5e0105a9fd06b19cc70");
+/// assert_eq!(
+///     store.path_of(1234, source),
+///     Some(dir.join("4b").join("4bcab6e211cce5e0105a9fd06b19cc70.mp3"))
+/// );
+///
+/// // A reopened store finds it through its index
+/// assert_eq!(ContentStore::open(&dir)?.get(1234, source), Some(entry));
+/// # std::fs::remove_dir_all(&dir)?;
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug)]
+pub struct ContentStore {
+    dir: PathBuf,
+    index: Mutex<Vec<StoreEntry>>,
+}
+
+impl ContentStore {
+    /// Opens the store in `dir`, creating the directory if needed
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+        let index = read_index(&dir)?;
+        Ok(Self {
+            dir,
+            index: Mutex::new(index),
+        })
+    }
+
+    /// Returns the directory of the store
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns every entry of the index
+    pub fn entries(&self) -> Vec<StoreEntry> {
+        self.index.lock().unwrap().clone()
+    }
+
+    /// Returns the path of the file with the given digest and extension
+    pub fn object_path(&self, md5: &str, extension: &str) -> PathBuf {
+        let name = match extension.is_empty() {
+            true => md5.to_string(),
+            false => format!("{md5}.{extension}"),
+        };
+        self.dir.join(md5.get(..2).unwrap_or(md5)).join(name)
+    }
+
+    /// Returns the entry of a file of a sound, if the store holds it
+    ///
+    /// Entries whose file was removed from the store are ignored.
+    pub fn get(&self, sound_id: i32, source: DownloadSource) -> Option<StoreEntry> {
+        self.index
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|e| e.sound_id == sound_id && e.source == source)
+            .filter(|e| self.object_path(&e.md5, &e.extension).exists())
+            .cloned()
+    }
+
+    /// Returns the path of a file of a sound, if the store holds it
+    pub fn path_of(&self, sound_id: i32, source: DownloadSource) -> Option<PathBuf> {
+        self.get(sound_id, source)
+            .map(|e| self.object_path(&e.md5, &e.extension))
+    }
+
+    /// Move a file into the store, as the given file of a sound
+    ///
+    /// The file is hashed and renamed to its place in the store, or deleted if
+    /// the store already holds the same content. It must be on the same file
+    /// system as the store.
+    pub fn insert(&self, sound_id: i32, source: DownloadSource, file: &Path) -> Result<StoreEntry> {
+        let (md5, bytes) = hash_file(file)?;
+        let extension = extension(file);
+        let object = self.object_path(&md5, &extension);
+        if object.exists() {
+            std::fs::remove_file(file)?;
+        } else {
+            if let Some(parent) = object.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::rename(file, &object)?;
+        }
+        let entry = StoreEntry {
+            sound_id,
+            source,
+            md5,
+            extension,
+            bytes,
+        };
+        self.record(entry.clone())?;
+        Ok(entry)
+    }
+
+    /// Returns the entry of the file of a job, recording it if the store
+    /// already holds a file with the expected digest of the job
+    pub(crate) fn lookup(&self, job: &DownloadJob) -> Result<Option<StoreEntry>> {
+        if let Some(entry) = self.get(job.sound_id, job.source) {
+            return Ok(Some(entry));
+        }
+        let Some(md5) = &job.md5 else {
+            return Ok(None);
+        };
+        let md5 = md5.to_ascii_lowercase();
+        let extension = extension(&job.destination);
+        let Ok(metadata) = std::fs::metadata(self.object_path(&md5, &extension)) else {
+            return Ok(None);
+        };
+        let entry = StoreEntry {
+            sound_id: job.sound_id,
+            source: job.source,
+            md5,
+            extension,
+            bytes: metadata.len(),
+        };
+        self.record(entry.clone())?;
+        Ok(Some(entry))
+    }
+
+    /// Returns where the file of a job is written before being moved into the store
+    ///
+    /// Each call returns a new path, so jobs writing files of the same name at
+    /// once, in this process or another, do not share it.
+    pub(crate) fn incoming_path(&self, job: &DownloadJob) -> PathBuf {
+        let name = job
+            .destination
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let count = INCOMING_COUNT.fetch_add(1, Ordering::Relaxed);
+        self.dir
+            .join(INCOMING_DIR)
+            .join(format!("{}-{count}-{name}", std::process::id()))
+    }
+
+    /// Adds an entry to the index, keeping the ones other processes added
+    fn record(&self, entry: StoreEntry) -> Result<()> {
+        let mut index = self.index.lock().unwrap();
+        let lock = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(self.dir.join(INDEX_LOCK_FILE))?;
+        lock.lock()?;
+        for other in read_index(&self.dir)? {
+            if !index
+                .iter()
+                .any(|e| e.sound_id == other.sound_id && e.source == other.source)
+            {
+                index.push(other);
+            }
+        }
+        index.retain(|e| e.sound_id != entry.sound_id || e.source != entry.source);
+        index.push(entry);
+
+        let json = serde_json::to_vec_pretty(&*index)?;
+        let temporary = self
+            .dir
+            .join(format!("{STORE_INDEX_FILE}.{}.tmp", std::process::id()));
+        std::fs::write(&temporary, json)?;
+        std::fs::rename(&temporary, self.dir.join(STORE_INDEX_FILE))?;
+        Ok(())
+    }
+}
+
+fn read_index(dir: &Path) -> Result<Vec<StoreEntry>> {
+    match std::fs::read_to_string(dir.join(STORE_INDEX_FILE)) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn hash_file(path: &Path) -> Result<(String, u64)> {
+    let mut file = std::fs::File::open(path)?;
+    let mut digest = md5::Context::new();
+    let mut buffer = vec![0; 64 * 1024];
+    let mut bytes = 0;
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        digest.consume(&buffer[..read]);
+        bytes += read as u64;
+    }
+    Ok((format!("{:x}", digest.finalize()), bytes))
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
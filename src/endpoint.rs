@@ -0,0 +1,152 @@
+use crate::client::FreesoundClient;
+use crate::error::{FreesoundError, Result};
+use crate::inspect::{IntoRequest, PreparedRequest};
+use serde::de::DeserializeOwned;
+
+/// An API endpoint, described by its path template, method, parameters and
+/// response type
+///
+/// Endpoints the client does not wrap yet can be called in a typed way by
+/// implementing this trait and passing them by reference to
+/// [`FreesoundClient::execute`], which reads the typed response, or to
+/// [`FreesoundClient::prepare`] for inspection. Placeholders such as `{id}` in
+/// [`PATH`](Self::PATH) are replaced by the [`path_params`](Self::path_params),
+/// percent-encoded.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{Endpoint, FreesoundClient};
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Analysis {
+///     lowlevel: serde_json::Value,
+/// }
+///
+/// struct SoundAnalysis {
+///     id: i32,
+///     descriptors: Vec<String>,
+/// }
+///
+/// impl Endpoint for SoundAnalysis {
+///     type Response = Analysis;
+///     const PATH: &'static str = "sounds/{id}/analysis";
+///
+///     fn path_params(&self) -> Vec<(&'static str, String)> {
+///         vec![("id", self.id.to_string())]
+///     }
+///
+///     fn query(&self) -> Vec<(String, String)> {
+///         vec![("descriptors".to_string(), self.descriptors.join(","))]
+///     }
+/// }
+///
+/// let client = FreesoundClient::new("API_KEY".to_string(), None);
+/// let endpoint = SoundAnalysis { id: 1234, descriptors: vec!["lowlevel.mfcc".to_string()] };
+/// let request = client.prepare(&endpoint)?;
+/// assert_eq!(
+///     request.url().as_str(),
+///     "https://freesound.org/apiv2/sounds/1234/analysis?token=REDACTED&descriptors=lowlevel.mfcc"
+/// );
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+pub trait Endpoint {
+    /// The type the JSON response is read into
+    type Response: DeserializeOwned;
+
+    /// The path template, relative to the base URL, such as `sounds/{id}/comments`
+    const PATH: &'static str;
+
+    /// The HTTP method
+    const METHOD: reqwest::Method = reqwest::Method::GET;
+
+    /// Whether the endpoint needs an OAuth2 access token
+    const REQUIRES_OAUTH: bool = false;
+
+    /// Returns the values of the placeholders of the path template, by name
+    fn path_params(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    /// Returns the query parameters
+    fn query(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Returns the form fields sent as the body, if any
+    fn form(&self) -> Vec<(String, String)> {
+        Vec::new()
+    }
+
+    /// Returns the path, with the placeholders of the template replaced
+    ///
+    /// Fails with [`InvalidRequest`](FreesoundError::InvalidRequest) if a
+    /// placeholder has no value.
+    fn path(&self) -> Result<String> {
+        let params = self.path_params();
+        let mut path = String::with_capacity(Self::PATH.len());
+        let mut rest = Self::PATH;
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+                break;
+            };
+            let name = &rest[start + 1..end];
+            let value = params
+                .iter()
+                .find(|(param, _)| *param == name)
+                .map(|(_, value)| value)
+                .ok_or_else(|| {
+                    FreesoundError::InvalidRequest(format!(
+                        "no value for {{{name}}} in {}",
+                        Self::PATH
+                    ))
+                })?;
+            path.push_str(&rest[..start]);
+            path.push_str(&encode_segment(value));
+            rest = &rest[end + 1..];
+        }
+        path.push_str(rest);
+        Ok(path)
+    }
+}
+
+/// Percent-encodes a value for use as a path segment
+fn encode_segment(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Calls go through the same rate limiter, circuit breaker, journal and error
+/// handling as the wrapped endpoints
+impl<E: Endpoint> IntoRequest for &E {
+    type Output = E::Response;
+
+    fn into_request(self, client: &FreesoundClient) -> Result<PreparedRequest> {
+        if E::REQUIRES_OAUTH {
+            client.require_oauth(E::PATH)?;
+        }
+        let mut request = client.request(E::METHOD, &self.path()?);
+        let query = self.query();
+        if !query.is_empty() {
+            request = request.query(&query);
+        }
+        let form = self.form();
+        if !form.is_empty() {
+            request = request.form(&form);
+        }
+        request.into_request(client)
+    }
+
+    async fn read(client: &FreesoundClient, response: reqwest::Response) -> Result<E::Response> {
+        client.read_json(client.check_status(response).await?).await
+    }
+}
@@ -0,0 +1,93 @@
+use crate::client::DEFAULT_BASE_URL;
+use crate::error::{FreesoundError, Result};
+use std::fmt;
+use std::str::FromStr;
+use url::Url;
+
+/// The Freesound instance a client talks to
+///
+/// Custom URLs are checked when the client is built: they must be absolute
+/// `http` or `https` URLs without query or fragment. Trailing slashes are
+/// removed, so `https://example.org/apiv2/` and `https://example.org/apiv2`
+/// give the same requests.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{DEFAULT_BASE_URL, Environment, FreesoundClient};
+///
+/// let client = FreesoundClient::builder("API_KEY")
+///     .environment(Environment::custom("https://staging.example.org/apiv2/")?)
+///     .build()?;
+/// assert_eq!(client.base_url(), "https://staging.example.org/apiv2");
+///
+/// assert_eq!(Environment::Production.base_url()?, DEFAULT_BASE_URL);
+/// assert!(Environment::custom("staging.example.org").is_err());
+/// assert!(Environment::custom("ftp://example.org").is_err());
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Environment {
+    /// The public API at [`DEFAULT_BASE_URL`]
+    #[default]
+    Production,
+    /// Another instance, such as a mirror or a local mock server
+    Custom(Url),
+}
+
+impl Environment {
+    /// Parses and checks a custom base URL
+    pub fn custom(url: &str) -> Result<Self> {
+        let parsed = Url::parse(url)
+            .map_err(|e| FreesoundError::InvalidRequest(format!("invalid base URL {url}: {e}")))?;
+        let environment = Self::Custom(parsed);
+        environment.base_url()?;
+        Ok(environment)
+    }
+
+    /// Returns the normalized base URL, without trailing slash
+    ///
+    /// Fails if a [`Custom`](Self::Custom) URL is not a valid base URL.
+    pub fn base_url(&self) -> Result<String> {
+        let url = match self {
+            Self::Production => return Ok(DEFAULT_BASE_URL.to_string()),
+            Self::Custom(url) => url,
+        };
+        let invalid = |reason: &str| {
+            Err(FreesoundError::InvalidRequest(format!(
+                "invalid base URL {url}: {reason}"
+            )))
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            return invalid("the scheme must be http or https");
+        }
+        if url.host().is_none() {
+            return invalid("a host is required");
+        }
+        if url.query().is_some() || url.fragment().is_some() {
+            return invalid("query and fragment are not allowed");
+        }
+        Ok(url.as_str().trim_end_matches('/').to_string())
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Production => f.write_str(DEFAULT_BASE_URL),
+            Self::Custom(url) => write!(f, "{url}"),
+        }
+    }
+}
+
+impl FromStr for Environment {
+    type Err = FreesoundError;
+
+    /// Parses `production` or a custom base URL
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "production" => Ok(Self::Production),
+            url => Self::custom(url),
+        }
+    }
+}
@@ -1,6 +1,68 @@
+use std::fmt;
 use thiserror::Error;
 
+/// Where an error happened: the endpoint and key parameters of its request
+///
+/// See [`FreesoundError::context`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// The endpoint, such as `sounds/{id}`, or the host of a media file
+    pub endpoint: String,
+    /// The sound the request was about, if any
+    pub sound_id: Option<i32>,
+    /// The page requested, if any
+    pub page: Option<i32>,
+}
+
+impl ErrorContext {
+    /// Describes the request to `url`, made by a client using `base_url`
+    pub(crate) fn new(base_url: &str, url: &reqwest::Url) -> Self {
+        let sound_id = url.path_segments().and_then(|mut segments| {
+            segments.by_ref().find(|segment| *segment == "sounds")?;
+            segments.next()?.parse().ok()
+        });
+        let page = url
+            .query_pairs()
+            .find(|(k, _)| k == "page")
+            .and_then(|(_, v)| v.parse().ok());
+        Self {
+            endpoint: crate::usage::endpoint_name(base_url, url),
+            sound_id,
+            page,
+        }
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.endpoint)?;
+        if let Some(sound_id) = self.sound_id {
+            write!(f, ", sound {sound_id}")?;
+        }
+        if let Some(page) = self.page {
+            write!(f, ", page {page}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Errors of the crate
+///
+/// Errors raised by a request come wrapped in
+/// [`WithContext`](Self::WithContext), so matching the variants directly
+/// misses them: match on [`kind`](Self::kind), or on [`root`](Self::root) to
+/// read the fields of the error. New variants may be added, so matches need a
+/// wildcard arm.
+///
+/// # Migrating from 0.1
+///
+/// Code written as `Err(FreesoundError::SoundDeleted(id))` must now read
+/// `Err(e) if e.kind() == ErrorKind::SoundDeleted`, or match on
+/// `e.root()` when it needs `id`. Malformed JSON, whether in a response or in
+/// a file the crate reads back, is a [`JsonError`](Self::JsonError) rather
+/// than an [`IoError`](Self::IoError).
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum FreesoundError {
     #[error("HTTP request failed: {0}")]
     RequestError(#[from] reqwest::Error),
@@ -8,6 +70,287 @@ pub enum FreesoundError {
     AuthError(String),
     #[error("API error: {0}")]
     ApiError(String),
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Circuit open: {0}")]
+    CircuitOpen(String),
+    #[error("OAuth2 required: {0}")]
+    RequiresOAuth(String),
+    #[error("Pagination limit: pages after {max_reachable} cannot be fetched")]
+    PaginationLimit { max_reachable: i32 },
+    #[error("Decode error: {0}")]
+    DecodeError(String),
+    #[error(
+        "Insufficient space in {}: {available} bytes available, {required} required",
+        path.display()
+    )]
+    InsufficientSpace {
+        path: std::path::PathBuf,
+        available: u64,
+        required: u64,
+    },
+    #[error("Checksum mismatch for {}: expected {expected}, got {actual}", path.display())]
+    ChecksumMismatch {
+        path: std::path::PathBuf,
+        expected: String,
+        actual: String,
+    },
+    #[error("Rejected: {0}")]
+    Rejected(String),
+    #[error("Sound {0} was deleted")]
+    SoundDeleted(i32),
+    #[error("Sound {0} is not processed yet")]
+    SoundProcessing(i32),
+    #[error("Already exists: {0}")]
+    AlreadyExists(String),
+    #[error("Response too large: more than {limit} bytes")]
+    ResponseTooLarge { limit: u64 },
+    /// An error raised by a request, with the endpoint and parameters of the
+    /// request; match on [`root`](Self::root) to look through it
+    #[error("{error} ({context})")]
+    WithContext {
+        context: ErrorContext,
+        error: Box<FreesoundError>,
+    },
+}
+
+/// The kind of a [`FreesoundError`], whether it has a context or not
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{ErrorKind, FreesoundError};
+///
+/// fn should_skip(error: &FreesoundError) -> bool {
+///     matches!(error.kind(), ErrorKind::SoundDeleted | ErrorKind::SoundProcessing)
+/// }
+/// assert!(should_skip(&FreesoundError::SoundDeleted(1234)));
+/// assert!(!should_skip(&FreesoundError::ApiError("bad gateway".into())));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// [`FreesoundError::RequestError`]
+    Request,
+    /// [`FreesoundError::AuthError`]
+    Auth,
+    /// [`FreesoundError::ApiError`]
+    Api,
+    /// [`FreesoundError::InvalidQuery`]
+    InvalidQuery,
+    /// [`FreesoundError::InvalidRequest`]
+    InvalidRequest,
+    /// [`FreesoundError::IoError`]
+    Io,
+    /// [`FreesoundError::JsonError`]
+    Json,
+    /// [`FreesoundError::CircuitOpen`]
+    CircuitOpen,
+    /// [`FreesoundError::RequiresOAuth`]
+    RequiresOAuth,
+    /// [`FreesoundError::PaginationLimit`]
+    PaginationLimit,
+    /// [`FreesoundError::DecodeError`]
+    Decode,
+    /// [`FreesoundError::InsufficientSpace`]
+    InsufficientSpace,
+    /// [`FreesoundError::ChecksumMismatch`]
+    ChecksumMismatch,
+    /// [`FreesoundError::Rejected`]
+    Rejected,
+    /// [`FreesoundError::SoundDeleted`]
+    SoundDeleted,
+    /// [`FreesoundError::SoundProcessing`]
+    SoundProcessing,
+    /// [`FreesoundError::AlreadyExists`]
+    AlreadyExists,
+    /// [`FreesoundError::ResponseTooLarge`]
+    ResponseTooLarge,
+}
+
+impl FreesoundError {
+    /// Returns the kind of the error, looking through its context
+    pub fn kind(&self) -> ErrorKind {
+        match self.root() {
+            Self::RequestError(_) => ErrorKind::Request,
+            Self::AuthError(_) => ErrorKind::Auth,
+            Self::ApiError(_) => ErrorKind::Api,
+            Self::InvalidQuery(_) => ErrorKind::InvalidQuery,
+            Self::InvalidRequest(_) => ErrorKind::InvalidRequest,
+            Self::IoError(_) => ErrorKind::Io,
+            Self::JsonError(_) => ErrorKind::Json,
+            Self::CircuitOpen(_) => ErrorKind::CircuitOpen,
+            Self::RequiresOAuth(_) => ErrorKind::RequiresOAuth,
+            Self::PaginationLimit { .. } => ErrorKind::PaginationLimit,
+            Self::DecodeError(_) => ErrorKind::Decode,
+            Self::InsufficientSpace { .. } => ErrorKind::InsufficientSpace,
+            Self::ChecksumMismatch { .. } => ErrorKind::ChecksumMismatch,
+            Self::Rejected(_) => ErrorKind::Rejected,
+            Self::SoundDeleted(_) => ErrorKind::SoundDeleted,
+            Self::SoundProcessing(_) => ErrorKind::SoundProcessing,
+            Self::AlreadyExists(_) => ErrorKind::AlreadyExists,
+            Self::ResponseTooLarge { .. } => ErrorKind::ResponseTooLarge,
+            Self::WithContext { .. } => unreachable!("root() looks through contexts"),
+        }
+    }
+
+    /// Returns the error without its context
+    ///
+    /// Errors raised by a request are wrapped in
+    /// [`WithContext`](Self::WithContext); this is the error to match on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::FreesoundError;
+    ///
+    /// fn is_deleted(error: &FreesoundError) -> bool {
+    ///     matches!(error.root(), FreesoundError::SoundDeleted(_))
+    /// }
+    /// assert!(is_deleted(&FreesoundError::SoundDeleted(1234)));
+    /// ```
+    pub fn root(&self) -> &FreesoundError {
+        match self {
+            Self::WithContext { error, .. } => error.root(),
+            _ => self,
+        }
+    }
+
+    /// Returns the error without its context, by value
+    pub fn into_root(self) -> FreesoundError {
+        match self {
+            Self::WithContext { error, .. } => error.into_root(),
+            _ => self,
+        }
+    }
+
+    /// Returns the endpoint and parameters of the request that failed, if any
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::WithContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns the endpoint of the request that failed, such as `sounds/{id}`
+    pub fn endpoint(&self) -> Option<&str> {
+        self.context().map(|context| context.endpoint.as_str())
+    }
+
+    /// Returns the sound the error is about, if any
+    pub fn sound_id(&self) -> Option<i32> {
+        match self.root() {
+            Self::SoundDeleted(id) | Self::SoundProcessing(id) => Some(*id),
+            _ => self.context().and_then(|context| context.sound_id),
+        }
+    }
+
+    /// Returns the page requested when the error happened, if any
+    pub fn page(&self) -> Option<i32> {
+        self.context().and_then(|context| context.page)
+    }
+
+    /// Returns true if the instance could not serve the request at all
+    ///
+    /// Connection failures, timeouts, server errors (5xx), rate limiting (429)
+    /// and open circuits are such errors; the same request may succeed on
+    /// another instance or later.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::FreesoundError;
+    ///
+    /// assert!(FreesoundError::ApiError("API request failed: 503 Service Unavailable - ".into()).is_unavailable());
+    /// assert!(!FreesoundError::SoundDeleted(1234).is_unavailable());
+    /// ```
+    pub fn is_unavailable(&self) -> bool {
+        match self.root() {
+            Self::RequestError(e) => e.is_timeout() || e.is_connect(),
+            Self::ApiError(message) => {
+                message.starts_with("API request failed: 5")
+                    || message.starts_with("API request failed: 429")
+            }
+            Self::CircuitOpen(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Attaches the context of a request, unless the error already has one
+    pub(crate) fn with_context(self, context: ErrorContext) -> Self {
+        match self {
+            Self::WithContext { .. } => self,
+            error => Self::WithContext {
+                context,
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// Tells deleted sounds from sounds still processed, for requests about a sound
+    ///
+    /// Freesound answers 404 in both cases; the message of sounds not processed
+    /// or moderated yet says so. Only the 404s of the sound itself and of its
+    /// download are concerned: those of other endpoints, such as `similar/`,
+    /// or of the media hosts say nothing about the sound.
+    pub(crate) fn for_sound(self, sound_id: i32) -> Self {
+        let about_sound = self
+            .endpoint()
+            .is_some_and(|endpoint| matches!(endpoint, "sounds/{id}" | "sounds/{id}/download"));
+        if !about_sound {
+            return self;
+        }
+        self.map_root(|error| match &error {
+            Self::ApiError(message) if message.starts_with("API request failed: 404") => {
+                let message = message.to_lowercase();
+                if message.contains("process") || message.contains("moderat") {
+                    Self::SoundProcessing(sound_id)
+                } else {
+                    Self::SoundDeleted(sound_id)
+                }
+            }
+            _ => error,
+        })
+    }
+
+    /// Tells writes already done from other refusals, for write requests
+    ///
+    /// Freesound answers 409 to a sound rated twice, and says the resource
+    /// already exists for some other refused writes, so a retried write fails
+    /// with [`AlreadyExists`](Self::AlreadyExists) instead of posting twice.
+    pub(crate) fn for_write(self) -> Self {
+        self.map_root(|error| match &error {
+            Self::ApiError(message)
+                if message.starts_with("API request failed: 409")
+                    || (message.starts_with("API request failed: 4")
+                        && message.to_lowercase().contains("already")) =>
+            {
+                let body = message.split_once(" - ").map_or("", |(_, body)| body);
+                let detail = serde_json::from_str::<serde_json::Value>(body)
+                    .ok()
+                    .and_then(|body| Some(body.get("detail")?.as_str()?.to_string()));
+                Self::AlreadyExists(detail.unwrap_or_else(|| body.to_string()))
+            }
+            _ => error,
+        })
+    }
+
+    /// Replaces the error under the context, keeping the context
+    fn map_root(self, f: impl FnOnce(Self) -> Self) -> Self {
+        match self {
+            Self::WithContext { context, error } => Self::WithContext {
+                context,
+                error: Box::new(error.map_root(f)),
+            },
+            error => f(error),
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, FreesoundError>;
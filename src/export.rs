@@ -0,0 +1,74 @@
+//! Export of results to formats other tools read
+//!
+//! [`to_geojson`] turns geotagged sounds into a GeoJSON `FeatureCollection`,
+//! which web maps such as Leaflet or OpenLayers can plot directly.
+
+use crate::models::Sound;
+use serde_json::{Map, Value, json};
+
+/// Builds a GeoJSON `FeatureCollection` of the geotagged sounds
+///
+/// Each sound becomes a `Point` feature whose id is the sound id. Its
+/// properties hold the name, username, license, tags, duration and page URL of
+/// the sound, and its preview URLs under their API names (`preview-hq-mp3`...)
+/// when it was fetched with its `previews` field. Sounds without a geotag are
+/// left out.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::Sound;
+/// use freesound_rs::export::to_geojson;
+///
+/// let sounds = [
+///     Sound { id: 1, name: "Market".to_string(), geotag: Some("41.38 2.17".to_string()), ..Default::default() },
+///     Sound { id: 2, name: "Studio".to_string(), geotag: None, ..Default::default() },
+/// ];
+/// let geojson = to_geojson(&sounds);
+///
+/// assert_eq!(geojson["type"], "FeatureCollection");
+/// let features = geojson["features"].as_array().unwrap();
+/// assert_eq!(features.len(), 1);
+/// assert_eq!(features[0]["id"], 1);
+/// // GeoJSON puts the longitude first
+/// assert_eq!(features[0]["geometry"]["coordinates"], serde_json::json!([2.17, 41.38]));
+/// assert_eq!(features[0]["properties"]["name"], "Market");
+/// ```
+pub fn to_geojson(sounds: &[Sound]) -> Value {
+    let features: Vec<Value> = sounds
+        .iter()
+        .filter_map(|sound| {
+            let (latitude, longitude) = sound.coordinates()?;
+            Some(json!({
+                "type": "Feature",
+                "id": sound.id,
+                "geometry": {
+                    "type": "Point",
+                    "coordinates": [longitude, latitude],
+                },
+                "properties": properties(sound),
+            }))
+        })
+        .collect();
+
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+fn properties(sound: &Sound) -> Value {
+    let mut properties = Map::new();
+    properties.insert("name".to_string(), json!(sound.name));
+    properties.insert("username".to_string(), json!(sound.username));
+    properties.insert("license".to_string(), json!(sound.license));
+    properties.insert("tags".to_string(), json!(sound.tags));
+    properties.insert("duration".to_string(), json!(sound.duration));
+    properties.insert("url".to_string(), json!(sound.url));
+    if let Some(previews) = &sound.previews
+        && let Value::Object(previews) = json!(previews)
+    {
+        properties.extend(previews);
+    }
+    Value::Object(properties)
+}
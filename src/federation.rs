@@ -0,0 +1,183 @@
+use crate::client::FreesoundClient;
+use crate::error::{FreesoundError, Result};
+use crate::models::{GetSoundOptions, SearchResponse, Sound, SoundListOptions};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+
+/// When a [`Federation`] moves on to its next instance
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fallback {
+    /// When an instance is unavailable (see [`FreesoundError::is_unavailable`])
+    #[default]
+    Unavailable,
+    /// Also when an instance does not hold the requested sound, for partial mirrors
+    Missing,
+    /// On any error
+    Always,
+}
+
+impl Fallback {
+    /// Returns true if a call failing with `error` is tried on the next instance
+    pub fn applies_to(self, error: &FreesoundError) -> bool {
+        match self {
+            Self::Unavailable => error.is_unavailable(),
+            Self::Missing => {
+                error.is_unavailable()
+                    || match error.root() {
+                        FreesoundError::SoundDeleted(_) | FreesoundError::SoundProcessing(_) => {
+                            true
+                        }
+                        FreesoundError::ApiError(message) => {
+                            message.starts_with("API request failed: 404")
+                        }
+                        _ => false,
+                    }
+            }
+            Self::Always => true,
+        }
+    }
+}
+
+impl fmt::Display for Fallback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Unavailable => "unavailable",
+            Self::Missing => "missing",
+            Self::Always => "always",
+        })
+    }
+}
+
+impl FromStr for Fallback {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "unavailable" => Ok(Self::Unavailable),
+            "missing" => Ok(Self::Missing),
+            "always" => Ok(Self::Always),
+            other => Err(format!("unknown fallback: {other}")),
+        }
+    }
+}
+
+/// Several Freesound instances, such as a private mirror and freesound.org,
+/// tried in order of preference
+///
+/// Each instance is a [`FreesoundClient`] with its own base URL and
+/// credentials (see [`FreesoundClient::builder`]). Calls go to the first
+/// instance, and move on to the next one when they fail with an error the
+/// [`Fallback`] covers; the error of the last instance tried is returned.
+/// Instances with a [circuit breaker](FreesoundClient::with_circuit_breaker)
+/// that opened are skipped at once.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{Environment, Federation, FreesoundClient, FreesoundError};
+///
+/// let mirror = FreesoundClient::builder("MIRROR_KEY")
+///     .environment(Environment::custom("https://freesound.example.edu/apiv2")?)
+///     .build()?;
+/// let public = FreesoundClient::new("API_KEY".to_string(), None);
+/// let federation = Federation::new()
+///     .instance("mirror", mirror)
+///     .instance("freesound.org", public);
+///
+/// // A call failing on the mirror is tried on freesound.org
+/// let served_by = futures::executor::block_on(federation.call(|client| async move {
+///     match client.base_url().contains("example.edu") {
+///         true => Err(FreesoundError::CircuitOpen("mirror down".to_string())),
+///         false => Ok(client.base_url().to_string()),
+///     }
+/// }))?;
+/// assert_eq!(served_by, "https://freesound.org/apiv2");
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Federation {
+    instances: Vec<(String, FreesoundClient)>,
+    fallback: Fallback,
+}
+
+impl Federation {
+    /// Create a federation without instances, falling back on unavailable ones
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an instance, tried after the ones added before
+    pub fn instance<S: Into<String>>(mut self, name: S, client: FreesoundClient) -> Self {
+        self.instances.push((name.into(), client));
+        self
+    }
+
+    /// Set when calls move on to the next instance
+    pub fn fallback(mut self, fallback: Fallback) -> Self {
+        self.fallback = fallback;
+        self
+    }
+
+    /// Returns the instances with their names, in order of preference
+    pub fn instances(&self) -> impl Iterator<Item = (&str, &FreesoundClient)> {
+        self.instances
+            .iter()
+            .map(|(name, client)| (name.as_str(), client))
+    }
+
+    /// Returns the preferred instance, if any
+    pub fn primary(&self) -> Option<&FreesoundClient> {
+        self.instances.first().map(|(_, client)| client)
+    }
+
+    /// Run a call on the instances, in order, until one succeeds
+    ///
+    /// `call` is given a clone of the client of each instance tried. Fails with
+    /// [`InvalidRequest`](FreesoundError::InvalidRequest) if the federation has
+    /// no instance.
+    pub async fn call<T, F, Fut>(&self, call: F) -> Result<T>
+    where
+        F: Fn(FreesoundClient) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let Some(last) = self.instances.len().checked_sub(1) else {
+            return Err(FreesoundError::InvalidRequest(
+                "the federation has no instance".to_string(),
+            ));
+        };
+        for (i, (name, client)) in self.instances.iter().enumerate() {
+            match call(client.clone()).await {
+                Err(e) if i < last && self.fallback.applies_to(&e) => {
+                    log::warn!("Instance {name} failed, trying the next one: {e}");
+                }
+                result => return result,
+            }
+        }
+        unreachable!("the last instance returns")
+    }
+
+    /// Get a sound from the first instance serving it
+    pub async fn get_sound(&self, sound_id: i32, options: &GetSoundOptions) -> Result<Sound> {
+        self.call(|client| async move { client.get_sound(sound_id, options).await })
+            .await
+    }
+
+    /// Search for sounds on the first instance answering
+    pub async fn search(&self, query: &[(String, String)]) -> Result<SearchResponse> {
+        self.call(|client| async move { client.search(query).await })
+            .await
+    }
+
+    /// List the sounds similar to a sound, from the first instance serving it
+    pub async fn get_similar_sounds(
+        &self,
+        sound_id: i32,
+        options: &SoundListOptions,
+    ) -> Result<SearchResponse> {
+        self.call(|client| async move { client.get_similar_sounds(sound_id, options).await })
+            .await
+    }
+}
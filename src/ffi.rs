@@ -0,0 +1,304 @@
+//! A C ABI over the client, for embedding the library in other languages
+//!
+//! Needs the `ffi` feature. A client is an opaque [`FreesoundHandle`], created
+//! with [`freesound_client_new`] and released with [`freesound_client_free`].
+//! Requests take their parameters as C strings and write their result as a
+//! JSON string to an out-pointer, to be released with
+//! [`freesound_string_free`]. They return [`FREESOUND_OK`], or a status
+//! whose JSON result is an object such as `{"error": "...", "sound_id": 1234}`.
+//!
+//! Calls block until the request completes, on a runtime owned by the handle;
+//! they must not be made from an audio thread. A handle can be shared between
+//! threads. `include/freesound.h` declares these functions for C and C++.
+//!
+//! # Examples
+//!
+//! ```
+//! use freesound_rs::ffi::*;
+//! use std::ffi::{CStr, c_char};
+//! use std::ptr;
+//!
+//! unsafe {
+//!     let client = freesound_client_new(c"API_KEY".as_ptr(), ptr::null());
+//!     assert!(!client.is_null());
+//!
+//!     let mut out: *mut c_char = ptr::null_mut();
+//!     let status = freesound_search(client, c"not json".as_ptr(), &mut out);
+//!     assert_eq!(status, FREESOUND_INVALID_ARGUMENT);
+//!     let error = CStr::from_ptr(out).to_str().unwrap();
+//!     assert!(error.starts_with(r#"{"error":"#));
+//!
+//!     freesound_string_free(out);
+//!     freesound_client_free(client);
+//! }
+//! ```
+
+use crate::client::FreesoundClient;
+use crate::error::FreesoundError;
+use crate::models::{GetSoundOptions, PreviewQuality};
+use serde_json::{Value, json};
+use std::ffi::{CStr, CString, c_char, c_int};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+
+/// The call succeeded and its result is the requested JSON value
+pub const FREESOUND_OK: c_int = 0;
+
+/// The request failed; the result holds the error
+pub const FREESOUND_ERROR: c_int = 1;
+
+/// An argument is null, not UTF-8 or not valid; the result holds the error
+pub const FREESOUND_INVALID_ARGUMENT: c_int = 2;
+
+/// The library panicked; the result holds the panic message if it has one
+pub const FREESOUND_PANIC: c_int = 3;
+
+/// A client and the runtime its requests run on, opaque to C
+pub struct FreesoundHandle {
+    runtime: tokio::runtime::Runtime,
+    client: FreesoundClient,
+}
+
+/// Why a call failed, before it is written as JSON
+enum Failure {
+    Invalid(String),
+    Request(FreesoundError),
+}
+
+impl From<FreesoundError> for Failure {
+    fn from(error: FreesoundError) -> Self {
+        Self::Request(error)
+    }
+}
+
+/// Creates a client using `api_key`, and `access_token` if it is not null
+///
+/// Returns null if an argument is not valid UTF-8 or the runtime cannot be
+/// started.
+///
+/// # Safety
+///
+/// `api_key` must be a valid C string; `access_token` must be null or a valid
+/// C string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn freesound_client_new(
+    api_key: *const c_char,
+    access_token: *const c_char,
+) -> *mut FreesoundHandle {
+    let handle = catch_unwind(|| {
+        // SAFETY: the caller passes valid C strings or null
+        let api_key = unsafe { string(api_key) }.ok()?;
+        let access_token = if access_token.is_null() {
+            None
+        } else {
+            // SAFETY: as above
+            Some(unsafe { string(access_token) }.ok()?)
+        };
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .ok()?;
+        let mut client = FreesoundClient::new(api_key, None);
+        if let Some(token) = access_token {
+            client = client.with_access_token(token);
+        }
+        Some(Box::new(FreesoundHandle { runtime, client }))
+    });
+    match handle {
+        Ok(Some(handle)) => Box::into_raw(handle),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Releases a client; null is ignored
+///
+/// # Safety
+///
+/// `handle` must be null or come from [`freesound_client_new`], and must not
+/// be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn freesound_client_free(handle: *mut FreesoundHandle) {
+    if !handle.is_null() {
+        // SAFETY: the handle comes from `Box::into_raw` and is released once
+        drop(unsafe { Box::from_raw(handle) });
+    }
+}
+
+/// Searches for sounds, writing the first page of results
+///
+/// `query_json` is an object of search parameters, such as
+/// `{"query": "piano", "filter": "duration:[1 TO 5]", "page_size": 20}`.
+///
+/// # Safety
+///
+/// `handle` must come from [`freesound_client_new`], `query_json` must be a
+/// valid C string and `out_json` a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn freesound_search(
+    handle: *const FreesoundHandle,
+    query_json: *const c_char,
+    out_json: *mut *mut c_char,
+) -> c_int {
+    // SAFETY: the caller passes valid pointers
+    unsafe {
+        call(handle, out_json, |handle| {
+            let query = parameters(&string(query_json)?)?;
+            let page = handle.runtime.block_on(handle.client.search(&query))?;
+            Ok(json!(page))
+        })
+    }
+}
+
+/// Gets the details of a sound
+///
+/// # Safety
+///
+/// `handle` must come from [`freesound_client_new`] and `out_json` must be a
+/// valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn freesound_get_sound(
+    handle: *const FreesoundHandle,
+    sound_id: i32,
+    out_json: *mut *mut c_char,
+) -> c_int {
+    // SAFETY: the caller passes valid pointers
+    unsafe {
+        call(handle, out_json, |handle| {
+            let sound = handle
+                .runtime
+                .block_on(handle.client.get_sound(sound_id, &GetSoundOptions::new()))?;
+            Ok(json!(sound))
+        })
+    }
+}
+
+/// Downloads a file of a sound to `path`
+///
+/// `source` is `original`, which needs an access token, or a preview quality
+/// such as `hq-mp3`. The result is `{"path": "...", "bytes": 1234}`.
+///
+/// # Safety
+///
+/// `handle` must come from [`freesound_client_new`], `source` and `path` must
+/// be valid C strings and `out_json` a valid pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn freesound_download(
+    handle: *const FreesoundHandle,
+    sound_id: i32,
+    source: *const c_char,
+    path: *const c_char,
+    out_json: *mut *mut c_char,
+) -> c_int {
+    // SAFETY: the caller passes valid pointers
+    unsafe {
+        call(handle, out_json, |handle| {
+            let source = string(source)?;
+            let path = string(path)?;
+            let client = &handle.client;
+            let bytes = if source == "original" {
+                handle
+                    .runtime
+                    .block_on(client.download_original(sound_id, &path))?
+            } else {
+                let quality: PreviewQuality = source.parse().map_err(Failure::Invalid)?;
+                handle.runtime.block_on(async {
+                    let sound = client.get_sound(sound_id, &GetSoundOptions::new()).await?;
+                    client.download_preview(&sound, quality, &path).await
+                })?
+            };
+            Ok(json!({ "path": path, "bytes": bytes }))
+        })
+    }
+}
+
+/// Releases a string written by the library; null is ignored
+///
+/// # Safety
+///
+/// `string` must be null or come from the library, and must not be used
+/// afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn freesound_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: the string comes from `CString::into_raw` and is released once
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+/// Runs `f` on the handle, writing its result or error to `out_json`
+///
+/// # Safety
+///
+/// `handle` must be null or come from [`freesound_client_new`]; `out_json`
+/// must be null or a valid pointer.
+unsafe fn call(
+    handle: *const FreesoundHandle,
+    out_json: *mut *mut c_char,
+    f: impl FnOnce(&FreesoundHandle) -> std::result::Result<Value, Failure>,
+) -> c_int {
+    if out_json.is_null() {
+        return FREESOUND_INVALID_ARGUMENT;
+    }
+    // SAFETY: the caller passes a valid handle or null
+    let outcome = match unsafe { handle.as_ref() } {
+        Some(handle) => catch_unwind(AssertUnwindSafe(|| f(handle))),
+        None => Ok(Err(Failure::Invalid("null client handle".to_string()))),
+    };
+    let (status, value) = match outcome {
+        Ok(Ok(value)) => (FREESOUND_OK, value),
+        Ok(Err(Failure::Invalid(message))) => {
+            (FREESOUND_INVALID_ARGUMENT, json!({ "error": message }))
+        }
+        Ok(Err(Failure::Request(error))) => (
+            FREESOUND_ERROR,
+            json!({ "error": error.to_string(), "sound_id": error.sound_id() }),
+        ),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "panic".to_string());
+            (FREESOUND_PANIC, json!({ "error": message }))
+        }
+    };
+    // JSON escapes control characters, so the text has no NUL byte
+    let text = CString::new(value.to_string()).unwrap_or_default();
+    // SAFETY: checked not to be null above
+    unsafe { *out_json = text.into_raw() };
+    status
+}
+
+/// Reads a C string argument
+///
+/// # Safety
+///
+/// `s` must be null or a valid C string.
+unsafe fn string(s: *const c_char) -> std::result::Result<String, Failure> {
+    if s.is_null() {
+        return Err(Failure::Invalid("null string argument".to_string()));
+    }
+    // SAFETY: the caller passes a valid C string
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map(str::to_string)
+        .map_err(|_| Failure::Invalid("string argument is not UTF-8".to_string()))
+}
+
+/// Turns a JSON object into query parameters, numbers and booleans as text
+fn parameters(query_json: &str) -> std::result::Result<Vec<(String, String)>, Failure> {
+    let invalid = || Failure::Invalid("query must be a JSON object".to_string());
+    let Ok(Value::Object(object)) = serde_json::from_str(query_json) else {
+        return Err(invalid());
+    };
+    object
+        .into_iter()
+        .map(|(key, value)| match value {
+            Value::String(s) => Ok((key, s)),
+            Value::Number(_) | Value::Bool(_) => Ok((key, value.to_string())),
+            _ => Err(Failure::Invalid(format!(
+                "query parameter '{key}' must be a string, number or boolean"
+            ))),
+        })
+        .collect()
+}
@@ -0,0 +1,259 @@
+use crate::client::FreesoundClient;
+use crate::error::{FreesoundError, Result};
+use crate::models::{SearchQueryBuilder, Sound};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use std::collections::HashSet;
+use std::f64::consts::PI;
+use std::fmt;
+
+/// Fields returned by [`FreesoundClient::sounds_in_bbox`], enough to plot and play a sound
+pub const SOUNDMAP_FIELDS: [&str; 7] = [
+    "id", "name", "username", "license", "duration", "geotag", "previews",
+];
+
+/// Largest number of tiles [`FreesoundClient::sounds_in_bbox`] queries for one box
+pub const SOUNDMAP_MAX_TILES: usize = 16;
+
+/// Zoom level from which [`FreesoundClient::sounds_in_bbox`] fetches every page
+pub const SOUNDMAP_FULL_ZOOM: u8 = 12;
+
+/// Highest latitude covered by web map tiles
+const MERCATOR_MAX_LATITUDE: f64 = 85.051_128_779_806_59;
+
+/// An area between two parallels and two meridians, in degrees
+///
+/// A box whose `west` edge is greater than its `east` edge crosses the
+/// antimeridian.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::BoundingBox;
+///
+/// let barcelona = BoundingBox::new(41.32, 2.05, 41.47, 2.23)?;
+/// assert!(barcelona.contains(41.38, 2.17));
+/// assert_eq!(barcelona.filter(), "geotag:\"Intersects(2.05 41.32 2.23 41.47)\"");
+///
+/// // At zoom 13, the box spans 3 by 3 tiles of 512 pixels
+/// let tiles = barcelona.tiles(13);
+/// assert_eq!(tiles.len(), 9);
+/// assert!(tiles.iter().all(|tile| tile.south >= 41.32 && tile.east <= 2.23));
+///
+/// assert!(BoundingBox::new(50.0, 0.0, 40.0, 1.0).is_err());
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    /// Southern latitude
+    pub south: f64,
+    /// Western longitude
+    pub west: f64,
+    /// Northern latitude
+    pub north: f64,
+    /// Eastern longitude
+    pub east: f64,
+}
+
+impl BoundingBox {
+    /// Creates a box, checking that its edges are valid coordinates
+    pub fn new(south: f64, west: f64, north: f64, east: f64) -> Result<Self> {
+        let latitude = -90.0..=90.0;
+        let longitude = -180.0..=180.0;
+        if !latitude.contains(&south) || !latitude.contains(&north) || south > north {
+            return Err(FreesoundError::InvalidQuery(format!(
+                "invalid latitudes for a bounding box: {south} to {north}"
+            )));
+        }
+        if !longitude.contains(&west) || !longitude.contains(&east) {
+            return Err(FreesoundError::InvalidQuery(format!(
+                "invalid longitudes for a bounding box: {west} to {east}"
+            )));
+        }
+        Ok(Self {
+            south,
+            west,
+            north,
+            east,
+        })
+    }
+
+    /// Returns true if the point is inside the box or on its edges
+    pub fn contains(&self, latitude: f64, longitude: f64) -> bool {
+        (self.south..=self.north).contains(&latitude)
+            && self
+                .halves()
+                .iter()
+                .any(|half| (half.west..=half.east).contains(&longitude))
+    }
+
+    /// Returns the search filter matching the sounds geotagged in the box
+    pub fn filter(&self) -> String {
+        let filters: Vec<String> = self
+            .halves()
+            .iter()
+            .map(|half| {
+                format!(
+                    "geotag:\"Intersects({} {} {} {})\"",
+                    half.west, half.south, half.east, half.north
+                )
+            })
+            .collect();
+        match filters.as_slice() {
+            [filter] => filter.clone(),
+            filters => format!("({})", filters.join(" OR ")),
+        }
+    }
+
+    /// Splits the box along the 512 pixel web map tiles of a zoom level
+    ///
+    /// The tiles are those of zoom level `zoom - 1` in the usual web mercator
+    /// grid, clipped to the box, row by row from the north-west. Tiles touching
+    /// the poles are extended to them.
+    pub fn tiles(&self, zoom: u8) -> Vec<BoundingBox> {
+        let z = zoom.saturating_sub(1).min(30);
+        let n = 1u32 << z;
+        let (top, bottom) = (tile_y(self.north, n), tile_y(self.south, n));
+
+        let mut tiles = Vec::new();
+        for y in top..=bottom {
+            let north = if y == 0 { 90.0 } else { tile_latitude(y, n) };
+            let south = if y == n - 1 {
+                -90.0
+            } else {
+                tile_latitude(y + 1, n)
+            };
+            for half in self.halves() {
+                for x in tile_x(half.west, n)..=tile_x(half.east, n) {
+                    let west = x as f64 / n as f64 * 360.0 - 180.0;
+                    let east = (x + 1) as f64 / n as f64 * 360.0 - 180.0;
+                    tiles.push(BoundingBox {
+                        south: south.max(self.south),
+                        west: west.max(half.west),
+                        north: north.min(self.north),
+                        east: east.min(half.east),
+                    });
+                }
+            }
+        }
+        tiles
+    }
+
+    /// Splits a box crossing the antimeridian in two
+    fn halves(&self) -> Vec<BoundingBox> {
+        if self.west <= self.east {
+            return vec![*self];
+        }
+        vec![
+            BoundingBox {
+                east: 180.0,
+                ..*self
+            },
+            BoundingBox {
+                west: -180.0,
+                ..*self
+            },
+        ]
+    }
+}
+
+impl fmt::Display for BoundingBox {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{},{},{},{}",
+            self.south, self.west, self.north, self.east
+        )
+    }
+}
+
+fn tile_x(longitude: f64, n: u32) -> u32 {
+    (((longitude + 180.0) / 360.0 * n as f64) as u32).min(n - 1)
+}
+
+fn tile_y(latitude: f64, n: u32) -> u32 {
+    let latitude = latitude
+        .clamp(-MERCATOR_MAX_LATITUDE, MERCATOR_MAX_LATITUDE)
+        .to_radians();
+    let y = (1.0 - latitude.tan().asinh() / PI) / 2.0 * n as f64;
+    (y.max(0.0) as u32).min(n - 1)
+}
+
+fn tile_latitude(y: u32, n: u32) -> f64 {
+    (PI * (1.0 - 2.0 * y as f64 / n as f64))
+        .sinh()
+        .atan()
+        .to_degrees()
+}
+
+/// Returns the page size used for each tile at a zoom level
+///
+/// Zoomed out, a map can only show a sample of the sounds, so fewer are fetched.
+fn page_size(zoom: u8) -> i32 {
+    (15 << (zoom / 3).min(4)).min(150)
+}
+
+impl FreesoundClient {
+    /// Stream the geotagged sounds of an area, for a map shown at `zoom_hint`
+    ///
+    /// The box is split into [tiles](BoundingBox::tiles), at a coarser zoom
+    /// level if needed to query at most [`SOUNDMAP_MAX_TILES`] of them, and
+    /// each tile is searched in turn. Below [`SOUNDMAP_FULL_ZOOM`], only the
+    /// first page of each tile is fetched, with more results per page as the
+    /// zoom grows, which gives an even sample of the area; from that level on,
+    /// every page is. Sounds on the edge of two tiles are only yielded once.
+    /// The sounds come with the [`SOUNDMAP_FIELDS`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use futures::TryStreamExt;
+    /// use freesound_rs::{BoundingBox, FreesoundClient};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let barcelona = BoundingBox::new(41.32, 2.05, 41.47, 2.23)?;
+    /// let sounds: Vec<_> = client.sounds_in_bbox(barcelona, 13).try_collect().await?;
+    /// for sound in &sounds {
+    ///     println!("{} at {:?}", sound.name, sound.coordinates());
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn sounds_in_bbox(
+        &self,
+        bbox: BoundingBox,
+        zoom_hint: u8,
+    ) -> impl Stream<Item = Result<Sound>> + use<> {
+        let mut zoom = zoom_hint;
+        let mut tiles = bbox.tiles(zoom);
+        while tiles.len() > SOUNDMAP_MAX_TILES && zoom > 1 {
+            zoom -= 1;
+            tiles = bbox.tiles(zoom);
+        }
+
+        let client = self.clone();
+        let mut seen = HashSet::new();
+        stream::iter(tiles)
+            .map(move |tile| {
+                let query = SearchQueryBuilder::new()
+                    .filter(tile.filter())
+                    .page_size(page_size(zoom_hint))
+                    .fields(SOUNDMAP_FIELDS)
+                    .build();
+                if zoom_hint >= SOUNDMAP_FULL_ZOOM {
+                    client.search_stream(&query).boxed()
+                } else {
+                    let client = client.clone();
+                    stream::once(async move { client.search(&query).await })
+                        .map_ok(|page| stream::iter(page.results.into_iter().map(Ok)))
+                        .try_flatten()
+                        .boxed()
+                }
+            })
+            .flatten()
+            .try_filter(move |sound| std::future::ready(seen.insert(sound.id)))
+    }
+}
@@ -0,0 +1,390 @@
+use crate::client::FreesoundClient;
+use crate::error::{FreesoundError, Result};
+use crate::models::{Page, Sound};
+use futures::stream::{self, Stream};
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+/// Where a [`PageReader`] is in the page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Before the opening brace
+    Start,
+    /// Before a member name or the closing brace
+    Key,
+    /// Between a member name and its value
+    Colon,
+    /// Reading the value of a member other than `results`
+    Member,
+    /// Before the opening bracket of `results`
+    Results,
+    /// Before a result or the closing bracket
+    Item,
+    /// After a result
+    AfterItem,
+    /// After the value of a member
+    AfterMember,
+    /// After the closing brace
+    Done,
+}
+
+/// Progress through a JSON value whose end has not been read yet
+#[derive(Debug, Default)]
+struct ValueScan {
+    start: Option<usize>,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+}
+
+/// Splits a page of results into its items as the bytes of the page arrive
+///
+/// Each result is deserialized as soon as its last byte is fed, so only one
+/// result is held in memory at a time, besides the bytes not read yet. This is
+/// how [`search_incremental`](FreesoundClient::search_incremental) yields the
+/// first sounds of a large page before the rest of it is received.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{PageReader, SearchSound};
+///
+/// let mut reader = PageReader::<SearchSound>::new();
+/// let mut names = Vec::new();
+/// for chunk in [r#"{"count": 2, "results": [{"id": 1, "na"#, r#"me": "Rain"}, {"id": 2"#, r#"}], "next": null}"#] {
+///     for sound in reader.feed(chunk.as_bytes())? {
+///         names.push(sound.name);
+///     }
+/// }
+/// assert_eq!(names, [Some("Rain".to_string()), None]);
+///
+/// // The rest of the page comes with the last bytes
+/// let page = reader.finish()?;
+/// assert_eq!(page.count, 2);
+/// assert!(page.results.is_empty());
+/// assert!(!page.has_next());
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug)]
+pub struct PageReader<T> {
+    buffer: Vec<u8>,
+    /// Position of the next byte to read in `buffer`
+    pos: usize,
+    state: State,
+    value: ValueScan,
+    key: String,
+    members: serde_json::Map<String, serde_json::Value>,
+    _item: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for PageReader<T> {
+    fn default() -> Self {
+        Self {
+            buffer: Vec::new(),
+            pos: 0,
+            state: State::Start,
+            value: ValueScan::default(),
+            key: String::new(),
+            members: serde_json::Map::new(),
+            _item: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> PageReader<T> {
+    /// Create a reader for a page
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the next bytes of the page, returning the results they complete
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<Vec<T>> {
+        self.buffer.extend_from_slice(bytes);
+        let mut items = Vec::new();
+        loop {
+            if self.value.start.is_none() {
+                self.skip_whitespace();
+            }
+            let Some(&byte) = self.buffer.get(self.pos) else {
+                break;
+            };
+            // A value being scanned goes on, whatever byte comes next
+            let scanning = self.value.start.is_some();
+            match (self.state, byte) {
+                (State::Start, b'{') => self.advance(State::Key),
+                (State::Key, b'}') | (State::AfterMember, b'}') if !scanning => {
+                    self.advance(State::Done)
+                }
+                (State::Key, _) if scanning || byte == b'"' => {
+                    let Some(key) = self.scan_value() else {
+                        break;
+                    };
+                    self.key = parse(key)?;
+                    self.state = State::Colon;
+                }
+                (State::Colon, b':') if self.key == "results" => self.advance(State::Results),
+                (State::Colon, b':') => self.advance(State::Member),
+                (State::Member, _) => {
+                    let Some(value) = self.scan_value() else {
+                        break;
+                    };
+                    let value = parse(value)?;
+                    self.members.insert(std::mem::take(&mut self.key), value);
+                    self.state = State::AfterMember;
+                }
+                (State::AfterMember, b',') => self.advance(State::Key),
+                (State::Results, b'[') => self.advance(State::Item),
+                (State::Item, b']') | (State::AfterItem, b']') if !scanning => {
+                    self.advance(State::AfterMember)
+                }
+                (State::Item, _) => {
+                    let Some(item) = self.scan_value() else {
+                        break;
+                    };
+                    items.push(parse(item)?);
+                    self.state = State::AfterItem;
+                }
+                (State::AfterItem, b',') => self.advance(State::Item),
+                (_, byte) => {
+                    return Err(invalid(format!(
+                        "unexpected '{}' in a page of results",
+                        byte.escape_ascii()
+                    )));
+                }
+            }
+        }
+        self.compact();
+        Ok(items)
+    }
+
+    /// Returns the page without its results, once all of it was fed
+    ///
+    /// The results were returned by [`feed`](Self::feed). Fails if the page
+    /// was cut short.
+    pub fn finish(mut self) -> Result<Page<T>> {
+        if self.state != State::Done {
+            return Err(invalid("truncated page of results".to_string()));
+        }
+        self.members
+            .insert("results".to_string(), serde_json::Value::Array(Vec::new()));
+        serde_json::from_value(serde_json::Value::Object(self.members)).map_err(Into::into)
+    }
+
+    fn advance(&mut self, state: State) {
+        self.pos += 1;
+        self.state = state;
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .buffer
+            .get(self.pos)
+            .is_some_and(|b| b.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// Reads on to the end of the value starting at the current position,
+    /// returning it once it is complete
+    fn scan_value(&mut self) -> Option<&[u8]> {
+        let start = *self.value.start.get_or_insert(self.pos);
+        let scan = &mut self.value;
+        let mut end = None;
+        while let Some(&byte) = self.buffer.get(self.pos) {
+            if scan.in_string {
+                match byte {
+                    _ if scan.escaped => scan.escaped = false,
+                    b'\\' => scan.escaped = true,
+                    b'"' => scan.in_string = false,
+                    _ => {}
+                }
+            } else {
+                match byte {
+                    b'"' => scan.in_string = true,
+                    b'{' | b'[' => scan.depth += 1,
+                    b'}' | b']' if scan.depth > 0 => scan.depth -= 1,
+                    // A delimiter ends a number or literal without being part of it
+                    b'}' | b']' | b',' if scan.depth == 0 => {
+                        end = Some(self.pos);
+                        break;
+                    }
+                    _ if byte.is_ascii_whitespace() && scan.depth == 0 => {
+                        end = Some(self.pos);
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+            self.pos += 1;
+            if scan.depth == 0 && !scan.in_string && matches!(byte, b'"' | b'}' | b']') {
+                end = Some(self.pos);
+                break;
+            }
+        }
+        let end = end?;
+        self.value = ValueScan::default();
+        Some(&self.buffer[start..end])
+    }
+
+    /// Drops the bytes that were read, keeping the value being scanned
+    fn compact(&mut self) {
+        let read = self.value.start.unwrap_or(self.pos);
+        self.buffer.drain(..read);
+        self.pos -= read;
+        if let Some(start) = &mut self.value.start {
+            *start -= read;
+        }
+    }
+}
+
+fn parse<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    serde_json::from_slice(bytes).map_err(Into::into)
+}
+
+fn invalid(message: String) -> FreesoundError {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message).into()
+}
+
+/// The state of [`FreesoundClient::search_incremental`]
+struct Incremental {
+    client: FreesoundClient,
+    /// The request of the next page, if any
+    next: Option<reqwest::RequestBuilder>,
+    page: Option<PageRead>,
+    pending: VecDeque<Result<Sound>>,
+}
+
+/// A page being received
+struct PageRead {
+    response: reqwest::Response,
+    reader: PageReader<serde_json::Value>,
+    url: reqwest::Url,
+    normalized: bool,
+    bytes: u64,
+}
+
+impl Incremental {
+    async fn next_sound(&mut self) -> Result<Option<Sound>> {
+        loop {
+            if let Some(sound) = self.pending.pop_front() {
+                return sound.map(Some);
+            }
+            if let Some(page) = &mut self.page {
+                let Some(chunk) = page.response.chunk().await.map_err(FreesoundError::from)? else {
+                    let page = self.page.take().expect("a page is being read");
+                    self.next =
+                        page.reader.finish()?.next.map(|link| {
+                            self.client.request_url(reqwest::Method::GET, link.as_str())
+                        });
+                    continue;
+                };
+                page.bytes += chunk.len() as u64;
+                if let Some(limit) = self.client.max_response_size()
+                    && page.bytes > limit
+                {
+                    return Err(FreesoundError::ResponseTooLarge { limit }
+                        .with_context(self.client.error_context(&page.url)));
+                }
+                for mut value in page.reader.feed(&chunk)? {
+                    if page.normalized
+                        && let Some(sound) = value.as_object_mut()
+                    {
+                        sound.insert("analysis_normalized".to_string(), true.into());
+                    }
+                    self.pending
+                        .push_back(self.client.decode(page.url.as_str(), value));
+                }
+                continue;
+            }
+
+            let Some(request) = self.next.take() else {
+                return Ok(None);
+            };
+            let response = match self.client.send(request).await {
+                Ok(response) => self.client.check_status(response).await,
+                Err(e) => Err(e),
+            };
+            let response = match response {
+                Err(ref e) if let FreesoundError::PaginationLimit { max_reachable } = e.root() => {
+                    log::warn!(
+                        "Freesound serves no page after page {max_reachable}, stopping there"
+                    );
+                    return Ok(None);
+                }
+                response => response?,
+            };
+            let url = response.url().clone();
+            self.page = Some(PageRead {
+                normalized: url
+                    .query_pairs()
+                    .any(|(k, v)| k == "normalized" && v == "1"),
+                response,
+                reader: PageReader::new(),
+                url,
+                bytes: 0,
+            });
+        }
+    }
+}
+
+impl FreesoundClient {
+    /// Search for sounds, yielding each result as soon as it is received
+    ///
+    /// Like [`search_stream`](Self::search_stream), every page is walked
+    /// through, but the results of a page are decoded one at a time as its
+    /// bytes arrive (see [`PageReader`]). The first sounds of a page of 150
+    /// results with their analysis come sooner, and the page is never held in
+    /// memory as a whole. Pages are not stored in the search cache.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use futures::{StreamExt, pin_mut};
+    /// use freesound_rs::{FreesoundClient, SearchQueryBuilder};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let query = SearchQueryBuilder::new()
+    ///     .query("thunder")
+    ///     .fields(["id", "name", "analysis"])
+    ///     .page_size(150)
+    ///     .build();
+    /// let sounds = client.search_incremental(&query);
+    /// pin_mut!(sounds);
+    /// while let Some(sound) = sounds.next().await {
+    ///     println!("{}", sound?.name);
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn search_incremental(
+        &self,
+        query: &[(String, String)],
+    ) -> impl Stream<Item = Result<Sound>> + use<> {
+        let first = self
+            .request_url(
+                reqwest::Method::GET,
+                &format!("{}/search/text", self.base_url()),
+            )
+            .query(query);
+        let state = Incremental {
+            client: self.clone(),
+            next: Some(first),
+            page: None,
+            pending: VecDeque::new(),
+        };
+        stream::unfold(Some(state), |state| async move {
+            let mut state = state?;
+            match state.next_sound().await {
+                Ok(Some(sound)) => Some((Ok(sound), Some(state))),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), None)),
+            }
+        })
+    }
+}
@@ -0,0 +1,172 @@
+use crate::client::FreesoundClient;
+use crate::error::{FreesoundError, Result};
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
+use std::fmt;
+
+/// Placeholder replacing credentials in inspected requests
+pub const REDACTED: &str = "REDACTED";
+
+/// A fully built request, not sent yet
+///
+/// Obtained with [`FreesoundClient::prepare`], it can be logged without leaking
+/// credentials (the `Debug` output, [`url`](Self::url) and
+/// [`headers`](Self::headers) are redacted), sent with
+/// [`FreesoundClient::execute`], or turned into a [`reqwest::Request`] to be
+/// signed or routed elsewhere. `reqwest::Request` converts into an
+/// `http::Request` with `TryFrom`.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{FreesoundClient, SearchQueryBuilder};
+/// use reqwest::Method;
+///
+/// let client = FreesoundClient::new("API_KEY".to_string(), None);
+/// let query = SearchQueryBuilder::new().query("piano").build();
+/// let request = client.prepare(client.request(Method::GET, "search/text").query(&query))?;
+///
+/// assert_eq!(request.method(), Method::GET);
+/// assert_eq!(
+///     request.url().as_str(),
+///     "https://freesound.org/apiv2/search/text?token=REDACTED&query=piano"
+/// );
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+pub struct PreparedRequest {
+    request: reqwest::Request,
+}
+
+impl PreparedRequest {
+    /// Returns the HTTP method
+    pub fn method(&self) -> &reqwest::Method {
+        self.request.method()
+    }
+
+    /// Returns the URL, with the API key redacted
+    pub fn url(&self) -> reqwest::Url {
+        redact_url(self.request.url())
+    }
+
+    /// Returns the headers, with the access token redacted
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = self.request.headers().clone();
+        if headers.contains_key(AUTHORIZATION) {
+            headers.insert(AUTHORIZATION, HeaderValue::from_static(REDACTED));
+        }
+        headers
+    }
+
+    /// Returns the body, if it is held in memory
+    pub fn body(&self) -> Option<&[u8]> {
+        self.request.body().and_then(|body| body.as_bytes())
+    }
+
+    /// Returns the request, credentials included
+    pub fn as_request(&self) -> &reqwest::Request {
+        &self.request
+    }
+
+    /// Returns the request, credentials included, for signing or sending elsewhere
+    pub fn into_request(self) -> reqwest::Request {
+        self.request
+    }
+}
+
+impl fmt::Debug for PreparedRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PreparedRequest")
+            .field("method", self.method())
+            .field("url", &self.url().as_str())
+            .field("headers", &self.headers())
+            .finish()
+    }
+}
+
+/// A request that [`FreesoundClient::prepare`] can build and
+/// [`FreesoundClient::execute`] can send
+///
+/// Implemented for request builders from [`FreesoundClient::request`] and
+/// prepared requests, whose raw response is returned, and for references to
+/// an [`Endpoint`](crate::Endpoint), whose typed response is read.
+pub trait IntoRequest {
+    /// What [`FreesoundClient::execute`] returns
+    type Output;
+
+    /// Builds the request, credentials included
+    fn into_request(self, client: &FreesoundClient) -> Result<PreparedRequest>;
+
+    /// Reads the response of the request
+    fn read(
+        client: &FreesoundClient,
+        response: reqwest::Response,
+    ) -> impl Future<Output = Result<Self::Output>> + Send;
+}
+
+impl IntoRequest for reqwest::RequestBuilder {
+    type Output = reqwest::Response;
+
+    fn into_request(self, _: &FreesoundClient) -> Result<PreparedRequest> {
+        let request = self.build().map_err(FreesoundError::from)?;
+        Ok(PreparedRequest { request })
+    }
+
+    async fn read(_: &FreesoundClient, response: reqwest::Response) -> Result<reqwest::Response> {
+        Ok(response)
+    }
+}
+
+impl IntoRequest for PreparedRequest {
+    type Output = reqwest::Response;
+
+    fn into_request(self, _: &FreesoundClient) -> Result<PreparedRequest> {
+        Ok(self)
+    }
+
+    async fn read(_: &FreesoundClient, response: reqwest::Response) -> Result<reqwest::Response> {
+        Ok(response)
+    }
+}
+
+impl FreesoundClient {
+    /// Build a request without sending it
+    ///
+    /// The typed methods such as [`search`](Self::search) or
+    /// [`get_sound`](Self::get_sound) send their request right away and cannot
+    /// be prepared. Their request can be built from [`request`](Self::request)
+    /// instead, with the path and parameters of the method (e.g. `search/text`
+    /// and the query built by [`SearchQueryBuilder`](crate::SearchQueryBuilder)
+    /// for `search`).
+    pub fn prepare(&self, request: impl IntoRequest) -> Result<PreparedRequest> {
+        request.into_request(self)
+    }
+
+    /// Send a request, through the rate limiter, circuit breaker and journal
+    ///
+    /// Request builders and prepared requests return the raw response, whatever
+    /// its status.
+    pub async fn execute<R: IntoRequest>(&self, request: R) -> Result<R::Output> {
+        let request = request.into_request(self)?;
+        let response = self.execute_request(request.request).await?;
+        R::read(self, response).await
+    }
+}
+
+/// Returns `url` with the API key of its `token` parameter redacted
+pub(crate) fn redact_url(url: &reqwest::Url) -> reqwest::Url {
+    let mut url = url.clone();
+    if url.query_pairs().any(|(k, _)| k == "token") {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(k, v)| {
+                let v = if k == "token" {
+                    REDACTED.to_string()
+                } else {
+                    v.into_owned()
+                };
+                (k.into_owned(), v)
+            })
+            .collect();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+    url
+}
@@ -0,0 +1,171 @@
+use crate::error::Result;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Default size from which a [`FileJournal`] rotates its file, 10 MiB
+pub const DEFAULT_JOURNAL_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Default number of rotated files a [`FileJournal`] keeps
+pub const DEFAULT_JOURNAL_FILES: usize = 5;
+
+/// An API call, as recorded in a journal
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct JournalEntry {
+    /// When the request was sent, in milliseconds since the Unix epoch
+    pub timestamp: u64,
+    /// The HTTP method
+    pub method: String,
+    /// The endpoint, such as `sounds/{id}`, or the host of a media file
+    pub endpoint: String,
+    /// The path of the URL, such as `/apiv2/sounds/1234/`
+    pub path: String,
+    /// The query parameters, without the API key
+    pub params: Vec<(String, String)>,
+    /// The HTTP status, if a response was received
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Time until the response headers were received, in milliseconds
+    pub duration_ms: u64,
+    /// The error that prevented a response, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Receives every API call of a client, for auditing
+///
+/// Attach it with [`FreesoundClient::with_journal`](crate::FreesoundClient::with_journal).
+/// It is called once the response headers are received, or the request failed.
+/// Recording cannot fail a request: sinks deal with their own errors.
+pub trait JournalSink: fmt::Debug + Send + Sync {
+    /// Records an API call
+    fn record(&self, entry: &JournalEntry);
+}
+
+impl<T: JournalSink + ?Sized> JournalSink for Arc<T> {
+    fn record(&self, entry: &JournalEntry) {
+        (**self).record(entry);
+    }
+}
+
+/// A [`JournalSink`] keeping the entries in memory
+#[derive(Debug, Default)]
+pub struct MemoryJournal(Mutex<Vec<JournalEntry>>);
+
+impl MemoryJournal {
+    /// Returns the entries recorded so far, oldest first
+    pub fn entries(&self) -> Vec<JournalEntry> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl JournalSink for MemoryJournal {
+    fn record(&self, entry: &JournalEntry) {
+        self.0.lock().unwrap().push(entry.clone());
+    }
+}
+
+/// A [`JournalSink`] appending the entries to a JSON Lines file
+///
+/// Once the file reaches its maximum size, it is renamed with a `.1` suffix,
+/// the previous ones shifting to `.2`, `.3`... and the oldest beyond the
+/// number of files to keep being deleted. Write errors are ignored.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{FileJournal, FreesoundClient};
+///
+/// let path = std::env::temp_dir().join("freesound-rs-doctest-journal.jsonl");
+/// let journal = FileJournal::open(&path, 1024 * 1024)?.keep(3);
+/// assert_eq!(journal.path(), path);
+/// let client = FreesoundClient::new("API_KEY".to_string(), None).with_journal(journal);
+/// # std::fs::remove_file(&path)?;
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug)]
+pub struct FileJournal {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: Mutex<(File, u64)>,
+}
+
+impl FileJournal {
+    /// Opens the journal in the file at `path`, rotated from `max_bytes`
+    /// (such as [`DEFAULT_JOURNAL_MAX_BYTES`])
+    ///
+    /// Entries are appended to an existing file. [`DEFAULT_JOURNAL_FILES`]
+    /// rotated files are kept.
+    pub fn open(path: impl AsRef<Path>, max_bytes: u64) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_bytes,
+            keep: DEFAULT_JOURNAL_FILES,
+            file: Mutex::new((file, size)),
+        })
+    }
+
+    /// Set the number of rotated files to keep
+    pub fn keep(mut self, files: usize) -> Self {
+        self.keep = files;
+        self
+    }
+
+    /// Returns the path of the current file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the path of the `n`-th rotated file
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{n}"));
+        path.into()
+    }
+
+    /// Shifts the rotated files and starts a new current file
+    fn rotate(&self) -> std::io::Result<File> {
+        if self.keep == 0 {
+            std::fs::remove_file(&self.path)?;
+        } else {
+            let _ = std::fs::remove_file(self.rotated(self.keep));
+            for n in (1..self.keep).rev() {
+                let _ = std::fs::rename(self.rotated(n), self.rotated(n + 1));
+            }
+            std::fs::rename(&self.path, self.rotated(1))?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+    }
+}
+
+impl JournalSink for FileJournal {
+    fn record(&self, entry: &JournalEntry) {
+        let Ok(mut line) = serde_json::to_vec(entry) else {
+            return;
+        };
+        line.push(b'\n');
+
+        let mut file = self.file.lock().unwrap();
+        let (current, size) = &mut *file;
+        if *size > 0
+            && *size + line.len() as u64 > self.max_bytes
+            && let Ok(rotated) = self.rotate()
+        {
+            *current = rotated;
+            *size = 0;
+        }
+        if current.write_all(&line).is_ok() {
+            *size += line.len() as u64;
+        }
+    }
+}
@@ -2,11 +2,232 @@
 //!
 //! This library provides a convenient interface to interact with the Freesound API,
 //! allowing users to search, download and manage sound samples from Freesound.org.
+//!
+//! # Async runtimes
+//!
+//! The library is written against `futures` only: its futures and streams can
+//! be polled by any executor, and its timers come from `futures-timer`. The
+//! HTTP requests go through `reqwest`, whose connections need a tokio reactor;
+//! async-std or smol applications can provide one by wrapping the client calls
+//! in `async_compat::Compat`. The `tokio` feature adds conveniences tied to
+//! tokio, such as [`ShutdownHandle::shutdown_on_ctrl_c`].
+//!
+//! ```
+//! use freesound_rs::{Sleeper, SystemClock};
+//! use std::time::Duration;
+//!
+//! // No tokio runtime is needed to drive the futures of the library
+//! futures::executor::block_on(SystemClock.sleep(Duration::from_millis(1)));
+//! ```
 
+pub mod analysis;
+#[cfg(feature = "playback")]
+mod audition;
+pub mod batch;
+mod breaker;
+mod builder;
+mod cache;
 mod client;
+mod clock;
+#[cfg(feature = "decode")]
+pub mod convert;
+#[cfg(feature = "decode")]
+mod decode;
+pub mod descriptor;
+pub mod diff;
+mod download;
+mod endpoint;
+mod environment;
 mod error;
+pub mod export;
+mod federation;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod geo;
+mod incremental;
+mod inspect;
+mod journal;
+mod limiter;
+mod loudness;
+pub mod mirror;
 mod models;
+mod oauth;
+mod pagination;
+mod pipeline;
+mod queries;
+mod repository;
+mod retry;
+#[cfg(feature = "rich-text")]
+mod rich_text;
+#[cfg(feature = "schema-report")]
+mod schema;
+mod similarity;
+mod stats;
+mod strictness;
+mod sync;
+pub mod tags;
+mod updates;
+pub mod upload;
+mod usage;
+mod watch;
+#[cfg(feature = "decode")]
+pub mod waveform;
+mod write;
+
+#[cfg(feature = "playback")]
+pub use audition::{AuditionQueue, DEFAULT_POLL_INTERVAL, Player};
+pub use breaker::{CircuitBreaker, CircuitState, DEFAULT_COOLDOWN, DEFAULT_FAILURE_THRESHOLD};
+pub use builder::{
+    DEFAULT_CONNECT_TIMEOUT, DEFAULT_TIMEOUT, DEFAULT_USER_AGENT, FreesoundClientBuilder,
+};
+pub use cache::{CacheStats, DiskCache, SearchCache};
+pub use client::{AuthLevel, DEFAULT_BASE_URL, FreesoundClient};
+pub use clock::{Clock, MockClock, Sleeper, SystemClock};
+#[cfg(feature = "decode")]
+pub use decode::Pcm;
+#[cfg(feature = "indicatif")]
+pub use download::IndicatifProgress;
+pub use download::{
+    ContentStore, DownloadEvent, DownloadItem, DownloadJob, DownloadManager, DownloadReport,
+    DownloadSource, DownloadStatus, Drain, EventSink, JobStatus, Manifest, ManifestEntry,
+    NoProgress, OverwritePolicy, ProgressSink, RATE_SAMPLE_INTERVAL, RATE_SMOOTHING,
+    STORE_INDEX_FILE, ShutdownHandle, StoreEntry, TransferRate, TransferStats,
+};
+pub use endpoint::Endpoint;
+pub use environment::Environment;
+pub use error::{ErrorContext, ErrorKind, FreesoundError, Result};
+pub use federation::{Fallback, Federation};
+/// Derives [`SearchFields`] and `Deserialize` from the fields of a struct
+///
+/// Needs the `macros` feature. Each field of the struct is read from the
+/// sound field of the same name, or of the name given with
+/// `#[freesound(rename = "...")]`, and unknown names fail the build. Fields
+/// the API may leave out should be `Option`s.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{Field, FreesoundFields, SearchFields};
+///
+/// #[derive(Debug, FreesoundFields)]
+/// struct Hit {
+///     id: i32,
+///     name: String,
+///     #[freesound(rename = "type")]
+///     format: String,
+///     avg_rating: Option<f32>,
+/// }
+///
+/// assert_eq!(Hit::FIELDS, [Field::Id, Field::Name, Field::Type, Field::AvgRating]);
+/// let hit: Hit = serde_json::from_str(r#"{"id": 1, "name": "Rain", "type": "wav"}"#)?;
+/// assert_eq!(hit.format, "wav");
+/// assert_eq!(hit.avg_rating, None);
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+///
+/// ```compile_fail
+/// // `title` is not a sound field
+/// #[derive(freesound_rs::FreesoundFields)]
+/// struct Hit {
+///     title: String,
+/// }
+/// ```
+#[cfg(feature = "macros")]
+pub use freesound_macros::FreesoundFields;
+/// Builds a [`SearchQueryBuilder`] from an expression checked at compile time
+///
+/// Needs the `macros` feature. The expression is a list of comma-separated
+/// `key: value` entries, values with commas being double-quoted. A mistake,
+/// such as an unknown key or field, or an empty duration range, fails the
+/// build instead of a [`SearchQueryBuilder::parse`] call at run time.
+///
+/// Supported keys:
+///
+/// * `query` - the text query
+/// * `filter` - a raw filter, combined with the other ones (may be repeated)
+/// * `dur` / `duration`, `license`, `tag`, `type`, `user` / `username`, `pack` -
+///   filters, as in [`SearchQueryBuilder::parse`]
+/// * `sort` - a [`SortOption`] API name such as `rating_desc`, or one of the
+///   short names of [`SearchQueryBuilder::parse`]
+/// * `page` / `page_size` - pagination
+/// * `fields` / `descriptors` - space-separated names
+/// * `group_by_pack` / `normalized` - `true` or `false`
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{SearchQueryBuilder, SortOption, freesound_query};
+///
+/// let query = freesound_query!("query: piano, dur: 1..5, sort: rating_desc, fields: id name");
+/// assert_eq!(
+///     query,
+///     SearchQueryBuilder::new()
+///         .query("piano")
+///         .filter("duration:[1 TO 5]")
+///         .sort(SortOption::RatingDesc)
+///         .fields(["id", "name"])
+/// );
+/// ```
+///
+/// ```compile_fail
+/// // `colour` is not a sound field
+/// let query = freesound_rs::freesound_query!("query: piano, fields: id colour");
+/// ```
+#[cfg(feature = "macros")]
+pub use freesound_macros::freesound_query;
+pub use geo::{BoundingBox, SOUNDMAP_FIELDS, SOUNDMAP_FULL_ZOOM, SOUNDMAP_MAX_TILES};
+pub use incremental::PageReader;
+pub use inspect::{IntoRequest, PreparedRequest, REDACTED};
+pub use journal::{
+    DEFAULT_JOURNAL_FILES, DEFAULT_JOURNAL_MAX_BYTES, FileJournal, JournalEntry, JournalSink,
+    MemoryJournal,
+};
+pub use limiter::{DEFAULT_REQUESTS_PER_MINUTE, Priority, RateLimiter};
+pub use loudness::{Loudness, LoudnessCheck};
+pub use models::{
+    AcAnalysis, Avatar, AvatarSize, BookmarkCategory, CombinedSearchBuilder,
+    CombinedSearchResponse, Comment, Field, Fields, FilterBuilder, GetSoundOptions, ImageKind,
+    Images, Key, LOSSLESS_TYPES, ONE_SHOT_MAX_DURATION, Pack, PackId, Page, PendingSound,
+    PendingUploads, Preset, PreviewQuality, Previews, RateLimitInfo, ResponseMeta, SearchFields,
+    SearchQueryBuilder, SearchResponse, SearchSound, SimilaritySpace, SortOption, Sound,
+    SoundDetail, SoundListOptions, SoundRef, Target, UnknownField, UploadStatus, User, Username,
+    canonical_query,
+};
+pub use oauth::{
+    FileTokenStore, LOGIN_REDIRECT_PORT, LOGIN_TIMEOUT, MemoryTokenStore, OAuthTokens,
+    OUT_OF_BAND_REDIRECT_URL, TokenStore,
+};
+pub use pagination::MORE_ATTEMPTS;
+pub use pipeline::ResultPipeline;
+pub use queries::QueryStore;
+pub use repository::{
+    DEFAULT_MEDIA_CACHE_BYTES, DEFAULT_METADATA_TTL, HYDRATION_CONCURRENCY, Hydration,
+    SoundRepository,
+};
+pub use retry::{DEFAULT_API_ATTEMPTS, DEFAULT_MEDIA_ATTEMPTS, RetryPolicy};
+#[cfg(feature = "schema-report")]
+pub use schema::{SchemaIssue, SchemaIssueKind, SchemaReport};
+pub use similarity::DescriptorDistance;
+pub use stats::{DURATION_BUCKETS, DurationBucket, SearchStats};
+pub use strictness::Strictness;
+pub use sync::{
+    BookmarkSync, SYNC_MANIFEST_FILE, SYNC_REPORT_FILE, SyncFailure, SyncReport, sync_bookmarks,
+};
+pub use updates::{CHECK_CONCURRENCY, SoundChange, SoundUpdate};
+pub use upload::{
+    AUDIO_EXTENSIONS, BulkUpload, MIN_TAGS, SoundDescription, UploadJournalEntry, UploadResponse,
+};
+pub use url::Url;
+pub use usage::UsageReport;
+pub use watch::{FileWatermarkStore, MemoryWatermarkStore, WatermarkStore};
+pub use write::{
+    BookmarkRequest, CategoryBookmark, CommentRequest, MAX_BOOKMARK_NAME_LENGTH,
+    MAX_COMMENT_LENGTH, MAX_RATING, RateRequest, WriteRequest,
+};
 
-pub use client::{DEFAULT_BASE_URL, FreesoundClient};
-pub use error::{FreesoundError, Result};
-pub use models::{SearchQueryBuilder, SearchResponse, SortOption, Sound};
+/// Items the derive macros refer to, not part of the public API
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+}
@@ -0,0 +1,174 @@
+use crate::clock::{Clock, Sleeper, SystemClock};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default number of requests a [`RateLimiter`] lets through per minute,
+/// the limit of a standard Freesound API key
+pub const DEFAULT_REQUESTS_PER_MINUTE: u32 = 60;
+
+/// How urgent the requests of a client are for its [`RateLimiter`]
+///
+/// Interactive requests, such as a preview the user clicked, take the next
+/// free slot; background requests, such as a bookmark sync, only get a slot
+/// when no interactive request is waiting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    /// Requests a user is waiting for
+    #[default]
+    Interactive,
+    /// Bulk work that can wait
+    Background,
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Interactive => "interactive",
+            Self::Background => "background",
+        })
+    }
+}
+
+impl FromStr for Priority {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "interactive" => Ok(Self::Interactive),
+            "background" => Ok(Self::Background),
+            other => Err(format!("unknown priority: {other}")),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next: Option<Instant>,
+    interactive_waiting: usize,
+}
+
+/// Spaces the requests of a client to stay under the API quota
+///
+/// Requests are let through one per interval, `per / requests`, waiting for
+/// their slot if needed. A background request yields its slot to the waiting
+/// interactive ones (see [`Priority`]), so a long sync does not delay what the
+/// user asked for.
+///
+/// Attach it with [`FreesoundClient::with_rate_limiter`](crate::FreesoundClient::with_rate_limiter);
+/// clones of the client share it, and
+/// [`with_priority`](crate::FreesoundClient::with_priority) sets the priority
+/// of the requests of a clone.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use freesound_rs::{Clock, MockClock, Priority, RateLimiter};
+///
+/// let clock = Arc::new(MockClock::new());
+/// let limiter = RateLimiter::new(2, Duration::from_secs(1))
+///     .with_clock(clock.clone())
+///     .with_sleeper(clock.clone());
+///
+/// futures::executor::block_on(async {
+///     limiter.acquire(Priority::Background).await;
+///     limiter.acquire(Priority::Interactive).await;
+/// });
+/// // The second request waited for the next slot
+/// assert_eq!(clock.elapsed(), Duration::from_millis(500));
+/// ```
+#[derive(Debug)]
+pub struct RateLimiter {
+    interval: Duration,
+    clock: Arc<dyn Clock>,
+    sleeper: Arc<dyn Sleeper>,
+    state: Mutex<State>,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_REQUESTS_PER_MINUTE, Duration::from_secs(60))
+    }
+}
+
+impl RateLimiter {
+    /// Create a limiter letting through `requests` requests every `per`
+    pub fn new(requests: u32, per: Duration) -> Self {
+        Self {
+            interval: per / requests.max(1),
+            clock: Arc::new(SystemClock),
+            sleeper: Arc::new(SystemClock),
+            state: Mutex::default(),
+        }
+    }
+
+    /// Read the time from another clock (see [`MockClock`](crate::MockClock))
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Wait for the slots with another sleeper (see [`MockClock`](crate::MockClock))
+    pub fn with_sleeper(mut self, sleeper: Arc<dyn Sleeper>) -> Self {
+        self.sleeper = sleeper;
+        self
+    }
+
+    /// Returns the time between two requests
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Returns the number of interactive requests waiting for a slot
+    pub fn interactive_waiting(&self) -> usize {
+        self.state.lock().unwrap().interactive_waiting
+    }
+
+    /// Wait for a slot to send a request
+    ///
+    /// Requests sent by a client with a limiter already wait for their slot;
+    /// this is for other work sharing the quota.
+    pub async fn acquire(&self, priority: Priority) {
+        let _waiting = (priority == Priority::Interactive).then(|| Waiting::new(&self.state));
+        while let Some(wait) = self.try_acquire(priority) {
+            self.sleeper.sleep(wait).await;
+        }
+    }
+
+    /// Takes the current slot if it is free, or returns how long to wait
+    fn try_acquire(&self, priority: Priority) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        let now = self.clock.now();
+        if let Some(next) = state.next
+            && now < next
+        {
+            return Some(next - now);
+        }
+        if priority == Priority::Background && state.interactive_waiting > 0 {
+            return Some(self.interval);
+        }
+        state.next = Some(now + self.interval);
+        None
+    }
+}
+
+/// Counts an interactive request as waiting until it is dropped
+struct Waiting<'a>(&'a Mutex<State>);
+
+impl<'a> Waiting<'a> {
+    fn new(state: &'a Mutex<State>) -> Self {
+        state.lock().unwrap().interactive_waiting += 1;
+        Self(state)
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.0.lock().unwrap().interactive_waiting -= 1;
+    }
+}
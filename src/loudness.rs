@@ -0,0 +1,395 @@
+#[cfg(feature = "decode")]
+use crate::decode::Pcm;
+use serde::{Deserialize, Serialize};
+
+/// Loudness and peak level of an audio file, measured after ITU-R BS.1770
+///
+/// Recorded in the manifest entries of a [`DownloadManager`](crate::DownloadManager)
+/// set up with a [`loudness_check`](crate::DownloadManager::loudness_check).
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "decode")] {
+/// use freesound_rs::{Loudness, Pcm};
+///
+/// // One second of a 1 kHz sine at half scale
+/// let pcm = Pcm {
+///     sample_rate: 48_000,
+///     channels: 1,
+///     samples: (0..48_000)
+///         .map(|i| 0.5 * (i as f32 * 2.0 * std::f32::consts::PI / 48.0).sin())
+///         .collect(),
+/// };
+/// let loudness = Loudness::measure(&pcm);
+/// assert!((loudness.integrated_lufs.unwrap() + 9.03).abs() < 0.1);
+/// assert!((loudness.true_peak_dbtp.unwrap() + 6.02).abs() < 0.1);
+/// assert!(!loudness.clipped);
+///
+/// let silence = Loudness::measure(&Pcm { samples: vec![0.0; 48_000], ..pcm });
+/// assert_eq!(silence.integrated_lufs, None);
+/// assert_eq!(silence.true_peak_dbtp, None);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct Loudness {
+    /// Gated integrated loudness, in LUFS
+    ///
+    /// `None` when the audio is shorter than 400 ms or entirely below -70 LUFS.
+    pub integrated_lufs: Option<f64>,
+    /// Estimated true peak, in dB relative to full scale
+    ///
+    /// `None` for digital silence.
+    pub true_peak_dbtp: Option<f64>,
+    /// Whether the true peak reaches full scale
+    pub clipped: bool,
+}
+
+/// Blocks quieter than this are left out of the integrated loudness
+#[cfg(feature = "decode")]
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+
+/// Blocks quieter than the ungated loudness minus this are left out too
+#[cfg(feature = "decode")]
+const RELATIVE_GATE_LU: f64 = 10.0;
+
+impl Loudness {
+    /// Measures decoded audio
+    ///
+    /// The integrated loudness uses the K-weighting and the gating of BS.1770-4,
+    /// with every channel weighted equally, which is exact for mono and stereo.
+    /// The true peak is estimated by oversampling 4 times with the interpolation
+    /// filter of the standard. Requires the `decode` feature.
+    #[cfg(feature = "decode")]
+    pub fn measure(pcm: &Pcm) -> Self {
+        let channels = pcm.channels.max(1) as usize;
+        let mut weighting = vec![KWeighting::new(pcm.sample_rate as f64); channels];
+        let mut oversampling = vec![TruePeak::default(); channels];
+
+        // Mean square of every 100 ms step, summed over the channels
+        let step = (pcm.sample_rate as usize / 10).max(1);
+        let mut steps = Vec::new();
+        let mut energy = 0.0;
+        let mut peak = 0.0f64;
+        for (i, frame) in pcm.samples.chunks_exact(channels).enumerate() {
+            for (channel, &sample) in frame.iter().enumerate() {
+                let weighted = weighting[channel].process(sample as f64);
+                energy += weighted * weighted;
+                peak = peak.max(oversampling[channel].push(sample as f64));
+            }
+            if (i + 1) % step == 0 {
+                steps.push(energy / step as f64);
+                energy = 0.0;
+            }
+        }
+
+        // 400 ms blocks overlapping by 75%
+        let blocks = steps
+            .windows(4)
+            .map(|w| w.iter().sum::<f64>() / 4.0)
+            .filter(|&z| block_loudness(z) > ABSOLUTE_GATE_LUFS)
+            .collect::<Vec<_>>();
+        let integrated_lufs = match blocks.is_empty() {
+            true => None,
+            false => {
+                let gate = block_loudness(mean(&blocks)) - RELATIVE_GATE_LU;
+                let gated = blocks
+                    .into_iter()
+                    .filter(|&z| block_loudness(z) > gate)
+                    .collect::<Vec<_>>();
+                Some(block_loudness(mean(&gated)))
+            }
+        };
+
+        Self {
+            integrated_lufs,
+            true_peak_dbtp: (peak > 0.0).then(|| 20.0 * peak.log10()),
+            clipped: peak >= 1.0,
+        }
+    }
+}
+
+/// Limits a downloaded file must stay within
+///
+/// By default, only clipping files (true peak at 0 dBTP or above) are flagged.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{Loudness, LoudnessCheck};
+///
+/// let check = LoudnessCheck::new()
+///     .max_true_peak(-1.0)
+///     .min_lufs(-30.0)
+///     .max_lufs(-14.0);
+/// let loudness = Loudness {
+///     integrated_lufs: Some(-12.5),
+///     true_peak_dbtp: Some(-0.4),
+///     clipped: false,
+/// };
+/// assert_eq!(
+///     check.problems(&loudness),
+///     [
+///         "true peak of -0.40 dBTP reaches -1.00 dBTP",
+///         "integrated loudness of -12.5 LUFS above -14.0 LUFS",
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoudnessCheck {
+    max_true_peak: f64,
+    min_lufs: Option<f64>,
+    max_lufs: Option<f64>,
+    reject: bool,
+}
+
+impl Default for LoudnessCheck {
+    fn default() -> Self {
+        Self {
+            max_true_peak: 0.0,
+            min_lufs: None,
+            max_lufs: None,
+            reject: false,
+        }
+    }
+}
+
+impl LoudnessCheck {
+    /// Creates a check flagging clipping files only
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the highest true peak allowed, in dBTP (0.0 by default)
+    ///
+    /// Files reaching it are flagged.
+    pub fn max_true_peak(mut self, dbtp: f64) -> Self {
+        self.max_true_peak = dbtp;
+        self
+    }
+
+    /// Flag files quieter than `lufs`
+    ///
+    /// Files whose loudness cannot be measured, such as silent ones, are flagged too.
+    pub fn min_lufs(mut self, lufs: f64) -> Self {
+        self.min_lufs = Some(lufs);
+        self
+    }
+
+    /// Flag files louder than `lufs`
+    pub fn max_lufs(mut self, lufs: f64) -> Self {
+        self.max_lufs = Some(lufs);
+        self
+    }
+
+    /// Delete flagged files and fail their job instead of keeping them
+    ///
+    /// Defaults to false.
+    pub fn reject(mut self, enabled: bool) -> Self {
+        self.reject = enabled;
+        self
+    }
+
+    /// Returns whether flagged files are rejected
+    pub fn rejects(&self) -> bool {
+        self.reject
+    }
+
+    /// Returns why a measurement is out of limits, or nothing if it passes
+    pub fn problems(&self, loudness: &Loudness) -> Vec<String> {
+        let mut problems = Vec::new();
+        if let Some(peak) = loudness.true_peak_dbtp
+            && peak >= self.max_true_peak
+        {
+            problems.push(format!(
+                "true peak of {peak:.2} dBTP reaches {:.2} dBTP",
+                self.max_true_peak
+            ));
+        }
+        match (loudness.integrated_lufs, self.min_lufs) {
+            (None, Some(_)) => problems.push("loudness cannot be measured".to_string()),
+            (Some(lufs), Some(min)) if lufs < min => problems.push(format!(
+                "integrated loudness of {lufs:.1} LUFS below {min:.1} LUFS"
+            )),
+            _ => {}
+        }
+        if let (Some(lufs), Some(max)) = (loudness.integrated_lufs, self.max_lufs)
+            && lufs > max
+        {
+            problems.push(format!(
+                "integrated loudness of {lufs:.1} LUFS above {max:.1} LUFS"
+            ));
+        }
+        problems
+    }
+}
+
+#[cfg(feature = "decode")]
+fn block_loudness(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.log10()
+}
+
+#[cfg(feature = "decode")]
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// The two filters of the K-weighting, a high shelf then a high pass
+#[cfg(feature = "decode")]
+#[derive(Debug, Clone)]
+struct KWeighting {
+    shelf: Biquad,
+    high_pass: Biquad,
+}
+
+#[cfg(feature = "decode")]
+impl KWeighting {
+    /// Computes the coefficients for any sample rate, from the analog prototypes
+    fn new(sample_rate: f64) -> Self {
+        let k = (std::f64::consts::PI * 1681.974450955533 / sample_rate).tan();
+        let q = 0.7071752369554196;
+        let vh = 10f64.powf(3.999843853973347 / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+        let shelf = Biquad::new(
+            [
+                (vh + vb * k / q + k * k) / a0,
+                2.0 * (k * k - vh) / a0,
+                (vh - vb * k / q + k * k) / a0,
+            ],
+            [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        );
+
+        let k = (std::f64::consts::PI * 38.13547087602444 / sample_rate).tan();
+        let q = 0.5003270373238773;
+        let a0 = 1.0 + k / q + k * k;
+        let high_pass = Biquad::new(
+            [1.0, -2.0, 1.0],
+            [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        );
+
+        Self { shelf, high_pass }
+    }
+
+    fn process(&mut self, sample: f64) -> f64 {
+        self.high_pass.process(self.shelf.process(sample))
+    }
+}
+
+#[cfg(feature = "decode")]
+#[derive(Debug, Clone)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    x: [f64; 2],
+    y: [f64; 2],
+}
+
+#[cfg(feature = "decode")]
+impl Biquad {
+    fn new(b: [f64; 3], a: [f64; 2]) -> Self {
+        Self {
+            b,
+            a,
+            x: [0.0; 2],
+            y: [0.0; 2],
+        }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.b[1] * self.x[0] + self.b[2] * self.x[1]
+            - self.a[0] * self.y[0]
+            - self.a[1] * self.y[1];
+        self.x = [x, self.x[0]];
+        self.y = [y, self.y[0]];
+        y
+    }
+}
+
+/// Interpolation filter of BS.1770-4 annex 2, one row per phase
+#[cfg(feature = "decode")]
+const OVERSAMPLING: [[f64; 12]; 4] = [
+    [
+        0.0017089843750,
+        0.0109863281250,
+        -0.0196533203125,
+        0.0332031250000,
+        -0.0594482421875,
+        0.1373291015625,
+        0.9721679687500,
+        -0.1022949218750,
+        0.0476074218750,
+        -0.0266113281250,
+        0.0148925781250,
+        -0.0083007812500,
+    ],
+    [
+        -0.0291748046875,
+        0.0292968750000,
+        -0.0517578125000,
+        0.0891113281250,
+        -0.1665039062500,
+        0.4650878906250,
+        0.7797851562500,
+        -0.2003173828125,
+        0.1015625000000,
+        -0.0582275390625,
+        0.0330810546875,
+        -0.0189208984375,
+    ],
+    [
+        -0.0189208984375,
+        0.0330810546875,
+        -0.0582275390625,
+        0.1015625000000,
+        -0.2003173828125,
+        0.7797851562500,
+        0.4650878906250,
+        -0.1665039062500,
+        0.0891113281250,
+        -0.0517578125000,
+        0.0292968750000,
+        -0.0291748046875,
+    ],
+    [
+        -0.0083007812500,
+        0.0148925781250,
+        -0.0266113281250,
+        0.0476074218750,
+        -0.1022949218750,
+        0.9721679687500,
+        0.1373291015625,
+        -0.0594482421875,
+        0.0332031250000,
+        -0.0196533203125,
+        0.0109863281250,
+        0.0017089843750,
+    ],
+];
+
+/// Estimates the peak between the samples of one channel
+#[cfg(feature = "decode")]
+#[derive(Debug, Clone, Default)]
+struct TruePeak {
+    history: [f64; 12],
+}
+
+#[cfg(feature = "decode")]
+impl TruePeak {
+    /// Adds a sample and returns the highest absolute value around it
+    fn push(&mut self, sample: f64) -> f64 {
+        self.history.copy_within(..11, 1);
+        self.history[0] = sample;
+        OVERSAMPLING
+            .iter()
+            .map(|phase| {
+                phase
+                    .iter()
+                    .zip(&self.history)
+                    .map(|(c, x)| c * x)
+                    .sum::<f64>()
+                    .abs()
+            })
+            .fold(sample.abs(), f64::max)
+    }
+}
@@ -0,0 +1,333 @@
+//! Keeping a directory in sync with a search
+//!
+//! [`sync`], or a [`Mirror`] for more options, archives every sound matching
+//! a query into a directory, and keeps it up to date when run again: sounds
+//! created since the last run are downloaded, the metadata of the known ones
+//! is refreshed, re-uploaded files are downloaded again, and deleted sounds
+//! are flagged. New sounds are found through a watermark on their creation
+//! date, so a run only walks the results it has not seen yet.
+//!
+//! Each sound gets its file, named after its id, and a `<id>.json` file
+//! holding its metadata. The state of the mirror, including the download
+//! queue, is kept in the [`MIRROR_STATE_DIR`] sub-directory, so an
+//! interrupted run is resumed by the next one.
+
+use crate::client::FreesoundClient;
+use crate::download::{DownloadJob, DownloadManager, DownloadSource, DownloadStatus, ProgressSink};
+use crate::error::Result;
+use crate::models::{Fields, SortOption, Sound};
+use crate::sync::SyncFailure;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Name of the directory holding the state of a mirror, inside the mirror
+pub const MIRROR_STATE_DIR: &str = ".mirror";
+
+/// File of the state directory holding the watermark and the known sounds
+const STATE_FILE: &str = "state.json";
+
+/// File of the state directory holding the download queue
+const QUEUE_FILE: &str = "queue.jsonl";
+
+/// File of the state directory holding the report of the last run
+const REPORT_FILE: &str = "report.json";
+
+/// Number of sounds fetched by each search page
+const PAGE_SIZE: i32 = 150;
+
+/// Outcome of a mirror run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MirrorReport {
+    /// Sounds found since the last run, queued for download
+    pub added: Vec<i32>,
+    /// Files downloaded by this run, including those left by the previous one
+    pub downloaded: Vec<PathBuf>,
+    /// Downloads that failed, to be retried by the next run
+    pub failed: Vec<SyncFailure>,
+    /// Sounds whose metadata changed since the last run
+    pub refreshed: Vec<i32>,
+    /// Sounds whose file was replaced, downloaded again
+    pub reuploaded: Vec<i32>,
+    /// Sounds deleted from Freesound, whose files are kept
+    pub deleted: Vec<i32>,
+    /// Creation date of the newest sound of the mirror
+    pub watermark: Option<String>,
+}
+
+/// What a mirror keeps between runs
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct State {
+    watermark: Option<String>,
+    sounds: BTreeMap<i32, MirroredSound>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct MirroredSound {
+    md5: String,
+    #[serde(default)]
+    deleted: bool,
+}
+
+/// Archives the sounds matching a query into a directory, run after run
+///
+/// The `sort`, `page`, `page_size` and `fields` parameters of the query are
+/// replaced; its filter is kept and narrowed to the sounds created since the
+/// watermark. Failing downloads do not stop the run; they are listed in the
+/// report and retried by the next run. Searching, or touching the directory,
+/// does stop it. The report is also written to the state directory.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::env;
+/// use freesound_rs::mirror::Mirror;
+/// use freesound_rs::{DownloadSource, FreesoundClient, PreviewQuality, SearchQueryBuilder};
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     dotenvy::dotenv().ok();
+///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+///     let client = FreesoundClient::new(api_key, None);
+/// let query = SearchQueryBuilder::new().filter("tag:field-recording").build();
+/// let report = Mirror::new(client, &query, "archive")
+///     .source(DownloadSource::Preview(PreviewQuality::HqOgg))
+///     .run()
+///     .await?;
+/// println!(
+///     "{} new, {} refreshed, {} deleted upstream",
+///     report.added.len(),
+///     report.refreshed.len(),
+///     report.deleted.len()
+/// );
+///      Ok(())
+///  }
+/// ```
+pub struct Mirror {
+    client: FreesoundClient,
+    query: Vec<(String, String)>,
+    dir: PathBuf,
+    source: DownloadSource,
+    manager: DownloadManager,
+}
+
+impl Mirror {
+    /// Create a mirror of the sounds matching `query` in `dir`
+    pub fn new(
+        client: FreesoundClient,
+        query: &[(String, String)],
+        dir: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            manager: DownloadManager::new(client.clone()),
+            client: client.without_search_cache(),
+            query: query.to_vec(),
+            dir: dir.into(),
+            source: DownloadSource::default(),
+        }
+    }
+
+    /// Set which file of each sound to download (defaults to the HQ MP3 preview)
+    pub fn source(mut self, source: DownloadSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Set the number of downloads running at the same time
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.manager = self.manager.concurrency(concurrency);
+        self
+    }
+
+    /// Report download progress to `sink`
+    pub fn progress(mut self, sink: impl ProgressSink + 'static) -> Self {
+        self.manager = self.manager.progress(sink);
+        self
+    }
+
+    /// Run the mirror and write its report
+    pub async fn run(self) -> Result<MirrorReport> {
+        let Self {
+            client,
+            query,
+            dir,
+            source,
+            manager,
+        } = self;
+        let job = |sound: &Sound| DownloadJob::new(&client, sound, source, &dir);
+        let state_dir = dir.join(MIRROR_STATE_DIR);
+        std::fs::create_dir_all(&state_dir)?;
+        let mut state = read_state(&state_dir)?;
+        let mut report = MirrorReport::default();
+        let mut manager = manager.persistent_queue(state_dir.join(QUEUE_FILE))?;
+        let mut queued: HashSet<PathBuf> = manager
+            .queued()
+            .iter()
+            .map(|job| job.destination.clone())
+            .collect();
+        let mut enqueue = |manager: &mut DownloadManager, job: DownloadJob| -> Result<()> {
+            if queued.insert(job.destination.clone()) {
+                manager.enqueue(job)?;
+            }
+            Ok(())
+        };
+
+        // Known sounds first, so the new ones are not refreshed right away
+        let known: Vec<i32> = state
+            .sounds
+            .iter()
+            .filter(|(_, sound)| !sound.deleted)
+            .map(|(id, _)| *id)
+            .collect();
+        let fields: Vec<String> = Fields::default_set().iter().map(String::from).collect();
+        let current = client.current_sounds(&known, &fields).await?;
+        for id in known {
+            let mirrored = state.sounds.get_mut(&id).expect("known sound");
+            match current.get(&id) {
+                Some(Some(sound)) => {
+                    if write_metadata(&dir, sound)? {
+                        report.refreshed.push(id);
+                    }
+                    if !mirrored.md5.is_empty()
+                        && !sound.md5.is_empty()
+                        && mirrored.md5 != sound.md5
+                    {
+                        enqueue(&mut manager, job(sound)?)?;
+                        report.reuploaded.push(id);
+                    }
+                    mirrored.md5 = sound.md5.clone();
+                }
+                Some(None) => {
+                    mirrored.deleted = true;
+                    report.deleted.push(id);
+                }
+                // Being processed again, it can only be refreshed later
+                None => {}
+            }
+        }
+
+        // Then the sounds created since the watermark, oldest first, starting
+        // over from the new watermark when Freesound serves no deeper page
+        loop {
+            let first = client.search(&round_query(&query, &state)).await?;
+            let mut pages = Box::pin(client.pages(first));
+            let mut found = false;
+            let mut more = false;
+            while let Some(page) = pages.try_next().await? {
+                more = page.next.is_some();
+                for sound in page.results {
+                    if state.sounds.contains_key(&sound.id) {
+                        continue;
+                    }
+                    found = true;
+                    write_metadata(&dir, &sound)?;
+                    enqueue(&mut manager, job(&sound)?)?;
+                    report.added.push(sound.id);
+                    if state.watermark.as_ref() < Some(&sound.created) {
+                        state.watermark = Some(sound.created.clone());
+                    }
+                    state.sounds.insert(
+                        sound.id,
+                        MirroredSound {
+                            md5: sound.md5,
+                            deleted: false,
+                        },
+                    );
+                }
+            }
+            if !more || !found {
+                break;
+            }
+        }
+
+        // The queue journal has the jobs, so a killed run resumes with them
+        write_json(&state_dir.join(STATE_FILE), &state)?;
+
+        for item in manager.run().await.items {
+            match item.status {
+                DownloadStatus::Downloaded => report.downloaded.push(item.job.destination),
+                DownloadStatus::Skipped => {}
+                DownloadStatus::Failed => report.failed.push(SyncFailure {
+                    sound_id: item.job.sound_id,
+                    destination: item.job.destination,
+                    error: item.error.unwrap_or_default(),
+                }),
+            }
+        }
+
+        report.watermark = state.watermark;
+        write_json(&state_dir.join(REPORT_FILE), &report)?;
+        Ok(report)
+    }
+}
+
+/// Mirror the sounds matching `query` into `dir`
+///
+/// Downloads HQ MP3 previews. See [`Mirror`] to change this default.
+pub async fn sync(
+    client: &FreesoundClient,
+    query: &[(String, String)],
+    dir: impl Into<PathBuf>,
+) -> Result<MirrorReport> {
+    Mirror::new(client.clone(), query, dir).run().await
+}
+
+fn read_state(state_dir: &Path) -> Result<State> {
+    match std::fs::read_to_string(state_dir.join(STATE_FILE)) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(State::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Writes the metadata of a sound, returning true if it changed
+fn write_metadata(dir: &Path, sound: &Sound) -> Result<bool> {
+    let path = dir.join(format!("{}.json", sound.id));
+    let json = serde_json::to_string_pretty(sound)?;
+    match std::fs::read_to_string(&path) {
+        Ok(existing) if existing == json => Ok(false),
+        Ok(_) => {
+            std::fs::write(&path, json)?;
+            Ok(true)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            std::fs::write(&path, json)?;
+            Ok(false)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Writes a JSON file, replacing the previous one once fully written
+fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+    let json = serde_json::to_vec_pretty(value)?;
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(format!(".{}.tmp", std::process::id()));
+    std::fs::write(&temporary, json)?;
+    std::fs::rename(&temporary, path)?;
+    Ok(())
+}
+
+/// Returns the query of the sounds created since the watermark
+fn round_query(base: &[(String, String)], state: &State) -> Vec<(String, String)> {
+    let mut filters = Vec::new();
+    let mut query = Vec::new();
+    for (key, value) in base {
+        match key.as_str() {
+            "filter" => filters.push(value.clone()),
+            "sort" | "page" | "page_size" | "fields" => {}
+            _ => query.push((key.clone(), value.clone())),
+        }
+    }
+    if let Some(watermark) = &state.watermark {
+        let date = watermark.trim_end_matches('Z');
+        filters.push(format!("created:[{date}Z TO *]"));
+    }
+    if !filters.is_empty() {
+        query.push(("filter".to_string(), filters.join(" ")));
+    }
+    query.push(("sort".to_string(), SortOption::CreatedAsc.to_string()));
+    query.push(("page_size".to_string(), PAGE_SIZE.to_string()));
+    query.push(("fields".to_string(), Fields::default_set().to_string()));
+    query
+}
@@ -0,0 +1,63 @@
+use super::key::Key;
+use serde::{Deserialize, Serialize};
+
+/// Timbral and musical descriptors computed by the AudioCommons extractor
+///
+/// Returned in the `ac_analysis` field of a sound, which must be explicitly
+/// requested with `fields`. Every value is optional as the extractor does not
+/// produce all of them for every sound.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AcAnalysis {
+    /// Integrated loudness, in LUFS
+    pub ac_loudness: Option<f32>,
+    /// Loudness range, in LU
+    pub ac_dynamic_range: Option<f32>,
+    /// Temporal centroid of the envelope, in seconds
+    pub ac_temporal_centroid: Option<f32>,
+    /// Logarithm of the attack time
+    pub ac_log_attack_time: Option<f32>,
+    /// Whether the sound is a single event (one-shot)
+    pub ac_single_event: Option<bool>,
+    /// Estimated tonality, e.g. "C major"
+    pub ac_tonality: Option<String>,
+    /// Confidence of the tonality estimation (0 to 1)
+    pub ac_tonality_confidence: Option<f32>,
+    /// Whether the sound is loopable
+    pub ac_loop: Option<bool>,
+    /// Estimated tempo, in BPM
+    pub ac_tempo: Option<f32>,
+    /// Confidence of the tempo estimation (0 to 1)
+    pub ac_tempo_confidence: Option<f32>,
+    /// Estimated MIDI note
+    pub ac_note_midi: Option<i32>,
+    /// Estimated note name, e.g. "A4"
+    pub ac_note_name: Option<String>,
+    /// Estimated note frequency, in Hz
+    pub ac_note_frequency: Option<f32>,
+    /// Confidence of the note estimation (0 to 1)
+    pub ac_note_confidence: Option<f32>,
+    /// Brightness estimation (0 to 100)
+    pub ac_brightness: Option<f32>,
+    /// Depth estimation (0 to 100)
+    pub ac_depth: Option<f32>,
+    /// Hardness estimation (0 to 100)
+    pub ac_hardness: Option<f32>,
+    /// Roughness estimation (0 to 100)
+    pub ac_roughness: Option<f32>,
+    /// Boominess estimation (0 to 100)
+    pub ac_boominess: Option<f32>,
+    /// Warmth estimation (0 to 100)
+    pub ac_warmth: Option<f32>,
+    /// Sharpness estimation (0 to 100)
+    pub ac_sharpness: Option<f32>,
+    /// Whether the sound is reverberated
+    pub ac_reverb: Option<bool>,
+}
+
+impl AcAnalysis {
+    /// Returns the estimated key, if the tonality could be parsed
+    pub fn key(&self) -> Option<Key> {
+        self.ac_tonality.as_deref()?.parse().ok()
+    }
+}
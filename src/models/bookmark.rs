@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// A bookmark category of the authenticated user
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BookmarkCategory {
+    /// The category's unique identifier
+    pub id: i32,
+    /// The URI for this category on the Freesound website
+    pub url: String,
+    /// The name the user gave to the category
+    pub name: String,
+    /// The number of sounds in the category
+    pub num_sounds: i32,
+    /// URI pointing to the list of sounds in the category
+    pub sounds: String,
+}
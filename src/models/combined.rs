@@ -0,0 +1,191 @@
+use super::similarity_space::SimilaritySpace;
+use super::sound::Sound;
+use crate::error::{FreesoundError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Response of a combined search
+///
+/// Combined searches do not return a total count nor numbered pages: `more`
+/// links to the next batch of results, if any.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CombinedSearchResponse {
+    /// Sounds of this batch
+    pub results: Vec<Sound>,
+    /// Link to the next batch of results (null if none)
+    pub more: Option<String>,
+}
+
+/// Builder for combined searches, mixing a text query with a descriptor target
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{CombinedSearchBuilder, SimilaritySpace};
+///
+/// let query = CombinedSearchBuilder::new()
+///     .query("piano")
+///     .target("rhythm.bpm:120")
+///     .weights(0.7, 0.3)
+///     .build()?;
+/// assert_eq!(
+///     query,
+///     [
+///         ("query".to_string(), "piano".to_string()),
+///         ("target".to_string(), "rhythm.bpm:120".to_string()),
+///         ("weights".to_string(), "0.7,0.3".to_string()),
+///     ]
+/// );
+///
+/// let query = CombinedSearchBuilder::new()
+///     .target("rhythm.bpm:120")
+///     .similarity_space(SimilaritySpace::FreesoundClassic)
+///     .build()?;
+/// assert_eq!(query[1], ("similarity_space".to_string(), "freesound_classic".to_string()));
+///
+/// assert!(CombinedSearchBuilder::new().query("piano").weights(-1.0, 1.0).build().is_err());
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CombinedSearchBuilder {
+    query: Option<String>,
+    filter: Option<String>,
+    target: Option<String>,
+    descriptors_filter: Option<String>,
+    weights: Option<(f32, f32)>,
+    page_size: Option<i32>,
+    fields: Option<Vec<String>>,
+    descriptors: Option<Vec<String>>,
+    normalized: Option<bool>,
+    similarity_space: Option<SimilaritySpace>,
+}
+
+impl CombinedSearchBuilder {
+    /// Create a new CombinedSearchBuilder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the search query text
+    pub fn query<S: Into<String>>(mut self, query: S) -> Self {
+        self.query = Some(query.into());
+        self
+    }
+
+    /// Set the filter on the metadata, as for [`SearchQueryBuilder::filter`](crate::SearchQueryBuilder::filter)
+    pub fn filter<S: Into<String>>(mut self, filter: S) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    /// Set the descriptor values the results should be close to
+    pub fn target<S: Into<String>>(mut self, target: S) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Set the filter on the descriptors
+    pub fn descriptors_filter<S: Into<String>>(mut self, filter: S) -> Self {
+        self.descriptors_filter = Some(filter.into());
+        self
+    }
+
+    /// Set how much the text query and the descriptor target weigh in the ranking
+    ///
+    /// Weights must be finite and non-negative, and not both zero; they are
+    /// checked by [`build`](Self::build).
+    pub fn weights(mut self, text: f32, content: f32) -> Self {
+        self.weights = Some((text, content));
+        self
+    }
+
+    /// Set the number of results per batch
+    pub fn page_size(mut self, size: i32) -> Self {
+        self.page_size = Some(size);
+        self
+    }
+
+    /// Set the fields to return
+    pub fn fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.fields = Some(fields.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    /// Set the descriptors to return
+    pub fn descriptors<I, S>(mut self, descriptors: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.descriptors = Some(descriptors.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    /// Set whether to normalize descriptor values
+    pub fn normalized(mut self, normalized: bool) -> Self {
+        self.normalized = Some(normalized);
+        self
+    }
+
+    /// Set the space in which the descriptor target is matched
+    pub fn similarity_space(mut self, space: SimilaritySpace) -> Self {
+        self.similarity_space = Some(space);
+        self
+    }
+
+    /// Build the query parameters
+    ///
+    /// Fails with [`InvalidQuery`](FreesoundError::InvalidQuery) if the search
+    /// has neither a text query nor a target, or if the weights are invalid.
+    pub fn build(&self) -> Result<Vec<(String, String)>> {
+        if self.query.is_none() && self.target.is_none() {
+            return Err(FreesoundError::InvalidQuery(
+                "a combined search needs a text query or a target".to_string(),
+            ));
+        }
+
+        let mut params = Vec::new();
+        let mut push = |name: &str, value: String| params.push((name.to_string(), value));
+        if let Some(ref query) = self.query {
+            push("query", query.clone());
+        }
+        if let Some(ref filter) = self.filter {
+            push("filter", filter.clone());
+        }
+        if let Some(ref target) = self.target {
+            push("target", target.clone());
+        }
+        if let Some(ref filter) = self.descriptors_filter {
+            push("descriptors_filter", filter.clone());
+        }
+        if let Some((text, content)) = self.weights {
+            let valid = |w: f32| w.is_finite() && w >= 0.0;
+            if !valid(text) || !valid(content) || text + content == 0.0 {
+                return Err(FreesoundError::InvalidQuery(format!(
+                    "invalid weights {text} and {content}: they must be non-negative and not both zero"
+                )));
+            }
+            push("weights", format!("{text},{content}"));
+        }
+        if let Some(size) = self.page_size {
+            push("page_size", size.to_string());
+        }
+        if let Some(ref fields) = self.fields {
+            push("fields", fields.join(","));
+        }
+        if let Some(ref descriptors) = self.descriptors {
+            push("descriptors", descriptors.join(","));
+        }
+        if let Some(normalized) = self.normalized {
+            push("normalized", if normalized { "1" } else { "0" }.to_string());
+        }
+        if let Some(ref space) = self.similarity_space {
+            push("similarity_space", space.to_string());
+        }
+        Ok(params)
+    }
+}
@@ -0,0 +1,14 @@
+use super::ids::Username;
+use serde::{Deserialize, Serialize};
+
+/// A comment on a sound
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Comment {
+    /// The username of the comment's author
+    pub username: Username,
+    /// The text of the comment
+    pub comment: String,
+    /// The date when the comment was posted
+    pub created: String,
+}
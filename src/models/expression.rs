@@ -0,0 +1,179 @@
+use super::search::{SearchQueryBuilder, SortOption};
+use crate::error::{FreesoundError, Result};
+
+impl SearchQueryBuilder {
+    /// Parse a compact, human-written search expression into a query builder
+    ///
+    /// Words are used as the text query, while `key:value` pairs are turned into
+    /// filters and options. Values containing spaces can be double-quoted.
+    ///
+    /// Supported keys:
+    ///
+    /// * `dur` / `duration` - a duration in seconds or a range (`1..5`, `..2`, `10..`)
+    /// * `license` - `cc0`, `by` or `by-nc`
+    /// * `tag` - a tag the sounds must have (may be repeated)
+    /// * `type` - a file type (`wav`, `aiff`, `ogg`, `mp3`, `m4a`, `flac`)
+    /// * `user` / `username` - the uploader's username
+    /// * `pack` - the pack name
+    /// * `sort` - `score`, `rating`, `downloads`, `newest`, `oldest`, `longest`,
+    ///   `shortest` or any [`SortOption`] API name such as `rating_asc`
+    /// * `page` / `page_size` - pagination
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::SearchQueryBuilder;
+    ///
+    /// let query = SearchQueryBuilder::parse("piano dur:1..5 license:cc0 sort:rating")?.build();
+    /// assert!(query.contains(&("query".to_string(), "piano".to_string())));
+    /// assert!(query.contains(&(
+    ///     "filter".to_string(),
+    ///     "duration:[1 TO 5] license:\"Creative Commons 0\"".to_string()
+    /// )));
+    /// assert!(query.contains(&("sort".to_string(), "rating_desc".to_string())));
+    ///
+    /// assert!(SearchQueryBuilder::parse("piano dur:5..1").is_err());
+    /// assert!(SearchQueryBuilder::parse("piano color:blue").is_err());
+    /// # Ok::<(), freesound_rs::FreesoundError>(())
+    /// ```
+    pub fn parse(expression: &str) -> Result<Self> {
+        let mut builder = Self::new();
+        let mut words = Vec::new();
+        let mut filters = Vec::new();
+
+        for token in tokenize(expression)? {
+            let Some((key, value)) = token.split_once(':').filter(|(k, _)| !k.is_empty()) else {
+                words.push(token);
+                continue;
+            };
+
+            if value.is_empty() {
+                return Err(invalid(format!("missing value for '{key}'")));
+            }
+
+            match key {
+                "dur" | "duration" => filters.push(format!("duration:{}", parse_range(value)?)),
+                "license" => filters.push(format!("license:\"{}\"", parse_license(value)?)),
+                "tag" => filters.push(format!("tag:{}", quote(value))),
+                "type" => filters.push(format!("type:{}", parse_type(value)?)),
+                "user" | "username" => filters.push(format!("username:{}", quote(value))),
+                "pack" => filters.push(format!("pack:{}", quote(value))),
+                "sort" => builder = builder.sort(parse_sort(value)?),
+                "page" => builder = builder.page(parse_positive(key, value)?),
+                "page_size" => builder = builder.page_size(parse_positive(key, value)?),
+                other => return Err(invalid(format!("unknown key '{other}'"))),
+            }
+        }
+
+        if !words.is_empty() {
+            builder = builder.query(words.join(" "));
+        }
+        if !filters.is_empty() {
+            builder = builder.filter(filters.join(" "));
+        }
+
+        Ok(builder)
+    }
+}
+
+fn invalid(message: String) -> FreesoundError {
+    FreesoundError::InvalidQuery(message)
+}
+
+/// Splits an expression on whitespace, keeping double-quoted sections together
+fn tokenize(expression: &str) -> Result<Vec<String>> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in expression.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(invalid("unterminated quote".to_string()));
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    Ok(tokens)
+}
+
+fn quote(value: &str) -> String {
+    if value.contains(char::is_whitespace) {
+        format!("\"{value}\"")
+    } else {
+        value.to_string()
+    }
+}
+
+fn parse_seconds(value: &str) -> Result<f32> {
+    match value.parse::<f32>() {
+        Ok(seconds) if seconds.is_finite() && seconds >= 0.0 => Ok(seconds),
+        _ => Err(invalid(format!("invalid duration '{value}'"))),
+    }
+}
+
+/// Turns `a..b`, `a..`, `..b` or `a` into a Solr range
+fn parse_range(value: &str) -> Result<String> {
+    let Some((min, max)) = value.split_once("..") else {
+        return Ok(parse_seconds(value)?.to_string());
+    };
+
+    let min = (!min.is_empty()).then(|| parse_seconds(min)).transpose()?;
+    let max = (!max.is_empty()).then(|| parse_seconds(max)).transpose()?;
+
+    if let (Some(min), Some(max)) = (min, max)
+        && min > max
+    {
+        return Err(invalid(format!("empty duration range '{value}'")));
+    }
+
+    let bound = |b: Option<f32>| b.map_or_else(|| "*".to_string(), |b| b.to_string());
+    Ok(format!("[{} TO {}]", bound(min), bound(max)))
+}
+
+fn parse_license(value: &str) -> Result<&'static str> {
+    match value.to_lowercase().as_str() {
+        "cc0" | "0" => Ok("Creative Commons 0"),
+        "by" | "attribution" => Ok("Attribution"),
+        "by-nc" | "noncommercial" => Ok("Attribution NonCommercial"),
+        _ => Err(invalid(format!("unknown license '{value}'"))),
+    }
+}
+
+fn parse_type(value: &str) -> Result<String> {
+    let value = value.to_lowercase();
+    match value.as_str() {
+        "wav" | "aif" | "aiff" | "ogg" | "mp3" | "m4a" | "flac" => Ok(value),
+        _ => Err(invalid(format!("unknown file type '{value}'"))),
+    }
+}
+
+fn parse_sort(value: &str) -> Result<SortOption> {
+    match value {
+        "rating" => Ok(SortOption::RatingDesc),
+        "downloads" => Ok(SortOption::DownloadsDesc),
+        "newest" | "created" => Ok(SortOption::CreatedDesc),
+        "oldest" => Ok(SortOption::CreatedAsc),
+        "longest" | "duration" => Ok(SortOption::DurationDesc),
+        "shortest" => Ok(SortOption::DurationAsc),
+        other => other.parse().map_err(invalid),
+    }
+}
+
+fn parse_positive(key: &str, value: &str) -> Result<i32> {
+    match value.parse::<i32>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(invalid(format!("'{key}' must be a positive integer"))),
+    }
+}
@@ -0,0 +1,337 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A field of a [`Sound`](crate::Sound), as named by the API
+///
+/// Fields can be passed to the `fields` method of the query builders, and
+/// checked with [`Sound::has_field`](crate::Sound::has_field) to tell a value
+/// that was not requested from an actual zero or empty value.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{Field, SearchQueryBuilder};
+///
+/// let query = SearchQueryBuilder::new()
+///     .query("rain")
+///     .fields([Field::Id, Field::Name, Field::Duration])
+///     .build();
+/// assert_eq!(query[1].1, "id,name,duration");
+/// assert_eq!("num_downloads".parse(), Ok(Field::NumDownloads));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+    Id,
+    Url,
+    Name,
+    Tags,
+    Description,
+    Geotag,
+    Created,
+    License,
+    #[serde(rename = "type")]
+    Type,
+    Channels,
+    Filesize,
+    Md5,
+    Bitrate,
+    Bitdepth,
+    Duration,
+    Samplerate,
+    Username,
+    Pack,
+    Download,
+    Bookmark,
+    Previews,
+    Images,
+    NumDownloads,
+    AvgRating,
+    NumRatings,
+    Rate,
+    Comments,
+    NumComments,
+    Comment,
+    SimilarSounds,
+    Analysis,
+    AnalysisStats,
+    AnalysisFrames,
+    AcAnalysis,
+}
+
+impl Field {
+    /// All the fields, in the order of the API documentation
+    pub const ALL: [Field; 34] = [
+        Self::Id,
+        Self::Url,
+        Self::Name,
+        Self::Tags,
+        Self::Description,
+        Self::Geotag,
+        Self::Created,
+        Self::License,
+        Self::Type,
+        Self::Channels,
+        Self::Filesize,
+        Self::Md5,
+        Self::Bitrate,
+        Self::Bitdepth,
+        Self::Duration,
+        Self::Samplerate,
+        Self::Username,
+        Self::Pack,
+        Self::Download,
+        Self::Bookmark,
+        Self::Previews,
+        Self::Images,
+        Self::NumDownloads,
+        Self::AvgRating,
+        Self::NumRatings,
+        Self::Rate,
+        Self::Comments,
+        Self::NumComments,
+        Self::Comment,
+        Self::SimilarSounds,
+        Self::Analysis,
+        Self::AnalysisStats,
+        Self::AnalysisFrames,
+        Self::AcAnalysis,
+    ];
+
+    /// Returns the name of the field in API responses
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Url => "url",
+            Self::Name => "name",
+            Self::Tags => "tags",
+            Self::Description => "description",
+            Self::Geotag => "geotag",
+            Self::Created => "created",
+            Self::License => "license",
+            Self::Type => "type",
+            Self::Channels => "channels",
+            Self::Filesize => "filesize",
+            Self::Md5 => "md5",
+            Self::Bitrate => "bitrate",
+            Self::Bitdepth => "bitdepth",
+            Self::Duration => "duration",
+            Self::Samplerate => "samplerate",
+            Self::Username => "username",
+            Self::Pack => "pack",
+            Self::Download => "download",
+            Self::Bookmark => "bookmark",
+            Self::Previews => "previews",
+            Self::Images => "images",
+            Self::NumDownloads => "num_downloads",
+            Self::AvgRating => "avg_rating",
+            Self::NumRatings => "num_ratings",
+            Self::Rate => "rate",
+            Self::Comments => "comments",
+            Self::NumComments => "num_comments",
+            Self::Comment => "comment",
+            Self::SimilarSounds => "similar_sounds",
+            Self::Analysis => "analysis",
+            Self::AnalysisStats => "analysis_stats",
+            Self::AnalysisFrames => "analysis_frames",
+            Self::AcAnalysis => "ac_analysis",
+        }
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Field {
+    type Err = UnknownField;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|field| field.as_str() == s)
+            .ok_or_else(|| UnknownField(s.to_string()))
+    }
+}
+
+/// The error of parsing a name that is not a [`Field`]
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{Field, UnknownField};
+///
+/// let error = "colour".parse::<Field>().unwrap_err();
+/// assert_eq!(error, UnknownField("colour".to_string()));
+/// assert_eq!(error.to_string(), "unknown field: colour");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownField(pub String);
+
+impl fmt::Display for UnknownField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown field: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownField {}
+
+impl From<Field> for String {
+    fn from(field: Field) -> Self {
+        field.as_str().to_string()
+    }
+}
+
+/// A set of [`Field`]s, to build the `fields` parameter of a request
+///
+/// Start from the set the API returns when no field is requested, then add
+/// or remove fields, rather than listing every field to get one more. The
+/// fields keep the order in which they were added.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{Field, Fields, SearchQueryBuilder};
+///
+/// let fields = Fields::default_set()
+///     .plus(Field::Analysis)
+///     .minus(Field::Description);
+/// assert!(fields.contains(Field::Analysis));
+/// assert!(!fields.contains(Field::Description));
+///
+/// let query = SearchQueryBuilder::new()
+///     .query("rain")
+///     .fields(Fields::search_default().plus(Field::Previews))
+///     .build();
+/// assert_eq!(query[1].1, "id,name,tags,username,license,previews");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Fields(Vec<Field>);
+
+impl Fields {
+    /// Creates an empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The fields of a sound returned by default by the sound endpoints
+    ///
+    /// That is every field but the analysis ones, which are only returned on
+    /// request.
+    pub fn default_set() -> Self {
+        Field::ALL
+            .into_iter()
+            .filter(|field| {
+                !matches!(
+                    field,
+                    Field::Analysis | Field::AnalysisStats | Field::AnalysisFrames
+                )
+            })
+            .collect()
+    }
+
+    /// The fields of a sound returned by default by the search endpoints
+    pub fn search_default() -> Self {
+        [
+            Field::Id,
+            Field::Name,
+            Field::Tags,
+            Field::Username,
+            Field::License,
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    /// Adds a field, if not in the set yet
+    pub fn plus(mut self, field: Field) -> Self {
+        if !self.contains(field) {
+            self.0.push(field);
+        }
+        self
+    }
+
+    /// Removes a field
+    pub fn minus(mut self, field: Field) -> Self {
+        self.0.retain(|f| *f != field);
+        self
+    }
+
+    /// Returns true if the field is in the set
+    pub fn contains(&self, field: Field) -> bool {
+        self.0.contains(&field)
+    }
+
+    /// Returns the fields, in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = Field> + '_ {
+        self.0.iter().copied()
+    }
+
+    /// Returns the number of fields
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl FromIterator<Field> for Fields {
+    fn from_iter<I: IntoIterator<Item = Field>>(iter: I) -> Self {
+        iter.into_iter().fold(Self::new(), Self::plus)
+    }
+}
+
+impl IntoIterator for Fields {
+    type Item = Field;
+    type IntoIter = std::vec::IntoIter<Field>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl fmt::Display for Fields {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = self.iter().map(|field| field.as_str()).collect();
+        f.write_str(&names.join(","))
+    }
+}
+
+/// A type search results can be read into, made of the given sound fields
+///
+/// [`FreesoundClient::search_as`](crate::FreesoundClient::search_as) asks for
+/// exactly these fields, so the type and the `fields` parameter cannot drift
+/// apart. With the `macros` feature, both this trait and `Deserialize` can be
+/// derived from the struct with [`FreesoundFields`](crate::FreesoundFields).
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{Field, SearchFields};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Hit {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// impl SearchFields for Hit {
+///     const FIELDS: &'static [Field] = &[Field::Id, Field::Name];
+/// }
+///
+/// let hit: Hit = serde_json::from_str(r#"{"id": 1, "name": "Rain"}"#)?;
+/// assert_eq!((hit.id, hit.name.as_str()), (1, "Rain"));
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+pub trait SearchFields: DeserializeOwned {
+    /// The fields the type is read from
+    const FIELDS: &'static [Field];
+}
@@ -0,0 +1,245 @@
+use super::key::Key;
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
+
+/// File types stored without loss, kept by [`FilterBuilder::lossless_only`]
+pub const LOSSLESS_TYPES: [&str; 4] = ["wav", "aif", "aiff", "flac"];
+
+/// Builder for search filters
+///
+/// Each method appends a condition, and all conditions must match. The result
+/// is passed to [`SearchQueryBuilder::filter`](crate::SearchQueryBuilder::filter).
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{FilterBuilder, SearchQueryBuilder};
+///
+/// let filter = FilterBuilder::new()
+///     .field("tag", "drum")
+///     .range("duration", 0.5..=2.0)
+///     .ac_single_event(true)
+///     .build();
+/// assert_eq!(filter, "tag:drum duration:[0.5 TO 2] ac_single_event:true");
+///
+/// let query = SearchQueryBuilder::new().query("snare").filter(filter).build();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FilterBuilder {
+    conditions: Vec<String>,
+}
+
+impl FilterBuilder {
+    /// Create a new, empty FilterBuilder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a raw condition, in Solr syntax
+    pub fn raw<S: Into<String>>(mut self, condition: S) -> Self {
+        self.conditions.push(condition.into());
+        self
+    }
+
+    /// Require a field to have a value
+    ///
+    /// Values containing whitespace are quoted.
+    pub fn field<V: fmt::Display>(self, name: &str, value: V) -> Self {
+        let value = value.to_string();
+        if value.contains(char::is_whitespace) {
+            self.raw(format!("{name}:\"{}\"", value.replace('"', "\\\"")))
+        } else {
+            self.raw(format!("{name}:{value}"))
+        }
+    }
+
+    /// Require a numeric field to be within a range
+    ///
+    /// Any Rust range can be used: `1..5`, `1..=5`, `1..` or `..=5`.
+    pub fn range<T: fmt::Display>(self, name: &str, range: impl RangeBounds<T>) -> Self {
+        let (open, min) = match range.start_bound() {
+            Bound::Included(v) => ('[', v.to_string()),
+            Bound::Excluded(v) => ('{', v.to_string()),
+            Bound::Unbounded => ('[', "*".to_string()),
+        };
+        let (max, close) = match range.end_bound() {
+            Bound::Included(v) => (v.to_string(), ']'),
+            Bound::Excluded(v) => (v.to_string(), '}'),
+            Bound::Unbounded => ("*".to_string(), ']'),
+        };
+        self.raw(format!("{name}:{open}{min} TO {max}{close}"))
+    }
+
+    /// Filter on the AudioCommons loudness estimation, in LUFS
+    pub fn ac_loudness(self, range: impl RangeBounds<f32>) -> Self {
+        self.range("ac_loudness", range)
+    }
+
+    /// Filter on the AudioCommons dynamic range estimation, in LU
+    pub fn ac_dynamic_range(self, range: impl RangeBounds<f32>) -> Self {
+        self.range("ac_dynamic_range", range)
+    }
+
+    /// Filter on the AudioCommons brightness estimation (0 to 100)
+    pub fn ac_brightness(self, range: impl RangeBounds<f32>) -> Self {
+        self.range("ac_brightness", range)
+    }
+
+    /// Filter on the AudioCommons depth estimation (0 to 100)
+    pub fn ac_depth(self, range: impl RangeBounds<f32>) -> Self {
+        self.range("ac_depth", range)
+    }
+
+    /// Filter on the AudioCommons hardness estimation (0 to 100)
+    pub fn ac_hardness(self, range: impl RangeBounds<f32>) -> Self {
+        self.range("ac_hardness", range)
+    }
+
+    /// Filter on the AudioCommons roughness estimation (0 to 100)
+    pub fn ac_roughness(self, range: impl RangeBounds<f32>) -> Self {
+        self.range("ac_roughness", range)
+    }
+
+    /// Filter on the AudioCommons boominess estimation (0 to 100)
+    pub fn ac_boominess(self, range: impl RangeBounds<f32>) -> Self {
+        self.range("ac_boominess", range)
+    }
+
+    /// Filter on the AudioCommons warmth estimation (0 to 100)
+    pub fn ac_warmth(self, range: impl RangeBounds<f32>) -> Self {
+        self.range("ac_warmth", range)
+    }
+
+    /// Filter on the AudioCommons sharpness estimation (0 to 100)
+    pub fn ac_sharpness(self, range: impl RangeBounds<f32>) -> Self {
+        self.range("ac_sharpness", range)
+    }
+
+    /// Filter on the AudioCommons tempo estimation, in BPM
+    pub fn ac_tempo(self, range: impl RangeBounds<u32>) -> Self {
+        self.range("ac_tempo", range)
+    }
+
+    /// Filter on the MIDI note estimated by AudioCommons (0 to 127)
+    pub fn ac_note_midi(self, range: impl RangeBounds<u8>) -> Self {
+        self.range("ac_note_midi", range)
+    }
+
+    /// Filter on the note name estimated by AudioCommons, e.g. `"A4"`
+    pub fn ac_note_name(self, note: &str) -> Self {
+        self.field("ac_note_name", note)
+    }
+
+    /// Filter on the tonality estimated by AudioCommons, e.g. `"C major"`
+    pub fn ac_tonality(self, tonality: &str) -> Self {
+        self.field("ac_tonality", tonality)
+    }
+
+    /// Keep only sounds that AudioCommons considers to be a single event (or not)
+    pub fn ac_single_event(self, single_event: bool) -> Self {
+        self.field("ac_single_event", single_event)
+    }
+
+    /// Keep only sounds that AudioCommons considers loopable (or not)
+    pub fn ac_loop(self, is_loop: bool) -> Self {
+        self.field("ac_loop", is_loop)
+    }
+
+    /// Keep only sounds that AudioCommons considers reverberated (or not)
+    pub fn ac_reverb(self, reverb: bool) -> Self {
+        self.field("ac_reverb", reverb)
+    }
+
+    /// Keep only sounds whose estimated tempo is within a range, in BPM
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::{FilterBuilder, Key};
+    ///
+    /// let filter = FilterBuilder::new().bpm(118..=126).key(Key::AMinor).build();
+    /// assert_eq!(filter, "ac_tempo:[118 TO 126] ac_tonality:\"A minor\"");
+    /// ```
+    pub fn bpm(self, range: impl RangeBounds<u32>) -> Self {
+        self.ac_tempo(range)
+    }
+
+    /// Keep only sounds whose estimated key is `key`
+    pub fn key(self, key: Key) -> Self {
+        self.ac_tonality(key.as_str())
+    }
+
+    /// Keep only sounds whose estimated key is one of `keys`
+    ///
+    /// Useful to also accept the relative key, as estimations often confuse them.
+    pub fn any_key(self, keys: &[Key]) -> Self {
+        match keys {
+            [] => self,
+            [key] => self.key(*key),
+            keys => {
+                let keys: Vec<_> = keys.iter().map(|k| format!("\"{k}\"")).collect();
+                self.raw(format!("ac_tonality:({})", keys.join(" OR ")))
+            }
+        }
+    }
+
+    /// Keep only sounds in a lossless format ([`LOSSLESS_TYPES`])
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::FilterBuilder;
+    ///
+    /// let filter = FilterBuilder::new().lossless_only().min_samplerate(48_000).min_bitdepth(24);
+    /// assert_eq!(
+    ///     filter.build(),
+    ///     "type:(wav OR aif OR aiff OR flac) samplerate:[48000 TO *] bitdepth:[24 TO *]"
+    /// );
+    /// assert_eq!(filter, FilterBuilder::new().pro_quality());
+    /// ```
+    pub fn lossless_only(self) -> Self {
+        self.raw(format!("type:({})", LOSSLESS_TYPES.join(" OR ")))
+    }
+
+    /// Keep only sounds with a sample rate of at least `samplerate`, in Hz
+    pub fn min_samplerate(self, samplerate: u32) -> Self {
+        self.range("samplerate", samplerate..)
+    }
+
+    /// Keep only sounds with a bit depth of at least `bitdepth`
+    ///
+    /// Freesound reports a bit depth of 0 for lossy formats, so they are left out.
+    pub fn min_bitdepth(self, bitdepth: u8) -> Self {
+        self.range("bitdepth", bitdepth..)
+    }
+
+    /// Keep only lossless sounds of at least 48 kHz and 24 bits
+    ///
+    /// The "pro quality" toggle of sample browsers, see
+    /// [`lossless_only`](Self::lossless_only).
+    pub fn pro_quality(self) -> Self {
+        self.lossless_only().min_samplerate(48_000).min_bitdepth(24)
+    }
+
+    /// Returns true if no condition was added
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    /// Build the filter string
+    pub fn build(&self) -> String {
+        self.conditions.join(" ")
+    }
+}
+
+impl fmt::Display for FilterBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.build())
+    }
+}
+
+impl From<FilterBuilder> for String {
+    fn from(filter: FilterBuilder) -> Self {
+        filter.build()
+    }
+}
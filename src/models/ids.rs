@@ -0,0 +1,163 @@
+use crate::error::{FreesoundError, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// The identifier of a pack
+///
+/// Pack endpoints take a `PackId` rather than a bare integer, so a sound id
+/// cannot be passed by mistake.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::PackId;
+///
+/// let id: PackId = "9678".parse()?;
+/// assert_eq!(id, PackId(9678));
+/// assert_eq!(id.to_string(), "9678");
+/// assert!("pack".parse::<PackId>().is_err());
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct PackId(pub i32);
+
+impl fmt::Display for PackId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for PackId {
+    type Err = FreesoundError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        s.trim()
+            .parse()
+            .map(Self)
+            .map_err(|_| FreesoundError::InvalidRequest(format!("invalid pack id '{s}'")))
+    }
+}
+
+impl From<PackId> for i32 {
+    fn from(id: PackId) -> Self {
+        id.0
+    }
+}
+
+/// The name of a Freesound user
+///
+/// Parsing rejects names that would not fit in a URL path: empty names, and
+/// names with whitespace, `/`, `?` or `#`. Usernames read from the API are
+/// taken as they are.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::Username;
+///
+/// let username: Username = "Jovica".parse()?;
+/// assert_eq!(username, "Jovica");
+/// assert_eq!(username.len(), 6);
+/// assert!("".parse::<Username>().is_err());
+/// assert!("../packs".parse::<Username>().is_err());
+/// assert!("..".parse::<Username>().is_err());
+/// assert!("50%off".parse::<Username>().is_err());
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Username(String);
+
+impl Username {
+    /// Returns the username as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Wraps a username read from a response, unchecked like deserialized ones
+    pub(crate) fn from_response(username: String) -> Self {
+        Self(username)
+    }
+
+    /// Returns the username percent-encoded for use as a URL path segment
+    ///
+    /// Deserialized usernames are not checked, so they are encoded anyway.
+    /// Names made of dots only are refused: URL parsers resolve them, encoded
+    /// or not, as relative segments.
+    pub(crate) fn path_segment(&self) -> Result<String> {
+        if self.0.is_empty() || self.0.chars().all(|c| c == '.') {
+            return Err(invalid(&self.0));
+        }
+        let mut segment = String::with_capacity(self.0.len());
+        for byte in self.0.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    segment.push(byte as char)
+                }
+                _ => segment.push_str(&format!("%{byte:02X}")),
+            }
+        }
+        Ok(segment)
+    }
+}
+
+impl fmt::Display for Username {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Username {
+    type Err = FreesoundError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.is_empty()
+            || s.chars().all(|c| c == '.')
+            || s.chars().any(|c| c.is_whitespace() || "/?#%".contains(c))
+        {
+            return Err(invalid(s));
+        }
+        Ok(Self(s.to_string()))
+    }
+}
+
+fn invalid(username: &str) -> FreesoundError {
+    FreesoundError::InvalidRequest(format!("invalid username '{username}'"))
+}
+
+impl Deref for Username {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Username {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Username {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Username {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl From<Username> for String {
+    fn from(username: Username) -> Self {
+        username.0
+    }
+}
@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A musical key, as estimated by Freesound's tonal analysis
+///
+/// Names follow the analysis output: flats for Eb, Ab and Bb, sharps for C# and
+/// F#. Parsing also accepts enharmonic spellings and short forms like `"Am"`.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::Key;
+///
+/// assert_eq!(Key::AMinor.as_str(), "A minor");
+/// assert_eq!("Am".parse::<Key>(), Ok(Key::AMinor));
+/// assert_eq!("D# major".parse::<Key>(), Ok(Key::EFlatMajor));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Key {
+    CMajor,
+    CMinor,
+    CSharpMajor,
+    CSharpMinor,
+    DMajor,
+    DMinor,
+    EFlatMajor,
+    EFlatMinor,
+    EMajor,
+    EMinor,
+    FMajor,
+    FMinor,
+    FSharpMajor,
+    FSharpMinor,
+    GMajor,
+    GMinor,
+    AFlatMajor,
+    AFlatMinor,
+    AMajor,
+    AMinor,
+    BFlatMajor,
+    BFlatMinor,
+    BMajor,
+    BMinor,
+}
+
+const KEYS: [Key; 24] = [
+    Key::CMajor,
+    Key::CMinor,
+    Key::CSharpMajor,
+    Key::CSharpMinor,
+    Key::DMajor,
+    Key::DMinor,
+    Key::EFlatMajor,
+    Key::EFlatMinor,
+    Key::EMajor,
+    Key::EMinor,
+    Key::FMajor,
+    Key::FMinor,
+    Key::FSharpMajor,
+    Key::FSharpMinor,
+    Key::GMajor,
+    Key::GMinor,
+    Key::AFlatMajor,
+    Key::AFlatMinor,
+    Key::AMajor,
+    Key::AMinor,
+    Key::BFlatMajor,
+    Key::BFlatMinor,
+    Key::BMajor,
+    Key::BMinor,
+];
+
+const TONICS: [&str; 12] = [
+    "C", "C#", "D", "Eb", "E", "F", "F#", "G", "Ab", "A", "Bb", "B",
+];
+
+impl Key {
+    /// Returns all 24 keys
+    pub fn all() -> &'static [Key] {
+        &KEYS
+    }
+
+    /// Returns the key with the given tonic and mode
+    ///
+    /// `pitch_class` is counted in semitones from C, modulo 12.
+    pub fn new(pitch_class: u8, minor: bool) -> Self {
+        KEYS[(pitch_class % 12) as usize * 2 + minor as usize]
+    }
+
+    /// Returns the tonic as a pitch class, in semitones from C
+    pub fn pitch_class(&self) -> u8 {
+        (*self as u8) / 2
+    }
+
+    /// Returns true for minor keys
+    pub fn is_minor(&self) -> bool {
+        (*self as u8) % 2 == 1
+    }
+
+    /// Returns the tonic name, e.g. "Eb"
+    pub fn tonic(&self) -> &'static str {
+        TONICS[self.pitch_class() as usize]
+    }
+
+    /// Returns the scale name, "major" or "minor"
+    pub fn scale(&self) -> &'static str {
+        if self.is_minor() { "minor" } else { "major" }
+    }
+
+    /// Returns the key name as used by the API, e.g. "A minor"
+    pub fn as_str(&self) -> &'static str {
+        const NAMES: [&str; 24] = [
+            "C major", "C minor", "C# major", "C# minor", "D major", "D minor", "Eb major",
+            "Eb minor", "E major", "E minor", "F major", "F minor", "F# major", "F# minor",
+            "G major", "G minor", "Ab major", "Ab minor", "A major", "A minor", "Bb major",
+            "Bb minor", "B major", "B minor",
+        ];
+        NAMES[*self as usize]
+    }
+
+    /// Returns the relative key (A minor for C major and vice versa)
+    pub fn relative(&self) -> Self {
+        if self.is_minor() {
+            Self::new(self.pitch_class() + 3, false)
+        } else {
+            Self::new(self.pitch_class() + 9, true)
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("unknown key: {s}");
+        let s = s.trim();
+
+        let mut chars = s.chars();
+        let letter = chars.next().ok_or_else(invalid)?.to_ascii_uppercase();
+        let natural: u8 = match letter {
+            'C' => 0,
+            'D' => 2,
+            'E' => 4,
+            'F' => 5,
+            'G' => 7,
+            'A' => 9,
+            'B' => 11,
+            _ => return Err(invalid()),
+        };
+
+        let rest = chars.as_str();
+        let (pitch_class, rest) = if let Some(rest) = rest.strip_prefix('#') {
+            (natural + 1, rest)
+        } else if let Some(rest) = rest.strip_prefix('b') {
+            (natural + 11, rest)
+        } else {
+            (natural, rest)
+        };
+
+        let minor = match rest.trim().to_lowercase().as_str() {
+            "" | "maj" | "major" => false,
+            "m" | "min" | "minor" => true,
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self::new(pitch_class, minor))
+    }
+}
+
+impl TryFrom<String> for Key {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Key> for String {
+    fn from(key: Key) -> Self {
+        key.as_str().to_string()
+    }
+}
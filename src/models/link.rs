@@ -0,0 +1,15 @@
+//! Parsing of the links found in responses
+//!
+//! Links are kept as the strings the API sent, so that a malformed one does
+//! not fail the whole response. The `*_url` accessors of the models parse them
+//! into [`Url`]s on demand.
+
+use url::Url;
+
+/// Parses a link, `None` if it is empty or malformed
+pub(crate) fn parse_link(link: &str) -> Option<Url> {
+    if link.is_empty() {
+        return None;
+    }
+    Url::parse(link).ok()
+}
@@ -0,0 +1,76 @@
+use reqwest::header::HeaderMap;
+use std::time::Duration;
+
+/// How a response was obtained, for debugging queries
+///
+/// Attached to the results of [`search`](crate::FreesoundClient::search), to
+/// the following [pages](crate::FreesoundClient::next_page) and to
+/// [`get_sound`](crate::FreesoundClient::get_sound) when the client was
+/// created [`with_response_meta`](crate::FreesoundClient::with_response_meta).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseMeta {
+    /// The URL answered, after redirects, with the API key redacted
+    pub final_url: String,
+    /// The HTTP status of the response
+    pub status: u16,
+    /// Time until the response headers arrived, not counting the wait for the
+    /// [rate limiter](crate::RateLimiter)
+    pub elapsed: Duration,
+    /// The quota announced by the server, if any
+    pub rate_limit: Option<RateLimitInfo>,
+}
+
+/// The `X-RateLimit-*` headers of a response
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimitInfo {
+    /// Requests allowed in the current window
+    pub limit: Option<u64>,
+    /// Requests left in the current window
+    pub remaining: Option<u64>,
+    /// Seconds until the window resets
+    pub reset: Option<u64>,
+}
+
+impl ResponseMeta {
+    pub(crate) fn new(response: &reqwest::Response, elapsed: Duration) -> Self {
+        Self {
+            final_url: crate::inspect::redact_url(response.url()).to_string(),
+            status: response.status().as_u16(),
+            elapsed,
+            rate_limit: RateLimitInfo::from_headers(response.headers()),
+        }
+    }
+}
+
+impl RateLimitInfo {
+    /// Reads the rate limit headers, `None` if there are none
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::RateLimitInfo;
+    /// use reqwest::header::{HeaderMap, HeaderValue};
+    ///
+    /// let mut headers = HeaderMap::new();
+    /// assert_eq!(RateLimitInfo::from_headers(&headers), None);
+    ///
+    /// headers.insert("x-ratelimit-limit", HeaderValue::from_static("60"));
+    /// headers.insert("x-ratelimit-remaining", HeaderValue::from_static("42"));
+    /// let info = RateLimitInfo::from_headers(&headers).unwrap();
+    /// assert_eq!((info.limit, info.remaining, info.reset), (Some(60), Some(42), None));
+    /// ```
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+        };
+        let info = Self {
+            limit: header("x-ratelimit-limit"),
+            remaining: header("x-ratelimit-remaining"),
+            reset: header("x-ratelimit-reset"),
+        };
+        (info != Self::default()).then_some(info)
+    }
+}
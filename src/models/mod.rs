@@ -1,5 +1,47 @@
+mod audio_commons;
+mod bookmark;
+mod combined;
+mod comment;
+mod expression;
+mod field;
+mod filter;
+mod ids;
+mod key;
+mod link;
+mod meta;
+mod options;
+mod pack;
+mod page;
+mod preset;
 mod search;
+mod search_sound;
+mod similarity_space;
 mod sound;
+mod sound_detail;
+mod sound_ref;
+mod target;
+mod upload;
+mod user;
 
-pub use search::{SearchQueryBuilder, SearchResponse, SortOption};
-pub use sound::Sound;
+pub use audio_commons::AcAnalysis;
+pub use bookmark::BookmarkCategory;
+pub use combined::{CombinedSearchBuilder, CombinedSearchResponse};
+pub use comment::Comment;
+pub use field::{Field, Fields, SearchFields, UnknownField};
+pub use filter::{FilterBuilder, LOSSLESS_TYPES};
+pub use ids::{PackId, Username};
+pub use key::Key;
+pub use meta::{RateLimitInfo, ResponseMeta};
+pub use options::{GetSoundOptions, SoundListOptions};
+pub use pack::Pack;
+pub use page::Page;
+pub use preset::{ONE_SHOT_MAX_DURATION, Preset};
+pub use search::{SearchQueryBuilder, SearchResponse, SortOption, canonical_query};
+pub use search_sound::SearchSound;
+pub use similarity_space::SimilaritySpace;
+pub use sound::{ImageKind, Images, PreviewQuality, Previews, Sound};
+pub use sound_detail::SoundDetail;
+pub use sound_ref::SoundRef;
+pub use target::Target;
+pub use upload::{PendingSound, PendingUploads, UploadStatus};
+pub use user::{Avatar, AvatarSize, User};
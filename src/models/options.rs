@@ -0,0 +1,203 @@
+use super::similarity_space::SimilaritySpace;
+
+/// Options of [`FreesoundClient::get_sound`](crate::FreesoundClient::get_sound)
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::GetSoundOptions;
+///
+/// let options = GetSoundOptions::new()
+///     .descriptors(["lowlevel.mfcc", "rhythm.bpm"])
+///     .normalized(true);
+/// assert_eq!(
+///     options.build(),
+///     [
+///         ("descriptors".to_string(), "lowlevel.mfcc,rhythm.bpm".to_string()),
+///         ("normalized".to_string(), "1".to_string()),
+///     ]
+/// );
+/// assert!(GetSoundOptions::default().build().is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GetSoundOptions {
+    fields: Option<Vec<String>>,
+    descriptors: Option<Vec<String>>,
+    normalized: Option<bool>,
+}
+
+impl GetSoundOptions {
+    /// Create options requesting the default response
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the fields to return
+    pub fn fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.fields = Some(fields.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    /// Set the descriptors to return
+    pub fn descriptors<I, S>(mut self, descriptors: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.descriptors = Some(descriptors.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    /// Set whether to normalize descriptor values
+    pub fn normalized(mut self, normalized: bool) -> Self {
+        self.normalized = Some(normalized);
+        self
+    }
+
+    /// Build the query parameters
+    pub fn build(&self) -> Vec<(String, String)> {
+        let mut params = Vec::new();
+        push_common(
+            &mut params,
+            &self.fields,
+            &self.descriptors,
+            self.normalized,
+        );
+        params
+    }
+}
+
+/// Options of the endpoints listing sounds outside of a search
+///
+/// Used by [`get_pack_sounds`](crate::FreesoundClient::get_pack_sounds),
+/// [`get_user_sounds`](crate::FreesoundClient::get_user_sounds) and
+/// [`get_similar_sounds`](crate::FreesoundClient::get_similar_sounds).
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{SimilaritySpace, SoundListOptions};
+///
+/// let options = SoundListOptions::new().page_size(50).fields(["id", "name"]);
+/// assert_eq!(
+///     options.build(),
+///     [
+///         ("page_size".to_string(), "50".to_string()),
+///         ("fields".to_string(), "id,name".to_string()),
+///     ]
+/// );
+///
+/// // Sounds with a similar timbre
+/// let options = SoundListOptions::new().similarity_space(SimilaritySpace::FreesoundClassic);
+/// assert_eq!(
+///     options.build(),
+///     [("similarity_space".to_string(), "freesound_classic".to_string())]
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SoundListOptions {
+    page: Option<i32>,
+    page_size: Option<i32>,
+    fields: Option<Vec<String>>,
+    descriptors: Option<Vec<String>>,
+    normalized: Option<bool>,
+    similarity_space: Option<SimilaritySpace>,
+}
+
+impl SoundListOptions {
+    /// Create options requesting the first page with the default fields
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the page number
+    pub fn page(mut self, page: i32) -> Self {
+        self.page = Some(page);
+        self
+    }
+
+    /// Set the number of results per page
+    pub fn page_size(mut self, size: i32) -> Self {
+        self.page_size = Some(size);
+        self
+    }
+
+    /// Set the fields to return
+    pub fn fields<I, S>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.fields = Some(fields.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    /// Set the descriptors to return
+    pub fn descriptors<I, S>(mut self, descriptors: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.descriptors = Some(descriptors.into_iter().map(|s| s.into()).collect());
+        self
+    }
+
+    /// Set whether to normalize descriptor values
+    pub fn normalized(mut self, normalized: bool) -> Self {
+        self.normalized = Some(normalized);
+        self
+    }
+
+    /// Set the space in which similar sounds are searched
+    ///
+    /// Only used by [`get_similar_sounds`](crate::FreesoundClient::get_similar_sounds).
+    pub fn similarity_space(mut self, space: SimilaritySpace) -> Self {
+        self.similarity_space = Some(space);
+        self
+    }
+
+    /// Build the query parameters
+    pub fn build(&self) -> Vec<(String, String)> {
+        let mut params = Vec::new();
+        if let Some(page) = self.page {
+            params.push(("page".to_string(), page.to_string()));
+        }
+        if let Some(size) = self.page_size {
+            params.push(("page_size".to_string(), size.to_string()));
+        }
+        push_common(
+            &mut params,
+            &self.fields,
+            &self.descriptors,
+            self.normalized,
+        );
+        if let Some(ref space) = self.similarity_space {
+            params.push(("similarity_space".to_string(), space.to_string()));
+        }
+        params
+    }
+}
+
+fn push_common(
+    params: &mut Vec<(String, String)>,
+    fields: &Option<Vec<String>>,
+    descriptors: &Option<Vec<String>>,
+    normalized: Option<bool>,
+) {
+    if let Some(fields) = fields {
+        params.push(("fields".to_string(), fields.join(",")));
+    }
+    if let Some(descriptors) = descriptors {
+        params.push(("descriptors".to_string(), descriptors.join(",")));
+    }
+    if let Some(normalized) = normalized {
+        params.push((
+            "normalized".to_string(),
+            if normalized { "1" } else { "0" }.to_string(),
+        ));
+    }
+}
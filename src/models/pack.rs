@@ -0,0 +1,26 @@
+use super::ids::{PackId, Username};
+use serde::{Deserialize, Serialize};
+
+/// A pack of sounds from Freesound
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Pack {
+    /// The pack's unique identifier
+    pub id: PackId,
+    /// The URI for this pack on the Freesound website
+    pub url: String,
+    /// The description the user gave to the pack
+    pub description: String,
+    /// The date when the pack was created
+    pub created: String,
+    /// The name user gave to the pack
+    pub name: String,
+    /// The username of the pack creator
+    pub username: Username,
+    /// The number of sounds in the pack
+    pub num_sounds: i32,
+    /// URI pointing to the list of sounds in the pack
+    pub sounds: String,
+    /// The number of times the pack was downloaded
+    pub num_downloads: i32,
+}
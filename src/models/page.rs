@@ -0,0 +1,84 @@
+use super::link::parse_link;
+use super::meta::ResponseMeta;
+use serde::{Deserialize, Serialize};
+
+/// A page of results from any paginated endpoint
+///
+/// Searches, pack and user sound lists all share this layout. Use
+/// [`FreesoundClient::next_page`](crate::FreesoundClient::next_page) or
+/// [`FreesoundClient::pages`](crate::FreesoundClient::pages) to walk through the
+/// following pages.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Page<T> {
+    /// Total number of results
+    pub count: i32,
+    /// Link to next page of results (null if none)
+    pub next: Option<String>,
+    /// List of results
+    pub results: Vec<T>,
+    /// Link to previous page of results (null if none)
+    pub previous: Option<String>,
+    /// How the page was obtained, if the client keeps track
+    ///
+    /// See [`FreesoundClient::with_response_meta`](crate::FreesoundClient::with_response_meta).
+    #[serde(skip)]
+    pub meta: Option<Box<ResponseMeta>>,
+}
+
+impl<T> Page<T> {
+    /// Returns true if there is a page after this one
+    pub fn has_next(&self) -> bool {
+        self.next.is_some()
+    }
+
+    /// Returns true if there is a page before this one
+    pub fn has_previous(&self) -> bool {
+        self.previous.is_some()
+    }
+
+    /// Returns the link to the next page as a [`Url`](crate::Url), `None` if
+    /// there is none or it is malformed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::{Page, Sound};
+    ///
+    /// let page: Page<Sound> = serde_json::from_str(
+    ///     r#"{"count": 30, "next": "https://freesound.org/apiv2/search/text/?page=2", "previous": null, "results": []}"#,
+    /// )?;
+    /// let next = page.next_url().unwrap();
+    /// assert_eq!(next.query(), Some("page=2"));
+    /// assert_eq!(page.previous_url(), None);
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn next_url(&self) -> Option<url::Url> {
+        self.next.as_deref().and_then(parse_link)
+    }
+
+    /// Returns the link to the previous page as a [`Url`](crate::Url), `None`
+    /// if there is none or it is malformed
+    pub fn previous_url(&self) -> Option<url::Url> {
+        self.previous.as_deref().and_then(parse_link)
+    }
+
+    /// Converts the results, keeping the pagination links
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            count: self.count,
+            next: self.next,
+            results: self.results.into_iter().map(f).collect(),
+            previous: self.previous,
+            meta: self.meta,
+        }
+    }
+}
+
+impl<T> IntoIterator for Page<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.results.into_iter()
+    }
+}
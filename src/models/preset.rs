@@ -0,0 +1,42 @@
+use super::filter::FilterBuilder;
+use std::ops::RangeBounds;
+
+/// Longest duration, in seconds, of a sound matched by [`Preset::one_shots`]
+pub const ONE_SHOT_MAX_DURATION: f32 = 3.0;
+
+/// Ready-made filters for common sampler use cases
+///
+/// Presets return a [`FilterBuilder`], so further conditions can be chained.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{Preset, SearchQueryBuilder};
+///
+/// let kicks = SearchQueryBuilder::new()
+///     .query("kick")
+///     .filter(Preset::one_shots())
+///     .build();
+///
+/// let loops = Preset::loops(118..=126).field("tag", "drums").build();
+/// assert_eq!(loops, "ac_loop:true ac_tempo:[118 TO 126] tag:drums");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Preset;
+
+impl Preset {
+    /// Short, single-event sounds: hits, stabs, one-shot samples
+    ///
+    /// Matches sounds AudioCommons considers a single event and lasting at most
+    /// [`ONE_SHOT_MAX_DURATION`] seconds.
+    pub fn one_shots() -> FilterBuilder {
+        FilterBuilder::new()
+            .ac_single_event(true)
+            .range("duration", 0.0..=ONE_SHOT_MAX_DURATION)
+    }
+
+    /// Loopable sounds within a tempo range, in BPM
+    pub fn loops(bpm: impl RangeBounds<u32>) -> FilterBuilder {
+        FilterBuilder::new().ac_loop(true).bpm(bpm)
+    }
+}
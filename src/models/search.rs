@@ -1,21 +1,17 @@
+use super::filter::FilterBuilder;
+use super::ids::{PackId, Username};
+use super::page::Page;
 use super::sound::Sound;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 /// Response of a search query containing a list of sounds
-#[derive(Debug, Deserialize)]
-pub struct SearchResponse {
-    /// Total number of results
-    pub count: i32,
-    /// Link to next page of results (null if none)
-    pub next: Option<String>,
-    /// List of sound results
-    pub results: Vec<Sound>,
-    /// Link to previous page of results (null if none)
-    pub previous: Option<String>,
-}
+pub type SearchResponse = Page<Sound>;
 
 /// Sort options for search results
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SortOption {
     /// Sort by relevance score (default)
     Score,
@@ -37,9 +33,9 @@ pub enum SortOption {
     RatingAsc,
 }
 
-impl ToString for SortOption {
-    fn to_string(&self) -> String {
-        match self {
+impl fmt::Display for SortOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
             Self::Score => "score",
             Self::DurationDesc => "duration_desc",
             Self::DurationAsc => "duration_asc",
@@ -49,22 +45,53 @@ impl ToString for SortOption {
             Self::DownloadsAsc => "downloads_asc",
             Self::RatingDesc => "rating_desc",
             Self::RatingAsc => "rating_asc",
+        })
+    }
+}
+
+impl FromStr for SortOption {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "score" => Ok(Self::Score),
+            "duration_desc" => Ok(Self::DurationDesc),
+            "duration_asc" => Ok(Self::DurationAsc),
+            "created_desc" => Ok(Self::CreatedDesc),
+            "created_asc" => Ok(Self::CreatedAsc),
+            "downloads_desc" => Ok(Self::DownloadsDesc),
+            "downloads_asc" => Ok(Self::DownloadsAsc),
+            "rating_desc" => Ok(Self::RatingDesc),
+            "rating_asc" => Ok(Self::RatingAsc),
+            other => Err(format!("unknown sort option: {other}")),
         }
-        .to_string()
     }
 }
 
 /// Builder pattern for constructing search queries
-#[derive(Debug, Default)]
+///
+/// Builders can be saved as JSON (see [`to_json`](Self::to_json)) and kept by
+/// name in a [`QueryStore`](crate::QueryStore).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SearchQueryBuilder {
+    #[serde(skip_serializing_if = "Option::is_none")]
     query: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     filter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     sort: Option<SortOption>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     group_by_pack: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     page: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     page_size: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     fields: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     descriptors: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     normalized: Option<bool>,
 }
 
@@ -86,6 +113,45 @@ impl SearchQueryBuilder {
         self
     }
 
+    /// Restrict the search to the sounds of a pack
+    ///
+    /// The condition is added to the current filter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::{PackId, SearchQueryBuilder};
+    ///
+    /// let query = SearchQueryBuilder::new()
+    ///     .filter("duration:[0 TO 2]")
+    ///     .in_pack(PackId(9678))
+    ///     .by_user(&"Jovica".parse()?)
+    ///     .build();
+    /// assert_eq!(
+    ///     query[0].1,
+    ///     "duration:[0 TO 2] grouping_pack:9678_* username:Jovica"
+    /// );
+    /// # Ok::<(), freesound_rs::FreesoundError>(())
+    /// ```
+    pub fn in_pack(self, pack_id: PackId) -> Self {
+        self.and_filter(format!("grouping_pack:{pack_id}_*"))
+    }
+
+    /// Restrict the search to the sounds uploaded by a user
+    ///
+    /// The condition is added to the current filter.
+    pub fn by_user(self, username: &Username) -> Self {
+        self.and_filter(FilterBuilder::new().field("username", username).build())
+    }
+
+    fn and_filter(mut self, condition: String) -> Self {
+        self.filter = Some(match self.filter.take() {
+            Some(filter) if !filter.trim().is_empty() => format!("{filter} {condition}"),
+            _ => condition,
+        });
+        self
+    }
+
     /// Set the sort option
     pub fn sort(mut self, sort: SortOption) -> Self {
         self.sort = Some(sort);
@@ -185,4 +251,91 @@ impl SearchQueryBuilder {
 
         params
     }
+
+    /// Returns a stable representation of the query
+    ///
+    /// See [`canonical_query`]. Two builders giving the same parameters, whatever
+    /// the order of the calls, have the same canonical string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::SearchQueryBuilder;
+    ///
+    /// let a = SearchQueryBuilder::new().query("dog bark").page(2);
+    /// let b = SearchQueryBuilder::new().page(2).query("dog bark");
+    /// assert_eq!(a.canonical_string(), "page=2&query=dog%20bark");
+    /// assert_eq!(a.canonical_string(), b.canonical_string());
+    /// ```
+    pub fn canonical_string(&self) -> String {
+        canonical_query(&self.build())
+    }
+
+    /// Serializes the query to JSON
+    ///
+    /// Only the parameters that were set are written.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::{SearchQueryBuilder, SortOption};
+    ///
+    /// let query = SearchQueryBuilder::new()
+    ///     .query("rain")
+    ///     .sort(SortOption::CreatedDesc);
+    /// let json = query.to_json()?;
+    /// assert_eq!(json, r#"{"query":"rain","sort":"created_desc"}"#);
+    /// assert_eq!(SearchQueryBuilder::from_json(&json)?, query);
+    /// # Ok::<(), freesound_rs::FreesoundError>(())
+    /// ```
+    pub fn to_json(&self) -> crate::Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Reads a query serialized by [`to_json`](Self::to_json)
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// Returns a stable representation of query parameters
+///
+/// Parameters are sorted by name then value, percent-encoded and joined with
+/// `&`. The `token` parameter is left out, so the result can be logged and
+/// used as a cache key.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::canonical_query;
+///
+/// let params = [
+///     ("token".to_string(), "secret".to_string()),
+///     ("query".to_string(), "rain & thunder".to_string()),
+///     ("filter".to_string(), "tag:storm".to_string()),
+/// ];
+/// assert_eq!(canonical_query(&params), "filter=tag%3Astorm&query=rain%20%26%20thunder");
+/// ```
+pub fn canonical_query(params: &[(String, String)]) -> String {
+    let mut params: Vec<_> = params.iter().filter(|(k, _)| k != "token").collect();
+    params.sort();
+    params
+        .iter()
+        .map(|(k, v)| format!("{}={}", percent_encode(k), percent_encode(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Percent-encodes everything but the unreserved characters of RFC 3986
+fn percent_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
 }
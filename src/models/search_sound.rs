@@ -0,0 +1,263 @@
+use super::audio_commons::AcAnalysis;
+use super::field::Field;
+use super::ids::Username;
+use super::sound::{Images, Previews, Sound};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// A search result holding exactly the fields that were returned
+///
+/// Unlike [`Sound`], a field missing from the response is `None` instead of a
+/// default value, so restricting the `fields` of a search cannot be mistaken
+/// for real data. Fields the API sends as `null` when they have no value, such
+/// as `geotag` or `pack`, are `Some(None)` when sent that way. Returned by
+/// [`search_lean`](crate::FreesoundClient::search_lean); convert it into a
+/// [`Sound`] to use the helpers of this crate.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{Field, SearchSound, Sound};
+///
+/// let lean: SearchSound = serde_json::from_str(r#"{"id": 1, "name": "Rain"}"#)?;
+/// assert_eq!(lean.name.as_deref(), Some("Rain"));
+/// assert_eq!(lean.duration, None);
+///
+/// let sound = Sound::from(lean);
+/// assert!(sound.has_field(Field::Name));
+/// assert!(!sound.has_field(Field::Duration));
+///
+/// // A sound without geotag, with the geotag requested
+/// let lean: SearchSound = serde_json::from_str(r#"{"id": 1, "geotag": null}"#)?;
+/// assert_eq!(lean.geotag, Some(None));
+/// assert!(Sound::from(lean).has_field(Field::Geotag));
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct SearchSound {
+    /// The sound's unique identifier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    /// The URI for this sound on the Freesound website
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The name user gave to the sound
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// An array of tags the user gave to the sound
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// The description the user gave to the sound
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Latitude and longitude of the geotag, when requested and available
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub geotag: Option<Option<String>>,
+    /// The date when the sound was uploaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    /// The license under which the sound is available
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+    /// The type of sound (wav, aif, aiff, mp3, m4a or flac)
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub sound_type: Option<String>,
+    /// The number of channels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channels: Option<i32>,
+    /// The size of the file in bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filesize: Option<i32>,
+    /// The MD5 checksum of the original file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub md5: Option<String>,
+    /// The bit rate of the sound in kbps
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bitrate: Option<Option<f32>>,
+    /// The bit depth of the sound
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bitdepth: Option<Option<i32>>,
+    /// The duration of the sound in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f32>,
+    /// The samplerate of the sound
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub samplerate: Option<f32>,
+    /// The username of the uploader
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<Username>,
+    /// URI pointing to the pack API resource, when requested and in a pack
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub pack: Option<Option<String>>,
+    /// URI for downloading the original sound
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download: Option<String>,
+    /// URI for bookmarking the sound
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bookmark: Option<String>,
+    /// URLs for mp3 and ogg versions of the sound
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previews: Option<Previews>,
+    /// URLs for spectrogram and waveform visualizations
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub images: Option<Images>,
+    /// The number of times the sound was downloaded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_downloads: Option<i32>,
+    /// The average rating of the sound
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_rating: Option<f32>,
+    /// The number of times the sound was rated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_ratings: Option<i32>,
+    /// The URI for rating the sound
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate: Option<String>,
+    /// The URI for the comments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<String>,
+    /// The number of comments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub num_comments: Option<i32>,
+    /// The URI to comment the sound
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    /// URI for similar sounds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub similar_sounds: Option<String>,
+    /// Analysis data (when requested)
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub analysis: Option<Option<serde_json::Value>>,
+    /// Whether the analysis values are normalized
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub analysis_normalized: bool,
+    /// URI for complete analysis results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analysis_stats: Option<String>,
+    /// URI for analysis frames
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub analysis_frames: Option<String>,
+    /// AudioCommons descriptors (when requested)
+    #[serde(
+        default,
+        deserialize_with = "nullable",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub ac_analysis: Option<Option<AcAnalysis>>,
+}
+
+/// Deserializes a field that may be `null`, as `Some(None)` when it is
+///
+/// Meant for `#[serde(default, deserialize_with = "...")]`, so that a missing
+/// field is `None`.
+pub(super) fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+impl From<SearchSound> for Sound {
+    /// Fills the missing fields with defaults, recording which ones were set
+    fn from(s: SearchSound) -> Self {
+        let present = [
+            (Field::Id, s.id.is_some()),
+            (Field::Url, s.url.is_some()),
+            (Field::Name, s.name.is_some()),
+            (Field::Tags, s.tags.is_some()),
+            (Field::Description, s.description.is_some()),
+            (Field::Geotag, s.geotag.is_some()),
+            (Field::Created, s.created.is_some()),
+            (Field::License, s.license.is_some()),
+            (Field::Type, s.sound_type.is_some()),
+            (Field::Channels, s.channels.is_some()),
+            (Field::Filesize, s.filesize.is_some()),
+            (Field::Md5, s.md5.is_some()),
+            (Field::Bitrate, s.bitrate.is_some()),
+            (Field::Bitdepth, s.bitdepth.is_some()),
+            (Field::Duration, s.duration.is_some()),
+            (Field::Samplerate, s.samplerate.is_some()),
+            (Field::Username, s.username.is_some()),
+            (Field::Pack, s.pack.is_some()),
+            (Field::Download, s.download.is_some()),
+            (Field::Bookmark, s.bookmark.is_some()),
+            (Field::Previews, s.previews.is_some()),
+            (Field::Images, s.images.is_some()),
+            (Field::NumDownloads, s.num_downloads.is_some()),
+            (Field::AvgRating, s.avg_rating.is_some()),
+            (Field::NumRatings, s.num_ratings.is_some()),
+            (Field::Rate, s.rate.is_some()),
+            (Field::Comments, s.comments.is_some()),
+            (Field::NumComments, s.num_comments.is_some()),
+            (Field::Comment, s.comment.is_some()),
+            (Field::SimilarSounds, s.similar_sounds.is_some()),
+            (Field::Analysis, s.analysis.is_some()),
+            (Field::AnalysisStats, s.analysis_stats.is_some()),
+            (Field::AnalysisFrames, s.analysis_frames.is_some()),
+            (Field::AcAnalysis, s.ac_analysis.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(field, set)| set.then_some(field))
+        .collect();
+
+        Self {
+            id: s.id.unwrap_or_default(),
+            url: s.url.unwrap_or_default(),
+            name: s.name.unwrap_or_default(),
+            tags: s.tags.unwrap_or_default(),
+            description: s.description.unwrap_or_default(),
+            geotag: s.geotag.flatten(),
+            created: s.created.unwrap_or_default(),
+            license: s.license.unwrap_or_default(),
+            sound_type: s.sound_type.unwrap_or_default(),
+            channels: s.channels.unwrap_or_default(),
+            filesize: s.filesize.unwrap_or_default(),
+            md5: s.md5.unwrap_or_default(),
+            bitrate: s.bitrate.flatten(),
+            bitdepth: s.bitdepth.flatten(),
+            duration: s.duration.unwrap_or_default(),
+            samplerate: s.samplerate.unwrap_or_default(),
+            username: s.username.unwrap_or_default(),
+            pack: s.pack.flatten(),
+            download: s.download.unwrap_or_default(),
+            bookmark: s.bookmark.unwrap_or_default(),
+            previews: s.previews,
+            images: s.images,
+            num_downloads: s.num_downloads.unwrap_or_default(),
+            avg_rating: s.avg_rating.unwrap_or_default(),
+            num_ratings: s.num_ratings.unwrap_or_default(),
+            rate: s.rate.unwrap_or_default(),
+            comments: s.comments.unwrap_or_default(),
+            num_comments: s.num_comments.unwrap_or_default(),
+            comment: s.comment.unwrap_or_default(),
+            similar_sounds: s.similar_sounds.unwrap_or_default(),
+            analysis: s.analysis.flatten(),
+            analysis_normalized: s.analysis_normalized,
+            analysis_stats: s.analysis_stats.unwrap_or_default(),
+            analysis_frames: s.analysis_frames.unwrap_or_default(),
+            ac_analysis: s.ac_analysis.flatten(),
+            present_fields: Some(present),
+            meta: None,
+        }
+    }
+}
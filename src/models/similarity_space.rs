@@ -0,0 +1,55 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// The space in which Freesound computes sound similarity
+///
+/// Used by [`SoundListOptions::similarity_space`](crate::SoundListOptions::similarity_space)
+/// for similar sounds and by
+/// [`CombinedSearchBuilder::similarity_space`](crate::CombinedSearchBuilder::similarity_space)
+/// for content searches. Without one, the API uses its default space.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::SimilaritySpace;
+///
+/// assert_eq!(SimilaritySpace::FreesoundClassic.to_string(), "freesound_classic");
+/// assert_eq!("laion_clap".parse(), Ok(SimilaritySpace::LaionClap));
+/// // Spaces this crate does not know yet are passed as is
+/// assert_eq!(
+///     "rhythm".parse(),
+///     Ok(SimilaritySpace::Other("rhythm".to_string()))
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SimilaritySpace {
+    /// The Gaia space of low-level descriptors, mostly timbral
+    FreesoundClassic,
+    /// The space of LAION-CLAP audio embeddings, closer to how sounds are described
+    LaionClap,
+    /// Another space offered by the API, by name
+    Other(String),
+}
+
+impl fmt::Display for SimilaritySpace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::FreesoundClassic => "freesound_classic",
+            Self::LaionClap => "laion_clap",
+            Self::Other(name) => name,
+        })
+    }
+}
+
+impl FromStr for SimilaritySpace {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" => Err("empty similarity space".to_string()),
+            "freesound_classic" => Ok(Self::FreesoundClassic),
+            "laion_clap" => Ok(Self::LaionClap),
+            other => Ok(Self::Other(other.to_string())),
+        }
+    }
+}
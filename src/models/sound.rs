@@ -1,7 +1,18 @@
-use serde::Deserialize;
+use super::audio_commons::AcAnalysis;
+use super::field::Field;
+use super::ids::Username;
+use super::key::Key;
+use super::link::parse_link;
+use super::meta::ResponseMeta;
+use super::search_sound::SearchSound;
+use crate::descriptor::{Normalized, Rhythm, Tonal};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
 
 /// Preview URLs for different formats and qualities
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Previews {
     /// High quality MP3 preview (~128kbps)
     #[serde(rename = "preview-hq-mp3")]
@@ -17,8 +28,70 @@ pub struct Previews {
     pub preview_lq_ogg: String,
 }
 
+impl Previews {
+    /// Returns the preview URL for the given quality
+    pub fn url(&self, quality: PreviewQuality) -> &str {
+        match quality {
+            PreviewQuality::HqMp3 => &self.preview_hq_mp3,
+            PreviewQuality::LqMp3 => &self.preview_lq_mp3,
+            PreviewQuality::HqOgg => &self.preview_hq_ogg,
+            PreviewQuality::LqOgg => &self.preview_lq_ogg,
+        }
+    }
+}
+
+/// Available preview formats and qualities
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PreviewQuality {
+    /// High quality MP3 preview (default)
+    #[default]
+    HqMp3,
+    /// Low quality MP3 preview
+    LqMp3,
+    /// High quality OGG preview
+    HqOgg,
+    /// Low quality OGG preview
+    LqOgg,
+}
+
+impl PreviewQuality {
+    /// Returns the file extension of the preview format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::HqMp3 | Self::LqMp3 => "mp3",
+            Self::HqOgg | Self::LqOgg => "ogg",
+        }
+    }
+}
+
+impl fmt::Display for PreviewQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::HqMp3 => "hq-mp3",
+            Self::LqMp3 => "lq-mp3",
+            Self::HqOgg => "hq-ogg",
+            Self::LqOgg => "lq-ogg",
+        })
+    }
+}
+
+impl FromStr for PreviewQuality {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "hq-mp3" => Ok(Self::HqMp3),
+            "lq-mp3" => Ok(Self::LqMp3),
+            "hq-ogg" => Ok(Self::HqOgg),
+            "lq-ogg" => Ok(Self::LqOgg),
+            other => Err(format!("unknown preview quality: {other}")),
+        }
+    }
+}
+
 /// Image URLs for sound visualization
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Images {
     /// Large waveform image
     pub waveform_l: String,
@@ -30,97 +103,317 @@ pub struct Images {
     pub spectral_m: String,
 }
 
+impl Images {
+    /// Returns the image URL for the given kind
+    pub fn url(&self, kind: ImageKind) -> &str {
+        match kind {
+            ImageKind::WaveformL => &self.waveform_l,
+            ImageKind::WaveformM => &self.waveform_m,
+            ImageKind::SpectralL => &self.spectral_l,
+            ImageKind::SpectralM => &self.spectral_m,
+        }
+    }
+}
+
+/// Available sound images
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ImageKind {
+    /// Large waveform image
+    WaveformL,
+    /// Medium waveform image (default)
+    #[default]
+    WaveformM,
+    /// Large spectral image
+    SpectralL,
+    /// Medium spectral image
+    SpectralM,
+}
+
 /// A sound from Freesound
-#[derive(Debug, Deserialize)]
-#[serde(default)] // Utilise les valeurs par défaut si les champs sont manquants
+///
+/// Fields missing from the response, for instance when a search restricts the
+/// returned `fields`, get their default value: see [`has_field`](Self::has_field)
+/// to tell them apart. A sound is read as a [`SearchSound`], whose fields are
+/// all optional, then converted, so the defaults are only filled in there.
+#[derive(Debug, Clone, Serialize)]
 pub struct Sound {
     /// The sound's unique identifier
     pub id: i32,
     /// The URI for this sound on the Freesound website
-    #[serde(default)]
     pub url: String,
     /// The name user gave to the sound
     pub name: String,
     /// An array of tags the user gave to the sound
-    #[serde(default)]
     pub tags: Vec<String>,
     /// The description the user gave to the sound
-    #[serde(default)]
     pub description: String,
     /// Latitude and longitude of the geotag (if available)
     pub geotag: Option<String>,
     /// The date when the sound was uploaded
-    #[serde(default)]
     pub created: String,
     /// The license under which the sound is available
     pub license: String,
     /// The type of sound (wav, aif, aiff, mp3, m4a or flac)
-    #[serde(rename = "type", default)]
+    #[serde(rename = "type")]
     pub sound_type: String,
     /// The number of channels
-    #[serde(default)]
     pub channels: i32,
     /// The size of the file in bytes
-    #[serde(default)]
     pub filesize: i32,
+    /// The MD5 checksum of the original file
+    pub md5: String,
     /// The bit rate of the sound in kbps
     pub bitrate: Option<f32>,
     /// The bit depth of the sound
     pub bitdepth: Option<i32>,
     /// The duration of the sound in seconds
-    #[serde(default)]
     pub duration: f32,
     /// The samplerate of the sound
-    #[serde(default)]
     pub samplerate: f32,
     /// The username of the uploader
-    pub username: String,
+    pub username: Username,
     /// URI pointing to the pack API resource
     pub pack: Option<String>,
     /// URI for downloading the original sound
-    #[serde(default)]
     pub download: String,
     /// URI for bookmarking the sound
-    #[serde(default)]
     pub bookmark: String,
     /// URLs for mp3 and ogg versions of the sound
-    #[serde(default)]
     pub previews: Option<Previews>,
     /// URLs for spectrogram and waveform visualizations
-    #[serde(default)]
     pub images: Option<Images>,
     /// The number of times the sound was downloaded
-    #[serde(default)]
     pub num_downloads: i32,
     /// The average rating of the sound
-    #[serde(default)]
     pub avg_rating: f32,
     /// The number of times the sound was rated
-    #[serde(default)]
     pub num_ratings: i32,
     /// The URI for rating the sound
-    #[serde(default)]
     pub rate: String,
     /// The URI for the comments
-    #[serde(default)]
     pub comments: String,
     /// The number of comments
-    #[serde(default)]
     pub num_comments: i32,
     /// The URI to comment the sound
-    #[serde(default)]
     pub comment: String,
     /// URI for similar sounds
-    #[serde(default)]
     pub similar_sounds: String,
     /// Analysis data (when requested)
     pub analysis: Option<serde_json::Value>,
+    /// Whether the analysis values are normalized
+    ///
+    /// Set by the client when the sound was fetched with `normalized=1`.
+    pub analysis_normalized: bool,
     /// URI for complete analysis results
-    #[serde(default)]
     pub analysis_stats: String,
     /// URI for analysis frames
-    #[serde(default)]
     pub analysis_frames: String,
+    /// AudioCommons descriptors (when requested)
+    pub ac_analysis: Option<AcAnalysis>,
+    /// Fields present in the response, `None` when unknown
+    ///
+    /// Set when the sound is deserialized; a sound built by hand has all its fields.
+    #[serde(skip)]
+    pub present_fields: Option<BTreeSet<Field>>,
+    /// How the sound was obtained, if the client keeps track
+    ///
+    /// See [`FreesoundClient::with_response_meta`](crate::FreesoundClient::with_response_meta).
+    #[serde(skip)]
+    pub meta: Option<Box<ResponseMeta>>,
+}
+
+impl<'de> Deserialize<'de> for Sound {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        SearchSound::deserialize(deserializer).map(Sound::from)
+    }
+}
+
+impl Sound {
+    /// Returns whether a field was part of the response
+    ///
+    /// Lets a zero or empty value be told from a field that was not requested.
+    /// A field sent as `null`, such as a missing geotag, counts as present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::{Field, Sound};
+    ///
+    /// let sound: Sound = serde_json::from_str(r#"{"id": 1, "name": "Rain", "geotag": null}"#)?;
+    /// assert!(sound.has_field(Field::Geotag));
+    /// assert!(!sound.has_field(Field::Duration));
+    /// assert_eq!(sound.duration, 0.0);
+    ///
+    /// // Sounds built by hand have all their fields
+    /// assert!(Sound::default().has_field(Field::Duration));
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn has_field(&self, field: Field) -> bool {
+        self.present_fields
+            .as_ref()
+            .is_none_or(|present| present.contains(&field))
+    }
+
+    /// Returns the download link as a [`Url`](crate::Url), `None` if it was
+    /// not requested or is malformed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::{PreviewQuality, Sound};
+    ///
+    /// let sound: Sound = serde_json::from_str(r#"{
+    ///     "id": 1,
+    ///     "name": "Rain",
+    ///     "download": "https://freesound.org/apiv2/sounds/1/download/",
+    ///     "previews": {
+    ///         "preview-hq-mp3": "https://cdn.freesound.org/previews/0/1_1-hq.mp3",
+    ///         "preview-lq-mp3": "https://cdn.freesound.org/previews/0/1_1-lq.mp3",
+    ///         "preview-hq-ogg": "https://cdn.freesound.org/previews/0/1_1-hq.ogg",
+    ///         "preview-lq-ogg": "https://cdn.freesound.org/previews/0/1_1-lq.ogg"
+    ///     }
+    /// }"#)?;
+    /// assert_eq!(sound.download_url().unwrap().path(), "/apiv2/sounds/1/download/");
+    /// assert_eq!(sound.bookmark_url(), None);
+    /// let preview = sound.preview_url(PreviewQuality::HqOgg).unwrap();
+    /// assert_eq!(preview.host_str(), Some("cdn.freesound.org"));
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn download_url(&self) -> Option<url::Url> {
+        parse_link(&self.download)
+    }
+
+    /// Returns the bookmark link as a [`Url`](crate::Url), `None` if it was
+    /// not requested or is malformed
+    pub fn bookmark_url(&self) -> Option<url::Url> {
+        parse_link(&self.bookmark)
+    }
+
+    /// Returns a preview link as a [`Url`](crate::Url), `None` if previews
+    /// were not requested or the link is malformed
+    pub fn preview_url(&self, quality: PreviewQuality) -> Option<url::Url> {
+        self.previews
+            .as_ref()
+            .and_then(|previews| parse_link(previews.url(quality)))
+    }
+
+    /// Returns an image link as a [`Url`](crate::Url), `None` if images were
+    /// not requested or the link is malformed
+    pub fn image_url(&self, kind: ImageKind) -> Option<url::Url> {
+        self.images
+            .as_ref()
+            .and_then(|images| parse_link(images.url(kind)))
+    }
+
+    /// Returns the value of a descriptor from the analysis data
+    ///
+    /// `name` is a dotted descriptor name such as `"rhythm.bpm"` or
+    /// [`Rhythm::Bpm`](crate::descriptor::Rhythm::Bpm), looked up in the nested
+    /// analysis object returned when descriptors are requested.
+    ///
+    /// Only raw values are returned: when the analysis is
+    /// [normalized](Self::analysis_normalized), use
+    /// [`normalized_descriptor`](Self::normalized_descriptor) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::Sound;
+    ///
+    /// let sound = Sound {
+    ///     analysis: Some(serde_json::json!({ "rhythm": { "bpm": 120.0 } })),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(sound.descriptor("rhythm.bpm").and_then(|v| v.as_f64()), Some(120.0));
+    /// assert_eq!(sound.bpm(), Some(120.0));
+    /// ```
+    pub fn descriptor(&self, name: impl AsRef<str>) -> Option<&serde_json::Value> {
+        if self.analysis_normalized {
+            return None;
+        }
+        self.analysis_value(name.as_ref())
+    }
+
+    /// Returns the value of a descriptor from normalized analysis data
+    ///
+    /// Returns `None` when the analysis holds raw values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::Sound;
+    /// use freesound_rs::descriptor::{Normalized, Rhythm};
+    ///
+    /// let sound = Sound {
+    ///     analysis: Some(serde_json::json!({ "rhythm": { "bpm": 0.42 } })),
+    ///     analysis_normalized: true,
+    ///     ..Default::default()
+    /// };
+    /// let bpm = sound.normalized_descriptor(Rhythm::Bpm).map(|v| v.map(|v| v.as_f64()));
+    /// assert_eq!(bpm, Some(Normalized(Some(0.42))));
+    /// assert_eq!(sound.descriptor(Rhythm::Bpm), None);
+    /// assert_eq!(sound.bpm(), None);
+    /// ```
+    pub fn normalized_descriptor(
+        &self,
+        name: impl AsRef<str>,
+    ) -> Option<Normalized<&serde_json::Value>> {
+        if !self.analysis_normalized {
+            return None;
+        }
+        self.analysis_value(name.as_ref()).map(Normalized)
+    }
+
+    /// Looks up a descriptor, whatever the space of the analysis
+    pub(crate) fn analysis_value(&self, name: &str) -> Option<&serde_json::Value> {
+        let analysis = self.analysis.as_ref()?;
+        name.split('.')
+            .try_fold(analysis, |value, part| value.get(part))
+            .or_else(|| analysis.get(name))
+    }
+
+    /// Returns the estimated tempo, in BPM
+    ///
+    /// Uses the AudioCommons tempo when available, the `rhythm.bpm` descriptor otherwise.
+    pub fn bpm(&self) -> Option<f32> {
+        self.ac_analysis
+            .as_ref()
+            .and_then(|ac| ac.ac_tempo)
+            .or_else(|| Some(self.descriptor(Rhythm::Bpm)?.as_f64()? as f32))
+    }
+
+    /// Returns the estimated key
+    ///
+    /// Uses the AudioCommons tonality when available, the `tonal.key_key` and
+    /// `tonal.key_scale` descriptors otherwise.
+    pub fn key(&self) -> Option<Key> {
+        self.ac_analysis
+            .as_ref()
+            .and_then(|ac| ac.key())
+            .or_else(|| {
+                let tonic = self.descriptor(Tonal::KeyKey)?.as_str()?;
+                let scale = self.descriptor(Tonal::KeyScale)?.as_str()?;
+                format!("{tonic} {scale}").parse().ok()
+            })
+    }
+    /// Returns the latitude and longitude of the geotag, in degrees
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::Sound;
+    ///
+    /// let sound = Sound { geotag: Some("41.3851 2.1734".to_string()), ..Default::default() };
+    /// assert_eq!(sound.coordinates(), Some((41.3851, 2.1734)));
+    /// assert_eq!(Sound::default().coordinates(), None);
+    /// ```
+    pub fn coordinates(&self) -> Option<(f64, f64)> {
+        let mut parts = self.geotag.as_deref()?.split_whitespace();
+        let latitude = parts.next()?.parse().ok()?;
+        let longitude = parts.next()?.parse().ok()?;
+        Some((latitude, longitude))
+    }
 }
 
 impl Default for Sound {
@@ -137,11 +430,12 @@ impl Default for Sound {
             sound_type: String::new(),
             channels: 0,
             filesize: 0,
+            md5: String::new(),
             bitrate: None,
             bitdepth: None,
             duration: 0.0,
             samplerate: 0.0,
-            username: String::new(),
+            username: Username::default(),
             pack: None,
             download: String::new(),
             bookmark: String::new(),
@@ -156,8 +450,12 @@ impl Default for Sound {
             comment: String::new(),
             similar_sounds: String::new(),
             analysis: None,
+            analysis_normalized: false,
             analysis_stats: String::new(),
             analysis_frames: String::new(),
+            ac_analysis: None,
+            present_fields: None,
+            meta: None,
         }
     }
 }
@@ -0,0 +1,132 @@
+use super::audio_commons::AcAnalysis;
+use super::ids::Username;
+use super::sound::{Images, Previews, Sound};
+use serde::{Deserialize, Serialize};
+
+/// The full description of a sound, as returned by the sound instance endpoint
+///
+/// Every field the API always sends is required: a response missing one fails
+/// to deserialize instead of being filled with a default value. The `Option`
+/// fields are the ones the API may send as `null` or leave out. Returned by
+/// [`get_sound_detail`](crate::FreesoundClient::get_sound_detail); convert it
+/// into a [`Sound`] to use the helpers of this crate.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SoundDetail {
+    /// The sound's unique identifier
+    pub id: i32,
+    /// The URI for this sound on the Freesound website
+    pub url: String,
+    /// The name user gave to the sound
+    pub name: String,
+    /// An array of tags the user gave to the sound
+    pub tags: Vec<String>,
+    /// The description the user gave to the sound
+    pub description: String,
+    /// Latitude and longitude of the geotag (if available)
+    pub geotag: Option<String>,
+    /// The date when the sound was uploaded
+    pub created: String,
+    /// The license under which the sound is available
+    pub license: String,
+    /// The type of sound (wav, aif, aiff, mp3, m4a or flac)
+    #[serde(rename = "type")]
+    pub sound_type: String,
+    /// The number of channels
+    pub channels: i32,
+    /// The size of the file in bytes
+    pub filesize: i32,
+    /// The MD5 checksum of the original file
+    pub md5: String,
+    /// The bit rate of the sound in kbps
+    pub bitrate: Option<f32>,
+    /// The bit depth of the sound
+    pub bitdepth: Option<i32>,
+    /// The duration of the sound in seconds
+    pub duration: f32,
+    /// The samplerate of the sound
+    pub samplerate: f32,
+    /// The username of the uploader
+    pub username: Username,
+    /// URI pointing to the pack API resource (if in a pack)
+    pub pack: Option<String>,
+    /// URI for downloading the original sound
+    pub download: String,
+    /// URI for bookmarking the sound
+    pub bookmark: String,
+    /// URLs for mp3 and ogg versions of the sound
+    pub previews: Previews,
+    /// URLs for spectrogram and waveform visualizations
+    pub images: Images,
+    /// The number of times the sound was downloaded
+    pub num_downloads: i32,
+    /// The average rating of the sound
+    pub avg_rating: f32,
+    /// The number of times the sound was rated
+    pub num_ratings: i32,
+    /// The URI for rating the sound
+    pub rate: String,
+    /// The URI for the comments
+    pub comments: String,
+    /// The number of comments
+    pub num_comments: i32,
+    /// The URI to comment the sound
+    pub comment: String,
+    /// URI for similar sounds
+    pub similar_sounds: String,
+    /// Analysis data (when requested)
+    pub analysis: Option<serde_json::Value>,
+    /// Whether the analysis values are normalized
+    #[serde(default)]
+    pub analysis_normalized: bool,
+    /// URI for complete analysis results
+    pub analysis_stats: String,
+    /// URI for analysis frames
+    pub analysis_frames: String,
+    /// AudioCommons descriptors (when requested)
+    #[serde(default)]
+    pub ac_analysis: Option<AcAnalysis>,
+}
+
+impl From<SoundDetail> for Sound {
+    fn from(s: SoundDetail) -> Self {
+        Self {
+            id: s.id,
+            url: s.url,
+            name: s.name,
+            tags: s.tags,
+            description: s.description,
+            geotag: s.geotag,
+            created: s.created,
+            license: s.license,
+            sound_type: s.sound_type,
+            channels: s.channels,
+            filesize: s.filesize,
+            md5: s.md5,
+            bitrate: s.bitrate,
+            bitdepth: s.bitdepth,
+            duration: s.duration,
+            samplerate: s.samplerate,
+            username: s.username,
+            pack: s.pack,
+            download: s.download,
+            bookmark: s.bookmark,
+            previews: Some(s.previews),
+            images: Some(s.images),
+            num_downloads: s.num_downloads,
+            avg_rating: s.avg_rating,
+            num_ratings: s.num_ratings,
+            rate: s.rate,
+            comments: s.comments,
+            num_comments: s.num_comments,
+            comment: s.comment,
+            similar_sounds: s.similar_sounds,
+            analysis: s.analysis,
+            analysis_normalized: s.analysis_normalized,
+            analysis_stats: s.analysis_stats,
+            analysis_frames: s.analysis_frames,
+            ac_analysis: s.ac_analysis,
+            present_fields: None,
+            meta: None,
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use super::ids::Username;
+use super::page::Page;
+use super::search_sound::{SearchSound, nullable};
+use crate::error::Result;
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+
+/// A search result borrowing its text from the response body
+///
+/// Parsing a page of [`search_bytes`](crate::FreesoundClient::search_bytes)
+/// with [`SoundRef::page`] allocates no string unless it holds escape
+/// sequences, which matters to crawlers reading millions of results. Only the
+/// flat fields are read; use [`SearchSound`] for previews, images and
+/// analysis. Like it, a field missing from the response is `None`, and a
+/// nullable field sent as `null` is `Some(None)`.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::SoundRef;
+/// use std::borrow::Cow;
+///
+/// let body = br#"{
+///     "count": 2,
+///     "next": null,
+///     "previous": null,
+///     "results": [
+///         {"id": 1, "name": "Rain", "tags": ["rain", "field-recording"], "username": "Jovica"},
+///         {"id": 2, "name": "\"Thunder\"", "duration": 12.5}
+///     ]
+/// }"#;
+/// let page = SoundRef::page(body)?;
+/// let rain = &page.results[0];
+/// assert!(matches!(rain.name, Some(Cow::Borrowed("Rain"))));
+/// assert_eq!(rain.tags.as_ref().map(Vec::len), Some(2));
+/// // Escaped strings have to be copied
+/// assert!(matches!(page.results[1].name, Some(Cow::Owned(_))));
+///
+/// let owned = page.results[0].clone().into_owned();
+/// assert_eq!(owned.username.as_deref(), Some("Jovica"));
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SoundRef<'a> {
+    /// The sound's unique identifier
+    pub id: Option<i32>,
+    /// The URI for this sound on the Freesound website
+    #[serde(borrow, default, deserialize_with = "borrowed")]
+    pub url: Option<Cow<'a, str>>,
+    /// The name user gave to the sound
+    #[serde(borrow, default, deserialize_with = "borrowed")]
+    pub name: Option<Cow<'a, str>>,
+    /// An array of tags the user gave to the sound
+    #[serde(borrow, default, deserialize_with = "borrowed_list")]
+    pub tags: Option<Vec<Cow<'a, str>>>,
+    /// The description the user gave to the sound
+    #[serde(borrow, default, deserialize_with = "borrowed")]
+    pub description: Option<Cow<'a, str>>,
+    /// Latitude and longitude of the geotag, when requested and available
+    #[serde(borrow, default, deserialize_with = "borrowed_nullable")]
+    pub geotag: Option<Option<Cow<'a, str>>>,
+    /// The date when the sound was uploaded
+    #[serde(borrow, default, deserialize_with = "borrowed")]
+    pub created: Option<Cow<'a, str>>,
+    /// The license under which the sound is available
+    #[serde(borrow, default, deserialize_with = "borrowed")]
+    pub license: Option<Cow<'a, str>>,
+    /// The type of sound (wav, aif, aiff, mp3, m4a or flac)
+    #[serde(rename = "type", borrow, default, deserialize_with = "borrowed")]
+    pub sound_type: Option<Cow<'a, str>>,
+    /// The number of channels
+    pub channels: Option<i32>,
+    /// The size of the file in bytes
+    pub filesize: Option<i32>,
+    /// The MD5 checksum of the original file
+    #[serde(borrow, default, deserialize_with = "borrowed")]
+    pub md5: Option<Cow<'a, str>>,
+    /// The bit rate of the sound in kbps
+    #[serde(default, deserialize_with = "nullable")]
+    pub bitrate: Option<Option<f32>>,
+    /// The bit depth of the sound
+    #[serde(default, deserialize_with = "nullable")]
+    pub bitdepth: Option<Option<i32>>,
+    /// The duration of the sound in seconds
+    pub duration: Option<f32>,
+    /// The sample rate of the sound
+    pub samplerate: Option<f32>,
+    /// The username of the uploader
+    #[serde(borrow, default, deserialize_with = "borrowed")]
+    pub username: Option<Cow<'a, str>>,
+    /// URI pointing to the pack API resource, when requested and in a pack
+    #[serde(borrow, default, deserialize_with = "borrowed_nullable")]
+    pub pack: Option<Option<Cow<'a, str>>>,
+    /// The number of times the sound was downloaded
+    pub num_downloads: Option<i32>,
+    /// The average rating of the sound
+    pub avg_rating: Option<f32>,
+    /// The number of times the sound was rated
+    pub num_ratings: Option<i32>,
+    /// The number of comments
+    pub num_comments: Option<i32>,
+}
+
+impl<'a> SoundRef<'a> {
+    /// Parses a page of results, borrowing from `body`
+    ///
+    /// `body` is a response of a search, such as the one returned by
+    /// [`search_bytes`](crate::FreesoundClient::search_bytes).
+    pub fn page(body: &'a [u8]) -> Result<Page<SoundRef<'a>>> {
+        Ok(serde_json::from_slice(body)?)
+    }
+
+    /// Copies the borrowed text, to keep the result after the body is dropped
+    pub fn into_owned(self) -> SearchSound {
+        let owned = |s: Option<Cow<'a, str>>| s.map(Cow::into_owned);
+        SearchSound {
+            id: self.id,
+            url: owned(self.url),
+            name: owned(self.name),
+            tags: self
+                .tags
+                .map(|tags| tags.into_iter().map(Cow::into_owned).collect()),
+            description: owned(self.description),
+            geotag: self.geotag.map(owned),
+            created: owned(self.created),
+            license: owned(self.license),
+            sound_type: owned(self.sound_type),
+            channels: self.channels,
+            filesize: self.filesize,
+            md5: owned(self.md5),
+            bitrate: self.bitrate,
+            bitdepth: self.bitdepth,
+            duration: self.duration,
+            samplerate: self.samplerate,
+            username: owned(self.username).map(Username::from_response),
+            pack: self.pack.map(owned),
+            num_downloads: self.num_downloads,
+            avg_rating: self.avg_rating,
+            num_ratings: self.num_ratings,
+            num_comments: self.num_comments,
+            ..SearchSound::default()
+        }
+    }
+}
+
+/// A string borrowed from the input when it holds no escape sequence
+///
+/// The `Deserialize` implementation of `Cow` always copies, except for fields
+/// of type `Cow<str>` themselves.
+struct Text<'a>(Cow<'a, str>);
+
+impl<'de: 'a, 'a> Deserialize<'de> for Text<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> serde::de::Visitor<'de> for Visitor {
+            type Value = Cow<'de, str>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_borrowed_str<E>(self, s: &'de str) -> std::result::Result<Self::Value, E> {
+                Ok(Cow::Borrowed(s))
+            }
+
+            fn visit_str<E>(self, s: &str) -> std::result::Result<Self::Value, E> {
+                Ok(Cow::Owned(s.to_string()))
+            }
+
+            fn visit_string<E>(self, s: String) -> std::result::Result<Self::Value, E> {
+                Ok(Cow::Owned(s))
+            }
+        }
+
+        deserializer.deserialize_str(Visitor).map(Text)
+    }
+}
+
+fn borrowed<'de: 'a, 'a, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Cow<'a, str>>, D::Error> {
+    Ok(Option::<Text>::deserialize(deserializer)?.map(|text| text.0))
+}
+
+fn borrowed_list<'de: 'a, 'a, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Vec<Cow<'a, str>>>, D::Error> {
+    Ok(Option::<Vec<Text>>::deserialize(deserializer)?
+        .map(|list| list.into_iter().map(|text| text.0).collect()))
+}
+
+fn borrowed_nullable<'de: 'a, 'a, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Option<Cow<'a, str>>>, D::Error> {
+    borrowed(deserializer).map(Some)
+}
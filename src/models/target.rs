@@ -0,0 +1,156 @@
+use super::sound::Sound;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Scalar(f64),
+    Vector(Vec<f64>),
+}
+
+/// Descriptor values a content or combined search should get close to
+///
+/// Displays in the syntax of the `target` parameter, so it can be passed to
+/// [`CombinedSearchBuilder::target`](crate::CombinedSearchBuilder::target).
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{CombinedSearchBuilder, Target};
+///
+/// let target = Target::new()
+///     .set("lowlevel.pitch.mean", 440.0)
+///     .set("rhythm.bpm", 120.0)
+///     .set_vector("lowlevel.mfcc.mean", [1.5, -2.0]);
+/// assert_eq!(
+///     target.to_string(),
+///     "lowlevel.pitch.mean:440 rhythm.bpm:120 lowlevel.mfcc.mean:[1.5,-2]"
+/// );
+///
+/// let query = CombinedSearchBuilder::new().query("bell").target(target).build()?;
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Target {
+    values: Vec<(String, Value)>,
+}
+
+impl Target {
+    /// Create a new, empty Target
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the value of a descriptor, replacing any previous one
+    pub fn set(self, descriptor: impl AsRef<str>, value: f64) -> Self {
+        self.insert(descriptor.as_ref(), Value::Scalar(value))
+    }
+
+    /// Set the value of a multidimensional descriptor, such as `lowlevel.mfcc.mean`
+    pub fn set_vector(
+        self,
+        descriptor: impl AsRef<str>,
+        values: impl IntoIterator<Item = f64>,
+    ) -> Self {
+        self.insert(
+            descriptor.as_ref(),
+            Value::Vector(values.into_iter().collect()),
+        )
+    }
+
+    fn insert(mut self, descriptor: &str, value: Value) -> Self {
+        match self.values.iter_mut().find(|(name, _)| name == descriptor) {
+            Some((_, previous)) => *previous = value,
+            None => self.values.push((descriptor.to_string(), value)),
+        }
+        self
+    }
+
+    /// Returns the number of descriptors set
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns true if no descriptor is set
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Builds a target from the numeric descriptors of an analyzed sound
+    ///
+    /// Every number, and every array of numbers, of the sound's `analysis` is
+    /// used, under its dotted name. The sound must have been fetched with the
+    /// descriptors wanted; without analysis, the target is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::{Sound, Target};
+    ///
+    /// let sound = Sound {
+    ///     analysis: Some(serde_json::json!({
+    ///         "rhythm": { "bpm": 120.0 },
+    ///         "tonal": { "key_key": "A", "hpcp_entropy": { "mean": 2.5 } },
+    ///     })),
+    ///     ..Default::default()
+    /// };
+    /// let target = Target::from_sound(&sound);
+    /// assert_eq!(target.to_string(), "rhythm.bpm:120 tonal.hpcp_entropy.mean:2.5");
+    /// ```
+    pub fn from_sound(sound: &Sound) -> Self {
+        let mut target = Self::new();
+        if let Some(analysis) = &sound.analysis {
+            collect(&mut target, String::new(), analysis);
+        }
+        target
+    }
+}
+
+fn collect(target: &mut Target, name: String, value: &serde_json::Value) {
+    match value {
+        serde_json::Value::Number(n) => {
+            if let Some(n) = n.as_f64() {
+                target.values.push((name, Value::Scalar(n)));
+            }
+        }
+        serde_json::Value::Array(items) if !items.is_empty() => {
+            let numbers: Option<Vec<f64>> = items.iter().map(|v| v.as_f64()).collect();
+            if let Some(numbers) = numbers {
+                target.values.push((name, Value::Vector(numbers)));
+            }
+        }
+        serde_json::Value::Object(fields) => {
+            for (key, value) in fields {
+                let name = match name.is_empty() {
+                    true => key.clone(),
+                    false => format!("{name}.{key}"),
+                };
+                collect(target, name, value);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, value)) in self.values.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            match value {
+                Value::Scalar(v) => write!(f, "{name}:{v}")?,
+                Value::Vector(values) => {
+                    let values: Vec<_> = values.iter().map(f64::to_string).collect();
+                    write!(f, "{name}:[{}]", values.join(","))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl From<Target> for String {
+    fn from(target: Target) -> Self {
+        target.to_string()
+    }
+}
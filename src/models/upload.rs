@@ -0,0 +1,123 @@
+use super::sound::Images;
+use serde::{Deserialize, Serialize};
+
+/// The uploads of the authenticated user that are not published yet
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PendingUploads {
+    /// File names of the uploads waiting for a description
+    pub pending_description: Vec<String>,
+    /// Described sounds waiting to be processed
+    pub pending_processing: Vec<PendingSound>,
+    /// Processed sounds waiting for moderation
+    pub pending_moderation: Vec<PendingSound>,
+}
+
+/// A described sound that is not published yet
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PendingSound {
+    /// The sound's unique identifier
+    pub id: i32,
+    /// The name given to the sound
+    pub name: String,
+    /// The name of the uploaded file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_filename: Option<String>,
+    /// The tags of the sound
+    pub tags: Vec<String>,
+    /// The description of the sound
+    pub description: String,
+    /// The date when the sound was described
+    pub created: String,
+    /// The license of the sound
+    pub license: String,
+    /// The processing state, for sounds pending processing (e.g. `"Failed"`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub processing_state: Option<String>,
+    /// Visual representations, for sounds pending moderation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub images: Option<Images>,
+}
+
+/// Where an upload stands between the upload and its publication
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UploadStatus {
+    /// The file was uploaded but not described yet
+    PendingDescription,
+    /// The sound was described and is being processed
+    PendingProcessing(i32),
+    /// The sound was processed and waits for a moderator
+    PendingModeration(i32),
+    /// The sound was approved and is public
+    Published(i32),
+}
+
+impl UploadStatus {
+    /// Returns the sound id, once the upload was described
+    pub fn sound_id(&self) -> Option<i32> {
+        match *self {
+            Self::PendingDescription => None,
+            Self::PendingProcessing(id) | Self::PendingModeration(id) | Self::Published(id) => {
+                Some(id)
+            }
+        }
+    }
+
+    /// Returns true once the sound is public
+    pub fn is_published(&self) -> bool {
+        matches!(self, Self::Published(_))
+    }
+}
+
+impl PendingUploads {
+    /// Find the status of an upload among the pending ones
+    ///
+    /// Described uploads are identified by `sound_id` when known, or else by
+    /// the name of their file; the name given to the sound is only used when
+    /// the response does not hold the file name. Returns `None` if the upload
+    /// is not pending, which happens once it is published, or rejected:
+    /// [`FreesoundClient::track_upload`](crate::FreesoundClient::track_upload)
+    /// looks the sound up to tell.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::{PendingSound, PendingUploads, UploadStatus};
+    ///
+    /// let pending = PendingUploads {
+    ///     pending_description: vec!["rain.wav".to_string()],
+    ///     pending_moderation: vec![PendingSound {
+    ///         id: 42,
+    ///         name: "Wind in the trees".to_string(),
+    ///         original_filename: Some("wind.wav".to_string()),
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(pending.status_of("rain.wav", None), Some(UploadStatus::PendingDescription));
+    /// assert_eq!(pending.status_of("wind.wav", None), Some(UploadStatus::PendingModeration(42)));
+    /// assert_eq!(pending.status_of("wind.wav", Some(42)), Some(UploadStatus::PendingModeration(42)));
+    /// assert_eq!(pending.status_of("wave.wav", Some(7)), None);
+    /// assert_eq!(pending.status_of("wave.wav", None), None);
+    /// ```
+    pub fn status_of(&self, filename: &str, sound_id: Option<i32>) -> Option<UploadStatus> {
+        let matches = |sound: &&PendingSound| match sound_id {
+            Some(id) => sound.id == id,
+            None => sound.original_filename.as_deref().unwrap_or(&sound.name) == filename,
+        };
+
+        if let Some(sound) = self.pending_processing.iter().find(matches) {
+            return Some(UploadStatus::PendingProcessing(sound.id));
+        }
+        if let Some(sound) = self.pending_moderation.iter().find(matches) {
+            return Some(UploadStatus::PendingModeration(sound.id));
+        }
+        if sound_id.is_none() && self.pending_description.iter().any(|f| f == filename) {
+            return Some(UploadStatus::PendingDescription);
+        }
+        None
+    }
+}
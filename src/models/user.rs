@@ -0,0 +1,84 @@
+use super::ids::Username;
+use serde::{Deserialize, Serialize};
+
+/// A Freesound user
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct User {
+    /// The URI for this user's profile on the Freesound website
+    pub url: String,
+    /// The username
+    pub username: Username,
+    /// The 'about' text of the user's profile
+    pub about: String,
+    /// The URI of the user's homepage (if any)
+    pub home_page: Option<String>,
+    /// URLs of the user's avatar in several sizes
+    #[serde(default)]
+    pub avatar: Option<Avatar>,
+    /// The date when the user joined Freesound
+    pub date_joined: String,
+    /// The number of sounds uploaded by the user
+    pub num_sounds: i32,
+    /// URI pointing to the list of sounds uploaded by the user
+    pub sounds: String,
+    /// The number of packs created by the user
+    pub num_packs: i32,
+    /// URI pointing to the list of packs created by the user
+    pub packs: String,
+    /// The number of forum posts of the user
+    pub num_posts: i32,
+    /// The number of comments the user made on other users' sounds
+    pub num_comments: i32,
+}
+
+/// Avatar URLs of a user
+///
+/// Each size is `None` when the user has no avatar.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Avatar {
+    /// Small avatar (32x32)
+    pub small: Option<String>,
+    /// Medium avatar (40x40)
+    pub medium: Option<String>,
+    /// Large avatar (70x70)
+    pub large: Option<String>,
+}
+
+impl Avatar {
+    /// Returns the avatar URL for the given size, if any
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::{Avatar, AvatarSize};
+    ///
+    /// let avatar: Avatar = serde_json::from_str(
+    ///     r#"{"small": "https://example.org/s.jpg", "medium": null, "large": null}"#,
+    /// )?;
+    /// assert_eq!(avatar.url(AvatarSize::Small), Some("https://example.org/s.jpg"));
+    /// assert_eq!(avatar.url(AvatarSize::Large), None);
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn url(&self, size: AvatarSize) -> Option<&str> {
+        match size {
+            AvatarSize::Small => self.small.as_deref(),
+            AvatarSize::Medium => self.medium.as_deref(),
+            AvatarSize::Large => self.large.as_deref(),
+        }
+    }
+}
+
+/// Available avatar sizes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AvatarSize {
+    /// Small avatar (32x32)
+    Small,
+    /// Medium avatar (40x40, default)
+    #[default]
+    Medium,
+    /// Large avatar (70x70)
+    Large,
+}
@@ -0,0 +1,536 @@
+use crate::client::FreesoundClient;
+use crate::error::{FreesoundError, Result};
+use blocking::unblock;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Time before the expiry from which tokens are considered expired
+const EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// Tokens granted by the Freesound OAuth2 server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuthTokens {
+    /// Token sent with the requests, see [`FreesoundClient::with_access_token`]
+    pub access_token: String,
+    /// Token used to get a new access token once it expires
+    pub refresh_token: String,
+    /// Lifetime of the access token, in seconds
+    pub expires_in: u64,
+    /// Scope granted to the application
+    #[serde(default)]
+    pub scope: String,
+    /// When the tokens were granted, in seconds since the Unix epoch
+    #[serde(default)]
+    pub obtained_at: u64,
+}
+
+impl OAuthTokens {
+    /// Returns when the access token expires
+    pub fn expires_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.obtained_at + self.expires_in)
+    }
+
+    /// Returns true if the access token expired, or is about to
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::OAuthTokens;
+    ///
+    /// let tokens = OAuthTokens {
+    ///     access_token: "access".to_string(),
+    ///     refresh_token: "refresh".to_string(),
+    ///     expires_in: 86400,
+    ///     scope: "read write".to_string(),
+    ///     obtained_at: 0,
+    /// };
+    /// assert!(tokens.is_expired());
+    /// ```
+    pub fn is_expired(&self) -> bool {
+        !self
+            .expires_at()
+            .duration_since(SystemTime::now())
+            .is_ok_and(|left| left > EXPIRY_MARGIN)
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs())
+}
+
+impl FreesoundClient {
+    /// Returns the page where a user authorizes an application
+    ///
+    /// Once the user accepts, Freesound redirects to the redirect URL of the
+    /// application with a `code` parameter (and `state`, if one was given), to be
+    /// exchanged with [`exchange_code`](Self::exchange_code).
+    ///
+    /// Fails with [`InvalidRequest`](FreesoundError::InvalidRequest) if the
+    /// base URL of the client is not a valid URL.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::FreesoundClient;
+    ///
+    /// let client = FreesoundClient::new("API_KEY".to_string(), None);
+    /// assert_eq!(
+    ///     client.authorize_url("CLIENT_ID", Some("xyz"))?,
+    ///     "https://freesound.org/apiv2/oauth2/authorize/?client_id=CLIENT_ID&response_type=code&state=xyz"
+    /// );
+    ///
+    /// let custom = FreesoundClient::new("API_KEY".to_string(), Some("custom.api.url".to_string()));
+    /// assert!(custom.authorize_url("CLIENT_ID", None).is_err());
+    /// # Ok::<(), freesound_rs::FreesoundError>(())
+    /// ```
+    pub fn authorize_url(&self, client_id: &str, state: Option<&str>) -> Result<String> {
+        let base_url = self.base_url();
+        let mut url =
+            reqwest::Url::parse(&format!("{base_url}/oauth2/authorize/")).map_err(|e| {
+                FreesoundError::InvalidRequest(format!("invalid base URL {base_url}: {e}"))
+            })?;
+        url.query_pairs_mut()
+            .append_pair("client_id", client_id)
+            .append_pair("response_type", "code");
+        if let Some(state) = state {
+            url.query_pairs_mut().append_pair("state", state);
+        }
+        Ok(url.into())
+    }
+
+    /// Exchanges an authorization code for tokens
+    ///
+    /// Codes expire ten minutes after being issued and can be used once.
+    pub async fn exchange_code(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        code: &str,
+    ) -> Result<OAuthTokens> {
+        self.request_tokens(&[
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+            ("grant_type", "authorization_code"),
+            ("code", code),
+        ])
+        .await
+    }
+
+    /// Gets new tokens from a refresh token
+    ///
+    /// The refresh token can be used once: the returned tokens replace the old ones.
+    pub async fn refresh_tokens(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        refresh_token: &str,
+    ) -> Result<OAuthTokens> {
+        self.request_tokens(&[
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ])
+        .await
+    }
+
+    /// Use the access token of `tokens` to authenticate requests
+    pub fn with_tokens(self, tokens: &OAuthTokens) -> Self {
+        self.with_access_token(tokens.access_token.clone())
+    }
+
+    async fn request_tokens(&self, form: &[(&str, &str)]) -> Result<OAuthTokens> {
+        let url = format!("{}/oauth2/access_token/", self.base_url());
+        let mut tokens: OAuthTokens = self
+            .send_json(self.http_client().post(url).form(form))
+            .await?;
+        tokens.obtained_at = unix_now();
+        Ok(tokens)
+    }
+}
+
+/// Keeps OAuth2 tokens between runs
+pub trait TokenStore: Send + Sync {
+    /// Returns the saved tokens, if any
+    fn load(&self) -> Result<Option<OAuthTokens>>;
+
+    /// Saves new tokens, replacing the previous ones
+    fn save(&self, tokens: &OAuthTokens) -> Result<()>;
+
+    /// Forgets the saved tokens
+    fn clear(&self) -> Result<()>;
+}
+
+/// A [`TokenStore`] keeping the tokens in memory only
+#[derive(Debug, Default)]
+pub struct MemoryTokenStore(Mutex<Option<OAuthTokens>>);
+
+impl TokenStore for MemoryTokenStore {
+    fn load(&self) -> Result<Option<OAuthTokens>> {
+        Ok(self.0.lock().unwrap().clone())
+    }
+
+    fn save(&self, tokens: &OAuthTokens) -> Result<()> {
+        *self.0.lock().unwrap() = Some(tokens.clone());
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        *self.0.lock().unwrap() = None;
+        Ok(())
+    }
+}
+
+/// A [`TokenStore`] keeping the tokens in a JSON file
+///
+/// The file is locked while it is read or written, and is only readable by its
+/// owner on Unix. Programs using the same file share the login session, like
+/// successive runs of a command line tool.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::env;
+/// use freesound_rs::{FileTokenStore, FreesoundClient};
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     dotenvy::dotenv().ok();
+///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+///     let client = FreesoundClient::new(api_key, None);
+/// let client_id = env::var("FREESOUND_CLIENT_ID")?;
+/// let client_secret = env::var("FREESOUND_CLIENT_SECRET")?;
+///
+/// let store = FileTokenStore::in_config_dir()?;
+/// let Some(tokens) = store.fresh_tokens(&client, &client_id, &client_secret).await? else {
+///     return Err("not logged in".into());
+/// };
+/// let client = client.with_tokens(&tokens);
+///      Ok(())
+///  }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FileTokenStore {
+    path: PathBuf,
+}
+
+impl FileTokenStore {
+    /// Creates a store using the file at `path`, which is created on first save
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Creates a store using `tokens.json` in the user configuration directory
+    ///
+    /// That is `~/.config/freesound-rs` on Linux, `~/Library/Application
+    /// Support/org.Freesound.freesound-rs` on macOS and
+    /// `%APPDATA%\Freesound\freesound-rs\config` on Windows.
+    pub fn in_config_dir() -> Result<Self> {
+        Self::default_path().map(Self::new).ok_or_else(|| {
+            FreesoundError::IoError(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no home directory to keep the tokens in",
+            ))
+        })
+    }
+
+    /// Returns the file used by [`in_config_dir`](Self::in_config_dir), if there is a home directory
+    pub fn default_path() -> Option<PathBuf> {
+        directories::ProjectDirs::from("org", "Freesound", "freesound-rs")
+            .map(|dirs| dirs.config_dir().join("tokens.json"))
+    }
+
+    /// Returns the path of the token file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the saved tokens, refreshing them first if they expired
+    ///
+    /// The refresh happens under a lock, so concurrent programs sharing the file
+    /// do not each spend the single-use refresh token. Returns `None` if no
+    /// tokens were saved.
+    pub async fn fresh_tokens(
+        &self,
+        client: &FreesoundClient,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Result<Option<OAuthTokens>> {
+        // Waiting for another program to release the lock must not stall the
+        // executor, so the file accesses run on the blocking pool
+        let lock_path = self.path.with_extension("lock");
+        let _guard = unblock(move || lock_file(&lock_path)).await?;
+        let store = self.clone();
+        let Some(tokens) = unblock(move || store.load()).await? else {
+            return Ok(None);
+        };
+        if !tokens.is_expired() {
+            return Ok(Some(tokens));
+        }
+
+        let tokens = client
+            .refresh_tokens(client_id, client_secret, &tokens.refresh_token)
+            .await?;
+        let (store, saved) = (self.clone(), tokens.clone());
+        unblock(move || store.save(&saved)).await?;
+        Ok(Some(tokens))
+    }
+}
+
+/// Opens and exclusively locks a file, creating it and its directory if needed
+///
+/// Blocks until no other program holds the lock.
+fn lock_file(path: &Path) -> Result<File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = OpenOptions::new();
+    options.read(true).write(true).create(true).truncate(false);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let file = options.open(path)?;
+    file.lock()?;
+    Ok(file)
+}
+
+impl TokenStore for FileTokenStore {
+    fn load(&self) -> Result<Option<OAuthTokens>> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        file.lock_shared()?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+        if content.trim().is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    fn save(&self, tokens: &OAuthTokens) -> Result<()> {
+        let mut file = lock_file(&self.path)?;
+        file.set_len(0)?;
+        serde_json::to_writer_pretty(&mut file, tokens)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Port of the redirect URL used by [`FreesoundClient::login_interactive`]
+///
+/// The redirect URL of the application, set in its Freesound API credentials,
+/// must be `http://127.0.0.1:8910/` (any path works): the listener only binds
+/// the IPv4 loopback address, which `localhost` may not resolve to.
+pub const LOGIN_REDIRECT_PORT: u16 = 8910;
+
+/// Time given to the user to authorize the application
+pub const LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+impl FreesoundClient {
+    /// Logs a user in through their browser, and returns the tokens
+    ///
+    /// Prints the authorization page URL on the standard error, opens it in the
+    /// browser with the `browser` feature, and listens on
+    /// [`LOGIN_REDIRECT_PORT`] for the redirection carrying the code, which is
+    /// then exchanged for tokens. Fails if the user does not authorize the
+    /// application within [`LOGIN_TIMEOUT`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FileTokenStore, FreesoundClient, TokenStore};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let client_id = env::var("FREESOUND_CLIENT_ID")?;
+    /// let client_secret = env::var("FREESOUND_CLIENT_SECRET")?;
+    ///
+    /// let tokens = client.login_interactive(&client_id, &client_secret).await?;
+    /// FileTokenStore::in_config_dir()?.save(&tokens)?;
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn login_interactive(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Result<OAuthTokens> {
+        self.login_interactive_on(client_id, client_secret, LOGIN_REDIRECT_PORT)
+            .await
+    }
+
+    /// Logs a user in through their browser, listening on another port
+    ///
+    /// See [`login_interactive`](Self::login_interactive).
+    pub async fn login_interactive_on(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+        port: u16,
+    ) -> Result<OAuthTokens> {
+        let listener = std::net::TcpListener::bind(("127.0.0.1", port))?;
+        listener.set_nonblocking(true)?;
+
+        let state = random_state()?;
+        let url = self.authorize_url(client_id, Some(&state))?;
+        eprintln!("Open this page to authorize the application:\n{url}");
+        #[cfg(feature = "browser")]
+        let _ = webbrowser::open(&url);
+
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(wait_for_redirect(&listener, LOGIN_TIMEOUT));
+        });
+        let params = receiver
+            .await
+            .map_err(|_| FreesoundError::AuthError("the login listener stopped".to_string()))??;
+
+        let param = |name: &str| params.iter().find(|(k, _)| k == name).map(|(_, v)| v);
+        if param("state") != Some(&state) {
+            return Err(FreesoundError::AuthError(
+                "the authorization state does not match".to_string(),
+            ));
+        }
+        match (param("code"), param("error")) {
+            (Some(code), _) => self.exchange_code(client_id, client_secret, code).await,
+            (None, error) => Err(FreesoundError::AuthError(format!(
+                "authorization refused: {}",
+                error.map_or("no code", String::as_str)
+            ))),
+        }
+    }
+}
+
+/// Returns a random value for the `state` parameter, from the system CSPRNG
+fn random_state() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|e| std::io::Error::other(e.to_string()))?;
+    Ok(bytes.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+/// Accepts connections until one carries a code or an error, and returns its query
+fn wait_for_redirect(
+    listener: &std::net::TcpListener,
+    timeout: Duration,
+) -> Result<Vec<(String, String)>> {
+    use std::io::{BufRead, BufReader};
+
+    let deadline = std::time::Instant::now() + timeout;
+    loop {
+        let mut stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                if std::time::Instant::now() >= deadline {
+                    return Err(FreesoundError::AuthError(
+                        "timed out waiting for the authorization".to_string(),
+                    ));
+                }
+                std::thread::sleep(Duration::from_millis(100));
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        // A connection that sends nothing, such as a browser preconnect, must
+        // not end the login: it is dropped and the next one is awaited
+        let mut request_line = String::new();
+        let read = stream
+            .set_nonblocking(false)
+            .and_then(|_| stream.set_read_timeout(Some(Duration::from_secs(5))))
+            .and_then(|_| BufReader::new(&stream).read_line(&mut request_line));
+        if let Err(e) = read {
+            log::debug!("Dropping a connection to the login listener: {e}");
+            continue;
+        }
+        let target = request_line.split_whitespace().nth(1).unwrap_or("/");
+        let params: Vec<(String, String)> = reqwest::Url::parse("http://localhost")
+            .and_then(|base| base.join(target))
+            .map(|url| url.query_pairs().into_owned().collect())
+            .unwrap_or_default();
+
+        if !params.iter().any(|(k, _)| k == "code" || k == "error") {
+            let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+            continue;
+        }
+        let body =
+            "<html><body>You can close this window and go back to the terminal.</body></html>";
+        let _ = write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        return Ok(params);
+    }
+}
+
+/// Redirect URL of applications using [`FreesoundClient::login_out_of_band`]
+///
+/// Freesound shows the authorization code on this page instead of redirecting
+/// elsewhere.
+pub const OUT_OF_BAND_REDIRECT_URL: &str =
+    "https://freesound.org/home/app_permissions/permission_granted/";
+
+impl FreesoundClient {
+    /// Logs a user in by copying the authorization code by hand
+    ///
+    /// For machines without a browser, such as servers reached over SSH: the
+    /// authorization URL is printed on the standard error, to be opened on any
+    /// other device, and the code Freesound shows once the application is
+    /// authorized is read from the standard input, then exchanged for tokens.
+    /// The whole redirected URL can be pasted as well.
+    ///
+    /// The redirect URL of the application must be [`OUT_OF_BAND_REDIRECT_URL`]
+    /// for Freesound to show the code.
+    pub async fn login_out_of_band(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Result<OAuthTokens> {
+        let url = self.authorize_url(client_id, None)?;
+        eprintln!("Open this page on any device to authorize the application:\n{url}");
+        eprint!("Paste the authorization code: ");
+
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        std::thread::spawn(move || {
+            let mut line = String::new();
+            let _ = sender.send(std::io::stdin().read_line(&mut line).map(|_| line));
+        });
+        let line = receiver
+            .await
+            .map_err(|_| FreesoundError::AuthError("could not read the code".to_string()))??;
+
+        let code = parse_pasted_code(&line).ok_or_else(|| {
+            FreesoundError::AuthError("no authorization code was pasted".to_string())
+        })?;
+        self.exchange_code(client_id, client_secret, &code).await
+    }
+}
+
+/// Extracts the code from a pasted code or redirected URL
+fn parse_pasted_code(pasted: &str) -> Option<String> {
+    let pasted = pasted.trim();
+    match reqwest::Url::parse(pasted) {
+        Ok(url) => url
+            .query_pairs()
+            .find(|(k, _)| k == "code")
+            .map(|(_, code)| code.into_owned()),
+        Err(_) if !pasted.is_empty() => Some(pasted.to_string()),
+        Err(_) => None,
+    }
+}
@@ -0,0 +1,287 @@
+use crate::client::FreesoundClient;
+use crate::error::{FreesoundError, Result};
+use crate::models::{CombinedSearchResponse, Comment, Page, Sound};
+use crate::retry::RetryPolicy;
+use futures::stream::{self, Stream, TryStreamExt};
+use serde::de::DeserializeOwned;
+
+/// Attempts made to fetch a `more` link of a combined search before giving up
+pub const MORE_ATTEMPTS: u32 = 3;
+
+impl FreesoundClient {
+    /// Fetch the page following `page`, if any
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FreesoundClient, SearchQueryBuilder};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let first = client
+    ///     .search(&SearchQueryBuilder::new().query("piano").build())
+    ///     .await?;
+    /// if let Some(second) = client.next_page(&first).await? {
+    ///     println!("Page 2 has {} sounds", second.results.len());
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn next_page<T: DeserializeOwned>(&self, page: &Page<T>) -> Result<Option<Page<T>>> {
+        match &page.next {
+            Some(url) => self.get_page(url).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetch the page preceding `page`, if any
+    pub async fn previous_page<T: DeserializeOwned>(
+        &self,
+        page: &Page<T>,
+    ) -> Result<Option<Page<T>>> {
+        match &page.previous {
+            Some(url) => self.get_page(url).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetch a page from a pagination link
+    pub async fn get_page<T: DeserializeOwned>(&self, url: &str) -> Result<Page<T>> {
+        self.get_link(url).await
+    }
+
+    /// Walk through pages, starting with `first` and following the `next` links
+    ///
+    /// The stream ends after the last page or after the first error. It also
+    /// ends, with a logged warning, when Freesound refuses to serve deeper pages
+    /// (see [`PaginationLimit`](FreesoundError::PaginationLimit)).
+    pub fn pages<T: DeserializeOwned>(
+        &self,
+        first: Page<T>,
+    ) -> impl Stream<Item = Result<Page<T>>> + use<T> {
+        let client = self.clone();
+        stream::unfold(Some(Ok(first)), move |state| {
+            let client = client.clone();
+            async move {
+                let page: Page<T> = match state? {
+                    Ok(page) => page,
+                    Err(e) => return Some((Err(e), None)),
+                };
+                let next = match &page.next {
+                    Some(url) => match client.get_page(url).await {
+                        Err(ref e)
+                            if let FreesoundError::PaginationLimit { max_reachable } = e.root() =>
+                        {
+                            log::warn!(
+                                "Freesound serves no page after page {max_reachable}, stopping there"
+                            );
+                            None
+                        }
+                        next => Some(next),
+                    },
+                    None => None,
+                };
+                Some((Ok(page), next))
+            }
+        })
+    }
+
+    /// Walk through the results of every page, starting with `first`
+    pub fn items<T: DeserializeOwned>(
+        &self,
+        first: Page<T>,
+    ) -> impl Stream<Item = Result<T>> + use<T> {
+        self.pages(first)
+            .map_ok(|page| stream::iter(page.results.into_iter().map(Ok)))
+            .try_flatten()
+    }
+
+    /// Search for sounds, yielding the results of every page
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use futures::{StreamExt, TryStreamExt};
+    /// use freesound_rs::{FreesoundClient, SearchQueryBuilder};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let query = SearchQueryBuilder::new().query("thunder").page_size(150).build();
+    /// let sounds: Vec<_> = client.search_stream(&query).take(500).try_collect().await?;
+    /// println!("Collected {} sounds", sounds.len());
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn search_stream(
+        &self,
+        query: &[(String, String)],
+    ) -> impl Stream<Item = Result<Sound>> + use<> {
+        let client = self.clone();
+        let query = query.to_vec();
+        stream::once(async move {
+            let first = client.search(&query).await?;
+            Ok::<_, FreesoundError>(client.items(first))
+        })
+        .try_flatten()
+    }
+
+    /// Search for sounds, collecting the results of every page
+    ///
+    /// Like [`search_stream`](Self::search_stream), this stops cleanly at the
+    /// deepest page Freesound serves; ask for a bigger `page_size` or narrow the
+    /// query to get more results.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FreesoundClient, SearchQueryBuilder};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let query = SearchQueryBuilder::new()
+    ///     .query("church bell")
+    ///     .page_size(150)
+    ///     .build();
+    /// let sounds = client.search_all(&query).await?;
+    /// println!("Collected {} sounds", sounds.len());
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn search_all(&self, query: &[(String, String)]) -> Result<Vec<Sound>> {
+        self.search_stream(query).try_collect().await
+    }
+
+    /// Stream every comment of a sound, newest first
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use futures::TryStreamExt;
+    /// use freesound_rs::FreesoundClient;
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let comments: Vec<_> = client.stream_sound_comments(1234).try_collect().await?;
+    /// for comment in comments {
+    ///     println!("{}: {}", comment.username, comment.comment);
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn stream_sound_comments(
+        &self,
+        sound_id: i32,
+    ) -> impl Stream<Item = Result<Comment>> + use<> {
+        let client = self.clone();
+        stream::once(async move {
+            let query = [("page_size".to_string(), "100".to_string())];
+            let first = client.get_sound_comments(sound_id, &query).await?;
+            Ok::<_, FreesoundError>(client.items(first))
+        })
+        .try_flatten()
+    }
+
+    /// Walk through the batches of a combined search, following the `more` links
+    ///
+    /// A `more` link failing with a server error, a rate limit, a timeout or a
+    /// connection error is tried again, up to [`MORE_ATTEMPTS`] times, waiting
+    /// longer each time. The stream ends after the last batch or after the
+    /// first error.
+    pub fn combined_batches(
+        &self,
+        first: CombinedSearchResponse,
+    ) -> impl Stream<Item = Result<CombinedSearchResponse>> + use<> {
+        let client = self.clone();
+        stream::unfold(Some(Ok(first)), move |state| {
+            let client = client.clone();
+            async move {
+                let batch = match state? {
+                    Ok(batch) => batch,
+                    Err(e) => return Some((Err(e), None)),
+                };
+                let next = match &batch.more {
+                    Some(url) => Some(client.get_more(url).await),
+                    None => None,
+                };
+                Some((Ok(batch), next))
+            }
+        })
+    }
+
+    /// Run a combined search, yielding the results of every batch
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use futures::{StreamExt, TryStreamExt};
+    /// use freesound_rs::{CombinedSearchBuilder, FreesoundClient};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let query = CombinedSearchBuilder::new()
+    ///     .query("bell")
+    ///     .target("lowlevel.pitch.mean:440")
+    ///     .build()?;
+    /// let sounds: Vec<_> = client.combined_search_stream(&query).take(200).try_collect().await?;
+    /// println!("Collected {} sounds", sounds.len());
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn combined_search_stream(
+        &self,
+        query: &[(String, String)],
+    ) -> impl Stream<Item = Result<Sound>> + use<> {
+        let client = self.clone();
+        let query = query.to_vec();
+        stream::once(async move {
+            let first = client.combined_search(&query).await?;
+            let batches = client.combined_batches(first);
+            Ok::<_, FreesoundError>(
+                batches.map_ok(|batch| stream::iter(batch.results.into_iter().map(Ok))),
+            )
+        })
+        .try_flatten()
+        .try_flatten()
+    }
+
+    /// Fetches a `more` link, trying again on transient failures
+    pub(crate) async fn get_more(&self, url: &str) -> Result<CombinedSearchResponse> {
+        let request = self.request_url(reqwest::Method::GET, url);
+        let response = self
+            .send_with_retries(request, RetryPolicy::new(MORE_ATTEMPTS))
+            .await?;
+        self.read_json(self.check_status(response).await?).await
+    }
+}
+
+impl CombinedSearchResponse {
+    /// Returns true if there is a batch after this one
+    pub fn has_more(&self) -> bool {
+        self.more.is_some()
+    }
+
+    /// Fetch the batch following this one, if any
+    ///
+    /// See [`FreesoundClient::combined_batches`] for how failures are retried.
+    pub async fn more(&self, client: &FreesoundClient) -> Result<Option<CombinedSearchResponse>> {
+        match &self.more {
+            Some(url) => client.get_more(url).await.map(Some),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use crate::models::{SearchResponse, Sound};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::hash::Hash;
+
+type Predicate = Box<dyn Fn(&Sound) -> bool + Send + Sync>;
+type Comparator = Box<dyn Fn(&Sound, &Sound) -> Ordering + Send + Sync>;
+
+/// Client-side post-processing of search results
+///
+/// A pipeline filters, deduplicates and re-ranks sounds, page after page.
+/// Deduplication remembers the sounds seen in previous pages, so the same
+/// pipeline should be reused for all the pages of a query. Ranking applies
+/// within each processed page.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{ResultPipeline, Sound};
+///
+/// let mut pipeline = ResultPipeline::new()
+///     .min_samplerate(44_100.0)
+///     .dedupe()
+///     .rank_by_rating();
+///
+/// let page = vec![
+///     Sound { id: 1, samplerate: 22_050.0, ..Default::default() },
+///     Sound { id: 2, samplerate: 48_000.0, avg_rating: 3.5, ..Default::default() },
+///     Sound { id: 3, samplerate: 44_100.0, avg_rating: 4.5, ..Default::default() },
+/// ];
+/// let ids: Vec<_> = pipeline.process(page).iter().map(|s| s.id).collect();
+/// assert_eq!(ids, [3, 2]);
+///
+/// // Sound 3 was already seen
+/// let next_page = vec![Sound { id: 3, samplerate: 44_100.0, ..Default::default() }];
+/// assert!(pipeline.process(next_page).is_empty());
+/// ```
+#[derive(Default)]
+pub struct ResultPipeline {
+    filters: Vec<Predicate>,
+    dedupe: Option<Box<dyn Seen>>,
+    ranking: Vec<Comparator>,
+}
+
+impl ResultPipeline {
+    /// Create a new pipeline, letting every sound through unchanged
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep only the sounds matching a predicate
+    pub fn filter(mut self, predicate: impl Fn(&Sound) -> bool + Send + Sync + 'static) -> Self {
+        self.filters.push(Box::new(predicate));
+        self
+    }
+
+    /// Drop sounds with a samplerate below `hz`
+    pub fn min_samplerate(self, hz: f32) -> Self {
+        self.filter(move |s| s.samplerate >= hz)
+    }
+
+    /// Drop sounds with a duration outside of `min..=max` seconds
+    pub fn duration_between(self, min: f32, max: f32) -> Self {
+        self.filter(move |s| (min..=max).contains(&s.duration))
+    }
+
+    /// Drop sounds already seen, by id
+    pub fn dedupe(self) -> Self {
+        self.dedupe_by(|s| s.id)
+    }
+
+    /// Drop sounds already seen, by a custom key
+    ///
+    /// For instance, `dedupe_by(|s| (s.username.clone(), s.name.clone()))`
+    /// drops re-uploads of the same file name by the same user.
+    pub fn dedupe_by<K: Hash + Eq + Send + Sync + 'static>(
+        mut self,
+        key: impl Fn(&Sound) -> K + Send + Sync + 'static,
+    ) -> Self {
+        self.dedupe = Some(Box::new(SeenKeys {
+            key,
+            seen: HashSet::new(),
+        }));
+        self
+    }
+
+    /// Sort sounds with a comparator
+    ///
+    /// Several rankings can be chained: later ones break the ties of earlier ones.
+    pub fn rank_by(
+        mut self,
+        compare: impl Fn(&Sound, &Sound) -> Ordering + Send + Sync + 'static,
+    ) -> Self {
+        self.ranking.push(Box::new(compare));
+        self
+    }
+
+    /// Sort sounds by ascending score, e.g. a distance to a target
+    pub fn rank_by_score(self, score: impl Fn(&Sound) -> f32 + Send + Sync + 'static) -> Self {
+        self.rank_by(move |a, b| score(a).total_cmp(&score(b)))
+    }
+
+    /// Sort sounds by average rating, best first
+    pub fn rank_by_rating(self) -> Self {
+        self.rank_by(|a, b| b.avg_rating.total_cmp(&a.avg_rating))
+    }
+
+    /// Sort sounds by number of downloads, most first
+    pub fn rank_by_downloads(self) -> Self {
+        self.rank_by(|a, b| b.num_downloads.cmp(&a.num_downloads))
+    }
+
+    /// Run a batch of sounds through the pipeline
+    pub fn process(&mut self, sounds: Vec<Sound>) -> Vec<Sound> {
+        let mut sounds: Vec<_> = sounds
+            .into_iter()
+            .filter(|s| self.filters.iter().all(|keep| keep(s)))
+            .filter(|s| match &mut self.dedupe {
+                Some(seen) => seen.insert(s),
+                None => true,
+            })
+            .collect();
+
+        if !self.ranking.is_empty() {
+            sounds.sort_by(|a, b| {
+                self.ranking
+                    .iter()
+                    .map(|compare| compare(a, b))
+                    .find(|o| o.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+
+        sounds
+    }
+
+    /// Run the results of a search page through the pipeline
+    pub fn process_page(&mut self, page: SearchResponse) -> Vec<Sound> {
+        self.process(page.results)
+    }
+
+    /// Forget the sounds seen so far
+    pub fn reset(&mut self) {
+        if let Some(seen) = &mut self.dedupe {
+            seen.clear();
+        }
+    }
+}
+
+/// The keys of the sounds seen by a deduplicating pipeline
+trait Seen: Send + Sync {
+    /// Remembers the key of a sound, returns `false` if it was already seen
+    fn insert(&mut self, sound: &Sound) -> bool;
+
+    fn clear(&mut self);
+}
+
+struct SeenKeys<K, F> {
+    key: F,
+    seen: HashSet<K>,
+}
+
+impl<K, F> Seen for SeenKeys<K, F>
+where
+    K: Hash + Eq + Send + Sync,
+    F: Fn(&Sound) -> K + Send + Sync,
+{
+    fn insert(&mut self, sound: &Sound) -> bool {
+        self.seen.insert((self.key)(sound))
+    }
+
+    fn clear(&mut self) {
+        self.seen.clear();
+    }
+}
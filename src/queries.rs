@@ -0,0 +1,94 @@
+use crate::error::Result;
+use crate::models::SearchQueryBuilder;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Named searches saved in a JSON file
+///
+/// The file maps each name to a [`SearchQueryBuilder`]; it is written on every
+/// change, so a program offering saved searches, or a scheduled job re-running
+/// them, always finds the latest version.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{QueryStore, SearchQueryBuilder};
+///
+/// let path = std::env::temp_dir().join("freesound-rs-doctest-queries.json");
+/// # let _ = std::fs::remove_file(&path);
+/// let mut store = QueryStore::open(&path)?;
+/// store.save("night rain", SearchQueryBuilder::new().query("rain").filter("tag:night"))?;
+///
+/// let store = QueryStore::open(&path)?;
+/// assert_eq!(store.names().collect::<Vec<_>>(), ["night rain"]);
+/// let query = store.get("night rain").unwrap().clone().page_size(50).build();
+/// assert_eq!(query[0], ("query".to_string(), "rain".to_string()));
+/// # std::fs::remove_file(&path)?;
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct QueryStore {
+    path: PathBuf,
+    queries: BTreeMap<String, SearchQueryBuilder>,
+}
+
+impl QueryStore {
+    /// Opens the store in the file at `path`, which is created on first save
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let queries = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, queries })
+    }
+
+    /// Returns the query saved under `name`
+    pub fn get(&self, name: &str) -> Option<&SearchQueryBuilder> {
+        self.queries.get(name)
+    }
+
+    /// Returns the names of the saved queries, in alphabetical order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.queries.keys().map(String::as_str)
+    }
+
+    /// Returns the number of saved queries
+    pub fn len(&self) -> usize {
+        self.queries.len()
+    }
+
+    /// Returns true if no query is saved
+    pub fn is_empty(&self) -> bool {
+        self.queries.is_empty()
+    }
+
+    /// Saves a query under `name`, replacing any query with the same name
+    pub fn save(&mut self, name: impl Into<String>, query: SearchQueryBuilder) -> Result<()> {
+        self.queries.insert(name.into(), query);
+        self.write()
+    }
+
+    /// Removes the query saved under `name`, and returns it
+    pub fn remove(&mut self, name: &str) -> Result<Option<SearchQueryBuilder>> {
+        let removed = self.queries.remove(name);
+        if removed.is_some() {
+            self.write()?;
+        }
+        Ok(removed)
+    }
+
+    /// Writes the queries, replacing the previous file once fully written
+    fn write(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(&self.queries)?;
+        let mut temporary = self.path.as_os_str().to_owned();
+        temporary.push(format!(".{}.tmp", std::process::id()));
+        std::fs::write(&temporary, json)?;
+        std::fs::rename(&temporary, &self.path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,225 @@
+use crate::cache::{DiskCache, SearchCache};
+use crate::client::FreesoundClient;
+use crate::clock::{Clock, SystemClock};
+use crate::download::{ContentStore, DownloadJob, DownloadSource};
+use crate::error::Result;
+use crate::limiter::RateLimiter;
+use crate::models::{Fields, GetSoundOptions, SearchResponse, Sound};
+use futures::{StreamExt, TryStreamExt, stream};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a [`SoundRepository`] keeps sound details and search pages
+pub const DEFAULT_METADATA_TTL: Duration = Duration::from_secs(3600);
+
+/// Size budget of the media cache of a [`SoundRepository`]
+pub const DEFAULT_MEDIA_CACHE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Number of sound details a [`SoundRepository`] fetches at once when hydrating
+pub const HYDRATION_CONCURRENCY: usize = 4;
+
+/// Directory of a [`SoundRepository`] holding the downloaded sounds
+const SOUNDS_DIR: &str = "sounds";
+
+/// Directory of a [`SoundRepository`] holding the cached media files
+const MEDIA_DIR: &str = "media";
+
+/// What a [`SoundRepository`] does with the partial sounds a search returns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Hydration {
+    /// Return them with the fields the search returned
+    #[default]
+    AsReturned,
+    /// Complete them with their details, from the cache or the API
+    Full,
+}
+
+/// A client with caches, rate limiting and a download store, behind a few
+/// simple methods
+///
+/// For applications that need sounds rather than API calls: sound details
+/// are kept in memory for a while, search pages go through a
+/// [`SearchCache`], previews through a [`DiskCache`], requests through a
+/// [`RateLimiter`], and downloaded files into a [`ContentStore`]. The pieces
+/// the client already has are kept, the missing ones get default settings.
+///
+/// The repository directory holds the store in `sounds/` and the media cache
+/// in `media/`.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{DownloadSource, FreesoundClient, Hydration, SoundRepository};
+///
+/// let dir = std::env::temp_dir().join("freesound-rs-doctest-repository");
+/// # let _ = std::fs::remove_dir_all(&dir);
+/// let client = FreesoundClient::new("API_KEY".to_string(), None);
+/// let repository = SoundRepository::open(client, &dir)?.hydration(Hydration::Full);
+/// assert!(repository.client().rate_limiter().is_some());
+/// assert!(repository.client().search_cache().is_some());
+///
+/// // Sounds already in the store are not downloaded again
+/// let file = dir.join("rain.mp3");
+/// std::fs::write(&file, b"not really an mp3")?;
+/// repository.store().insert(1234, DownloadSource::default(), &file)?;
+/// let path = futures::executor::block_on(repository.ensure_downloaded(1234))?;
+/// assert!(path.starts_with(dir.join("sounds")));
+/// # std::fs::remove_dir_all(&dir)?;
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct SoundRepository {
+    client: FreesoundClient,
+    store: Arc<ContentStore>,
+    sounds: Arc<Mutex<HashMap<i32, (Instant, Sound)>>>,
+    clock: Arc<dyn Clock>,
+    metadata_ttl: Duration,
+    hydration: Hydration,
+    source: DownloadSource,
+}
+
+impl SoundRepository {
+    /// Opens a repository in `dir`, completing the client with the pieces it lacks
+    pub fn open(client: FreesoundClient, dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let mut client = client;
+        if client.search_cache().is_none() {
+            client = client.with_search_cache(SearchCache::new(DEFAULT_METADATA_TTL));
+        }
+        if client.disk_cache().is_none() {
+            client = client.with_disk_cache(DiskCache::open(
+                dir.join(MEDIA_DIR),
+                DEFAULT_MEDIA_CACHE_BYTES,
+            )?);
+        }
+        if client.rate_limiter().is_none() {
+            client = client.with_rate_limiter(RateLimiter::default());
+        }
+        Ok(Self {
+            client,
+            store: Arc::new(ContentStore::open(dir.join(SOUNDS_DIR))?),
+            sounds: Arc::default(),
+            clock: Arc::new(SystemClock),
+            metadata_ttl: DEFAULT_METADATA_TTL,
+            hydration: Hydration::default(),
+            source: DownloadSource::default(),
+        })
+    }
+
+    /// Set what [`find`](Self::find) does with partial sounds
+    pub fn hydration(mut self, hydration: Hydration) -> Self {
+        self.hydration = hydration;
+        self
+    }
+
+    /// Set which file of the sounds [`ensure_downloaded`](Self::ensure_downloaded) downloads
+    ///
+    /// Originals need an OAuth2 access token. Defaults to the HQ MP3 preview.
+    pub fn download_source(mut self, source: DownloadSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Set how long sound details are kept
+    pub fn metadata_ttl(mut self, ttl: Duration) -> Self {
+        self.metadata_ttl = ttl;
+        self
+    }
+
+    /// Measure the age of sound details with another clock (see [`MockClock`](crate::MockClock))
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns the client, with the caches and rate limiter of the repository
+    pub fn client(&self) -> &FreesoundClient {
+        &self.client
+    }
+
+    /// Returns the store of the downloaded files
+    pub fn store(&self) -> &ContentStore {
+        &self.store
+    }
+
+    /// Search for sounds, returning the first page of results
+    ///
+    /// Results holding every default field are cached as sound details. With
+    /// [`Hydration::Full`], the others are replaced by their details.
+    pub async fn find(&self, query: &[(String, String)]) -> Result<SearchResponse> {
+        let mut page = self.client.search(query).await?;
+        let results = std::mem::take(&mut page.results);
+        page.results = stream::iter(results)
+            .map(|sound| async move {
+                if is_complete(&sound) {
+                    self.remember(&sound);
+                    return Ok(sound);
+                }
+                match self.hydration {
+                    Hydration::AsReturned => Ok(sound),
+                    Hydration::Full => self.get(sound.id).await,
+                }
+            })
+            .buffered(HYDRATION_CONCURRENCY)
+            .try_collect()
+            .await?;
+        Ok(page)
+    }
+
+    /// Get the details of a sound, from the cache if they are fresh enough
+    pub async fn get(&self, sound_id: i32) -> Result<Sound> {
+        let now = self.clock.now();
+        if let Some((stored, sound)) = self.sounds.lock().unwrap().get(&sound_id)
+            && now.duration_since(*stored) < self.metadata_ttl
+        {
+            return Ok(sound.clone());
+        }
+        let sound = self
+            .client
+            .get_sound(sound_id, &GetSoundOptions::new())
+            .await?;
+        self.remember(&sound);
+        Ok(sound)
+    }
+
+    /// Returns the path of the file of a sound, downloading it if the store
+    /// does not hold it yet
+    pub async fn ensure_downloaded(&self, sound_id: i32) -> Result<PathBuf> {
+        if let Some(path) = self.store.path_of(sound_id, self.source) {
+            return Ok(path);
+        }
+        let sound = self.get(sound_id).await?;
+        let job = DownloadJob::new(&self.client, &sound, self.source, "")?;
+        let incoming = self.store.incoming_path(&job);
+        if let Some(parent) = incoming.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let response = match job.source {
+            DownloadSource::Original => self.client.get_download(&job.url).await,
+            DownloadSource::Preview(_) => self.client.get_media(&job.url).await,
+        }
+        .map_err(|e| e.for_sound(sound_id))?;
+        self.client
+            .write_body_checked(response, &incoming, job.md5.as_deref(), |_| {})
+            .await?;
+        let entry = self.store.insert(sound_id, job.source, &incoming)?;
+        Ok(self.store.object_path(&entry.md5, &entry.extension))
+    }
+
+    fn remember(&self, sound: &Sound) {
+        let now = self.clock.now();
+        let ttl = self.metadata_ttl;
+        let mut sounds = self.sounds.lock().unwrap();
+        sounds.retain(|_, (stored, _)| now.duration_since(*stored) < ttl);
+        sounds.insert(sound.id, (now, sound.clone()));
+    }
+}
+
+/// Returns true if a sound holds every field the sound endpoint returns
+fn is_complete(sound: &Sound) -> bool {
+    Fields::default_set()
+        .iter()
+        .all(|field| sound.has_field(field))
+}
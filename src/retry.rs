@@ -0,0 +1,124 @@
+use crate::client::FreesoundClient;
+use crate::error::{FreesoundError, Result};
+use std::time::Duration;
+
+/// Default number of attempts made to fetch a file from a media host
+pub const DEFAULT_MEDIA_ATTEMPTS: u32 = 5;
+
+/// Default number of attempts made to fetch a file from the API
+pub const DEFAULT_API_ATTEMPTS: u32 = 3;
+
+/// How a file download is tried again on transient failures
+///
+/// Server errors, 429 responses, timeouts and connection failures are tried
+/// again until `attempts` were made in all, waiting `delay` before the first
+/// retry and twice as long before each following one. Only the request is
+/// retried: a transfer cut while the body is being read fails, and is left to
+/// the caller, such as a [`DownloadManager`](crate::DownloadManager) run again.
+///
+/// Previews and images are served by other hosts than the API, where
+/// transient 503s are common: they get their own policy, retrying sooner and
+/// more often. See [`FreesoundClient::with_media_retry`] and
+/// [`FreesoundClient::with_api_retry`].
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use freesound_rs::{FreesoundClient, RetryPolicy};
+///
+/// let client = FreesoundClient::new("API_KEY".to_string(), None)
+///     .with_media_retry(RetryPolicy {
+///         attempts: 8,
+///         timeout: Some(Duration::from_secs(30)),
+///         ..RetryPolicy::media()
+///     })
+///     .with_api_retry(RetryPolicy::none());
+/// assert_eq!(client.media_retry().attempts, 8);
+/// assert_eq!(client.api_retry().attempts, 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of attempts in all, the first one included
+    pub attempts: u32,
+    /// Time waited before the first retry, doubled after each
+    pub delay: Duration,
+    /// Time allowed for each attempt, `None` to use the client's
+    /// [download timeout](crate::FreesoundClientBuilder::download_timeout)
+    pub timeout: Option<Duration>,
+}
+
+impl RetryPolicy {
+    /// Create a policy making up to `attempts` attempts, waiting one second before the first retry
+    pub fn new(attempts: u32) -> Self {
+        Self {
+            attempts: attempts.max(1),
+            delay: Duration::from_secs(1),
+            timeout: None,
+        }
+    }
+
+    /// A policy making a single attempt
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// The default policy of media hosts: [`DEFAULT_MEDIA_ATTEMPTS`], half a second apart at first
+    pub fn media() -> Self {
+        Self {
+            delay: Duration::from_millis(500),
+            ..Self::new(DEFAULT_MEDIA_ATTEMPTS)
+        }
+    }
+
+    /// The default policy of the API: [`DEFAULT_API_ATTEMPTS`], a second apart at first
+    pub fn api() -> Self {
+        Self::new(DEFAULT_API_ATTEMPTS)
+    }
+}
+
+impl FreesoundClient {
+    /// Sends a request, trying again on transient failures if it is idempotent
+    ///
+    /// Server errors, 429 responses, timeouts and connection failures are tried
+    /// again as `policy` says. Requests that are not idempotent, such as the
+    /// POSTs of writes and uploads, are only sent once: a write that timed out
+    /// may still have reached Freesound.
+    pub(crate) async fn send_with_retries(
+        &self,
+        request: reqwest::RequestBuilder,
+        policy: RetryPolicy,
+    ) -> Result<reqwest::Response> {
+        let retryable = request
+            .try_clone()
+            .and_then(|request| request.build().ok())
+            .is_some_and(|request| request.method().is_idempotent());
+        if !retryable {
+            return self.send(request).await;
+        }
+
+        let mut delay = policy.delay;
+        let mut attempt = 1;
+        loop {
+            let retry = request.try_clone().expect("the request was cloned before");
+            let result = self.send(retry).await;
+            let transient = match &result {
+                Ok(response) => {
+                    response.status().is_server_error()
+                        || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS
+                }
+                Err(e) => match e.root() {
+                    FreesoundError::RequestError(e) => e.is_timeout() || e.is_connect(),
+                    _ => false,
+                },
+            };
+            if transient && attempt < policy.attempts {
+                self.sleeper().sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+                continue;
+            }
+            return result;
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use crate::models::Sound;
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+
+/// Tags kept in sanitized descriptions, the markup Freesound renders
+const ALLOWED_TAGS: [&str; 13] = [
+    "a",
+    "b",
+    "blockquote",
+    "br",
+    "code",
+    "em",
+    "i",
+    "li",
+    "ol",
+    "p",
+    "strong",
+    "u",
+    "ul",
+];
+
+static SANITIZER: LazyLock<ammonia::Builder<'static>> = LazyLock::new(|| {
+    let mut builder = ammonia::Builder::default();
+    builder
+        .tags(HashSet::from(ALLOWED_TAGS))
+        .generic_attributes(HashSet::new())
+        .tag_attributes(HashMap::from([("a", HashSet::from(["href"]))]))
+        .url_schemes(HashSet::from(["http", "https", "mailto"]))
+        .url_relative(ammonia::UrlRelative::Deny)
+        .link_rel(Some("noopener noreferrer nofollow"));
+    builder
+});
+
+impl Sound {
+    /// Returns the description as sanitized HTML, safe to render
+    ///
+    /// Needs the `rich-text` feature. Only basic formatting, lists, quotes and
+    /// absolute links are kept, links get `rel="noopener noreferrer nofollow"`,
+    /// BBCode tags (`[b]`, `[i]`, `[u]`, `[url]`) are turned into HTML and line
+    /// breaks into `<br>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::Sound;
+    ///
+    /// let sound = Sound {
+    ///     description: r#"<b>Rain</b><script>alert(1)</script> <a href="javascript:x">here</a>"#.to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(sound.description_html(), "<b>Rain</b> <a rel=\"noopener noreferrer nofollow\">here</a>");
+    /// ```
+    pub fn description_html(&self) -> String {
+        sanitize(&self.description)
+    }
+
+    /// Returns the description as plain text
+    ///
+    /// Needs the `rich-text` feature. Markup is removed, paragraphs are
+    /// separated by a blank line, list items start with `- ` and links are
+    /// followed by their URL when it differs from their text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::Sound;
+    ///
+    /// let sound = Sound {
+    ///     description: "Recorded with a <b>Zoom H5</b>.\r\n[url=https://example.org]My site[/url]\r\n<ul><li>48 kHz</li><li>24 bit</li></ul>".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     sound.description_plaintext(),
+    ///     "Recorded with a Zoom H5.\nMy site (https://example.org)\n\n- 48 kHz\n- 24 bit"
+    /// );
+    /// ```
+    pub fn description_plaintext(&self) -> String {
+        convert(&sanitize(&self.description), false)
+    }
+
+    /// Returns the description as Markdown
+    ///
+    /// Needs the `rich-text` feature. The markup kept by
+    /// [`description_html`](Self::description_html) is turned into its Markdown
+    /// equivalent, underlines excepted, and the text is escaped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use freesound_rs::Sound;
+    ///
+    /// let sound = Sound {
+    ///     description: "<p>Field recording, <em>not</em> edited.</p><p>See <a href=\"https://freesound.org\">Freesound</a> for *more*</p>".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     sound.description_markdown(),
+    ///     "Field recording, _not_ edited.\n\nSee [Freesound](https://freesound.org) for \\*more\\*"
+    /// );
+    /// ```
+    pub fn description_markdown(&self) -> String {
+        convert(&sanitize(&self.description), true)
+    }
+}
+
+/// Turns BBCode and line breaks into HTML, then keeps only the allowed markup
+fn sanitize(description: &str) -> String {
+    let html = bbcode_to_html(description)
+        .replace("\r\n", "\n")
+        .replace('\n', "<br>");
+    SANITIZER.clean(&html).to_string()
+}
+
+/// Replaces the BBCode tags of a description by their HTML equivalent
+fn bbcode_to_html(text: &str) -> String {
+    let mut html = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        html.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(']') else {
+            break;
+        };
+        let tag = &rest[1..end];
+        let after = &rest[end + 1..];
+        let lower = tag.to_ascii_lowercase();
+        match lower.as_str() {
+            "b" | "i" | "u" | "/b" | "/i" | "/u" | "/url" => {
+                html.push_str(&format!("<{lower}>").replace("/url", "/a"));
+            }
+            "url" => {
+                // The link is the text itself: [url]https://...[/url]
+                let close = after.to_ascii_lowercase().find("[/url]");
+                let link = &after[..close.unwrap_or(after.len())];
+                html.push_str(&format!("<a href=\"{}\">", attribute(link)));
+            }
+            _ if lower.starts_with("url=") => {
+                let link = tag[4..].trim_matches(['"', '\'']);
+                html.push_str(&format!("<a href=\"{}\">", attribute(link)));
+            }
+            _ => html.push_str(&rest[..=end]),
+        }
+        rest = after;
+    }
+    html.push_str(rest);
+    html
+}
+
+fn attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
+
+/// Reverses the escaping of the sanitizer output
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Break {
+    None,
+    Line,
+    /// Before a list item, which needs no hard break in Markdown
+    Item,
+    Paragraph,
+}
+
+/// Writes text, holding line breaks until more text comes
+struct Writer {
+    markdown: bool,
+    out: String,
+    pending: Break,
+    quotes: usize,
+    /// Quote depth of the text written last
+    written_quotes: usize,
+}
+
+impl Writer {
+    fn text(&mut self, text: &str) {
+        let text = match self.pending != Break::None || self.out.is_empty() {
+            true => text.trim_start(),
+            false => text,
+        };
+        if text.is_empty() {
+            return;
+        }
+        if self.pending != Break::None {
+            self.out.truncate(self.out.trim_end_matches(' ').len());
+        }
+        if !self.out.is_empty() {
+            let prefix = "> ".repeat(self.quotes);
+            match self.pending {
+                Break::None => {}
+                Break::Line if self.markdown => self.out.push_str(&format!("  \n{prefix}")),
+                Break::Item => self.out.push_str(&format!("\n{prefix}")),
+                Break::Line => self.out.push('\n'),
+                Break::Paragraph if self.markdown => {
+                    let blank = "> ".repeat(self.quotes.min(self.written_quotes));
+                    self.out
+                        .push_str(&format!("\n{}\n{prefix}", blank.trim_end()))
+                }
+                Break::Paragraph => self.out.push_str("\n\n"),
+            }
+        } else if self.markdown {
+            self.out.push_str(&"> ".repeat(self.quotes));
+        }
+        self.pending = Break::None;
+        self.written_quotes = self.quotes;
+        self.out.push_str(text);
+    }
+
+    fn line_break(&mut self, at_least: Break) {
+        self.pending = self.pending.max(at_least);
+    }
+}
+
+/// Converts sanitized HTML to plain text or Markdown
+fn convert(html: &str, markdown: bool) -> String {
+    let mut writer = Writer {
+        markdown,
+        out: String::new(),
+        pending: Break::None,
+        quotes: 0,
+        written_quotes: 0,
+    };
+    // Item counters of the open lists, `None` for unordered ones
+    let mut lists: Vec<Option<usize>> = Vec::new();
+    let mut links: Vec<(String, usize)> = Vec::new();
+
+    let mut rest = html;
+    while !rest.is_empty() {
+        let Some(start) = rest.find('<') else {
+            write_text(&mut writer, rest);
+            break;
+        };
+        write_text(&mut writer, &rest[..start]);
+        let end = tag_end(&rest[start..]).map_or(rest.len(), |end| start + end);
+        let tag = &rest[start + 1..end.saturating_sub(1).max(start + 1)];
+        rest = &rest[end..];
+
+        let closing = tag.starts_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .split([' ', '/'])
+            .next()
+            .unwrap_or_default();
+        match (name, closing) {
+            // Two line breaks in a row end a paragraph
+            ("br", _) if writer.pending == Break::Line => writer.line_break(Break::Paragraph),
+            ("br", _) => writer.line_break(Break::Line),
+            ("p", _) => writer.line_break(Break::Paragraph),
+            ("blockquote", false) => {
+                writer.line_break(Break::Paragraph);
+                writer.quotes += 1;
+            }
+            ("blockquote", true) => {
+                writer.line_break(Break::Paragraph);
+                writer.quotes = writer.quotes.saturating_sub(1);
+            }
+            ("ul", false) => {
+                writer.line_break(Break::Paragraph);
+                lists.push(None);
+            }
+            ("ol", false) => {
+                writer.line_break(Break::Paragraph);
+                lists.push(Some(0));
+            }
+            ("ul" | "ol", true) => {
+                lists.pop();
+                writer.line_break(Break::Paragraph);
+            }
+            ("li", false) => {
+                writer.line_break(Break::Item);
+                let marker = match lists.last_mut() {
+                    Some(Some(n)) if markdown => {
+                        *n += 1;
+                        format!("{n}. ")
+                    }
+                    _ => "- ".to_string(),
+                };
+                writer.text(&marker);
+            }
+            ("b" | "strong", _) if markdown => writer.text("**"),
+            ("i" | "em", _) if markdown => writer.text("_"),
+            ("code", _) if markdown => writer.text("`"),
+            ("a", false) => {
+                let href = href(tag).unwrap_or_default();
+                if markdown && !href.is_empty() {
+                    writer.text("[");
+                }
+                links.push((href, writer.out.len()));
+            }
+            ("a", true) => {
+                let Some((href, start)) = links.pop() else {
+                    continue;
+                };
+                if href.is_empty() {
+                    continue;
+                }
+                if markdown {
+                    match href.contains([' ', '(', ')']) {
+                        true => writer.text(&format!("](<{href}>)")),
+                        false => writer.text(&format!("]({href})")),
+                    }
+                } else {
+                    let text = writer.out[start..].trim();
+                    if text.trim_end_matches('/') != href.trim_end_matches('/') {
+                        writer.text(&format!(" ({href})"));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    writer.out
+}
+
+fn write_text(writer: &mut Writer, text: &str) {
+    let text = unescape(text);
+    match writer.markdown {
+        true => writer.text(&escape_markdown(&text)),
+        false => writer.text(&text),
+    }
+}
+
+/// Returns the length of the tag starting `html`, quoted attributes included
+fn tag_end(html: &str) -> Option<usize> {
+    let mut quoted = false;
+    for (i, c) in html.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '>' if !quoted => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Returns the `href` attribute of a sanitized `a` tag
+fn href(tag: &str) -> Option<String> {
+    let start = tag.find("href=\"")? + "href=\"".len();
+    let end = tag[start..].find('"')?;
+    Some(unescape(&tag[start..start + end]))
+}
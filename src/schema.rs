@@ -0,0 +1,207 @@
+use crate::client::FreesoundClient;
+use crate::strictness::Strictness;
+use serde::Serialize;
+use std::fmt;
+use std::sync::Mutex;
+
+/// What went wrong when reading a response, see [`SchemaIssue`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchemaIssueKind {
+    /// A field of the response is not part of the models
+    UnknownField,
+    /// An optional structure did not match its model and was read as `None`
+    /// (or failed the request, see [`Strictness`](crate::Strictness))
+    FailedStructure {
+        /// The deserialization error, for the last occurrence
+        error: String,
+    },
+}
+
+/// A mismatch between a response and the models, see [`SchemaReport`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaIssue {
+    /// The endpoint, such as `sounds/{id}`
+    pub endpoint: String,
+    /// Where the mismatch is, from the model that was read, e.g. `Sound.new_field`
+    ///
+    /// Items of arrays are noted `[]`.
+    pub path: String,
+    /// What went wrong
+    pub kind: SchemaIssueKind,
+    /// Number of times it happened
+    pub occurrences: u64,
+}
+
+/// Mismatches between the responses of Freesound and the models of the crate
+///
+/// Returned by [`FreesoundClient::schema_report`]. It tells which fields
+/// Freesound added and which structures changed shape, precisely enough to
+/// update the models or file an issue.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SchemaReport {
+    /// Every mismatch met, by endpoint and path
+    pub issues: Vec<SchemaIssue>,
+}
+
+impl SchemaReport {
+    /// Returns true if every response matched the models
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns the fields of the responses that are not part of the models
+    pub fn unknown_fields(&self) -> impl Iterator<Item = &SchemaIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.kind == SchemaIssueKind::UnknownField)
+    }
+
+    /// Returns the structures that did not match their model
+    pub fn failed_structures(&self) -> impl Iterator<Item = &SchemaIssue> {
+        self.issues
+            .iter()
+            .filter(|issue| matches!(issue.kind, SchemaIssueKind::FailedStructure { .. }))
+    }
+}
+
+impl fmt::Display for SchemaReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("the responses matched the models");
+        }
+        for (i, issue) in self.issues.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}: {} ", issue.endpoint, issue.path)?;
+            match &issue.kind {
+                SchemaIssueKind::UnknownField => write!(f, "is unknown")?,
+                SchemaIssueKind::FailedStructure { error } => write!(f, "failed: {error}")?,
+            }
+            write!(f, " ({}x)", issue.occurrences)?;
+        }
+        Ok(())
+    }
+}
+
+/// Mismatches recorded by a client and its clones
+#[derive(Debug, Default)]
+pub(crate) struct SchemaRecorder {
+    issues: Mutex<Vec<SchemaIssue>>,
+}
+
+impl SchemaRecorder {
+    /// Deserializes a response from `endpoint` with `strictness`, recording its mismatches
+    pub(crate) fn decode<T: serde::de::DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        strictness: Strictness,
+        mut value: serde_json::Value,
+    ) -> serde_json::Result<T> {
+        let root = type_name::<T>();
+        let mut found = Vec::new();
+        strictness.relax(&mut value, &mut |path, e| {
+            found.push((
+                format!("{root}{path}"),
+                SchemaIssueKind::FailedStructure {
+                    error: e.to_string(),
+                },
+            ));
+        });
+        let result = serde_ignored::deserialize(value, |path| {
+            found.push((
+                format!("{root}{}", path_suffix(&path)),
+                SchemaIssueKind::UnknownField,
+            ));
+        });
+
+        let mut issues = self.issues.lock().unwrap();
+        for (path, kind) in found {
+            let unknown = kind == SchemaIssueKind::UnknownField;
+            let known = issues.iter_mut().find(|issue| {
+                issue.endpoint == endpoint
+                    && issue.path == path
+                    && (issue.kind == SchemaIssueKind::UnknownField) == unknown
+            });
+            match known {
+                Some(issue) => {
+                    issue.kind = kind;
+                    issue.occurrences += 1;
+                }
+                None => issues.push(SchemaIssue {
+                    endpoint: endpoint.to_string(),
+                    path,
+                    kind,
+                    occurrences: 1,
+                }),
+            }
+        }
+        result
+    }
+
+    fn report(&self) -> SchemaReport {
+        let mut issues = self.issues.lock().unwrap().clone();
+        issues.sort_by(|a, b| (&a.endpoint, &a.path).cmp(&(&b.endpoint, &b.path)));
+        SchemaReport { issues }
+    }
+}
+
+fn path_suffix(path: &serde_ignored::Path) -> String {
+    use serde_ignored::Path;
+    match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, .. } => format!("{}[]", path_suffix(parent)),
+        Path::Map { parent, key } => format!("{}.{key}", path_suffix(parent)),
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => path_suffix(parent),
+    }
+}
+
+/// Returns the name of a type without its module paths, e.g. `Page<Sound>`
+fn type_name<T>() -> String {
+    let mut name = String::new();
+    let mut segment = String::new();
+    for c in std::any::type_name::<T>().chars() {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            segment.push(c);
+        } else {
+            name.push_str(segment.rsplit("::").next().unwrap_or_default());
+            segment.clear();
+            name.push(c);
+        }
+    }
+    name.push_str(segment.rsplit("::").next().unwrap_or_default());
+    name
+}
+
+impl FreesoundClient {
+    /// Returns the mismatches between the responses received and the models
+    ///
+    /// Needs the `schema-report` feature. Every response decoded by the client
+    /// and its clones is checked for fields the models do not know and for
+    /// optional structures that failed to deserialize.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FreesoundClient, SearchQueryBuilder};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// client.search(&SearchQueryBuilder::new().query("rain").build()).await?;
+    /// let report = client.schema_report();
+    /// if !report.is_empty() {
+    ///     eprintln!("{report}");
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn schema_report(&self) -> SchemaReport {
+        self.schema().report()
+    }
+}
@@ -0,0 +1,180 @@
+use crate::models::Sound;
+
+/// Ranks sounds by their distance to a target over a set of descriptors
+///
+/// Descriptors are looked up in the analysis data of each sound (see
+/// [`Sound::descriptor`]) and can be numbers or arrays of numbers such as MFCC
+/// means. Every dimension is scaled by its standard deviation over the target
+/// and the candidates, so descriptors with large values (spectral centroid in
+/// Hz) do not drown out small ones (BPM). Each descriptor then weighs the same,
+/// whatever its number of dimensions.
+///
+/// The sounds must have been fetched with the descriptors requested, e.g.
+/// `descriptors(["lowlevel.mfcc.mean", "rhythm.bpm"])` on the query builder.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{DescriptorDistance, Sound};
+/// use serde_json::json;
+///
+/// let sound = |id, bpm: f64, centroid: f64| Sound {
+///     id,
+///     analysis: Some(json!({
+///         "rhythm": { "bpm": bpm },
+///         "lowlevel": { "spectral_centroid": { "mean": centroid } }
+///     })),
+///     ..Default::default()
+/// };
+///
+/// let target = sound(0, 120.0, 1500.0);
+/// let candidates = vec![sound(1, 90.0, 4000.0), sound(2, 121.0, 1600.0), Sound::default()];
+///
+/// let ranked = DescriptorDistance::new(["rhythm.bpm", "lowlevel.spectral_centroid.mean"])
+///     .rank(&target, candidates);
+/// let ids: Vec<_> = ranked.iter().map(|(s, _)| s.id).collect();
+/// assert_eq!(ids, [2, 1, 0]);
+/// assert!(ranked[2].1.is_none()); // no analysis data
+/// ```
+#[derive(Debug, Clone)]
+pub struct DescriptorDistance {
+    descriptors: Vec<String>,
+}
+
+impl DescriptorDistance {
+    /// Create a distance over the given descriptor names
+    pub fn new<I, S>(descriptors: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            descriptors: descriptors.into_iter().map(|s| s.into()).collect(),
+        }
+    }
+
+    /// Returns the descriptor names used
+    pub fn descriptors(&self) -> &[String] {
+        &self.descriptors
+    }
+
+    /// Compute the distance of each candidate to the target
+    ///
+    /// Returns `None` for candidates missing a descriptor, or for all of them
+    /// if the target misses one; an empty array counts as missing. Candidates whose analysis is not in the same
+    /// space as the target, raw or [normalized](Sound::analysis_normalized),
+    /// get `None` too, like those whose arrays differ in length from the
+    /// target's.
+    ///
+    /// ```
+    /// use freesound_rs::{DescriptorDistance, Sound};
+    /// use serde_json::json;
+    ///
+    /// let sound = |mfcc: serde_json::Value| Sound {
+    ///     analysis: Some(json!({ "lowlevel": { "mfcc": { "mean": mfcc } } })),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let distance = DescriptorDistance::new(["lowlevel.mfcc.mean"]);
+    /// let target = sound(json!([1.0, 2.0]));
+    /// let candidates = [sound(json!([1.0, 3.0])), sound(json!([1.0])), sound(json!([]))];
+    /// let distances = distance.distances(&target, &candidates);
+    /// assert!(distances[0].is_some());
+    /// assert_eq!(distances[1..], [None, None]);
+    /// assert_eq!(distance.distances(&sound(json!([])), &candidates), [None; 3]);
+    /// ```
+    pub fn distances(&self, target: &Sound, candidates: &[Sound]) -> Vec<Option<f32>> {
+        let normalized = target.analysis_normalized;
+        let Some(target) = self.features(target) else {
+            return vec![None; candidates.len()];
+        };
+        let features: Vec<_> = candidates
+            .iter()
+            .map(|s| {
+                self.features(s)
+                    .filter(|f| s.analysis_normalized == normalized && same_shape(f, &target))
+            })
+            .collect();
+
+        let scales = self.scales(&target, features.iter().flatten());
+
+        features
+            .iter()
+            .map(|f| {
+                let f = f.as_ref()?;
+                let sum: f64 = f
+                    .iter()
+                    .zip(&target)
+                    .zip(&scales)
+                    .map(|((values, target), scales)| {
+                        let d: f64 = values
+                            .iter()
+                            .zip(target)
+                            .zip(scales)
+                            .map(|((v, t), s)| ((v - t) / s).powi(2))
+                            .sum();
+                        d / values.len() as f64
+                    })
+                    .sum();
+                Some(sum.sqrt() as f32)
+            })
+            .collect()
+    }
+
+    /// Sort candidates by increasing distance to the target
+    ///
+    /// Candidates without a distance are put last, in their original order.
+    pub fn rank(&self, target: &Sound, candidates: Vec<Sound>) -> Vec<(Sound, Option<f32>)> {
+        let distances = self.distances(target, &candidates);
+        let mut ranked: Vec<_> = candidates.into_iter().zip(distances).collect();
+        ranked.sort_by(|(_, a), (_, b)| match (a, b) {
+            (Some(a), Some(b)) => a.total_cmp(b),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => std::cmp::Ordering::Equal,
+        });
+        ranked
+    }
+
+    /// Extracts the values of every descriptor, if all are present and not empty
+    fn features(&self, sound: &Sound) -> Option<Vec<Vec<f64>>> {
+        self.descriptors
+            .iter()
+            .map(|name| match sound.analysis_value(name)? {
+                serde_json::Value::Array(values) if values.is_empty() => None,
+                serde_json::Value::Array(values) => values.iter().map(|v| v.as_f64()).collect(),
+                value => Some(vec![value.as_f64()?]),
+            })
+            .collect()
+    }
+
+    /// Standard deviation of every dimension, 1 where it is zero
+    fn scales<'a>(
+        &self,
+        target: &[Vec<f64>],
+        others: impl Iterator<Item = &'a Vec<Vec<f64>>>,
+    ) -> Vec<Vec<f64>> {
+        let all: Vec<&[Vec<f64>]> = std::iter::once(target)
+            .chain(others.map(|f| f.as_slice()))
+            .collect();
+        let n = all.len() as f64;
+
+        target
+            .iter()
+            .enumerate()
+            .map(|(d, values)| {
+                (0..values.len())
+                    .map(|i| {
+                        let mean = all.iter().map(|f| f[d][i]).sum::<f64>() / n;
+                        let var = all.iter().map(|f| (f[d][i] - mean).powi(2)).sum::<f64>() / n;
+                        if var > 0.0 { var.sqrt() } else { 1.0 }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn same_shape(a: &[Vec<f64>], b: &[Vec<f64>]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.len() == b.len())
+}
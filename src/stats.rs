@@ -0,0 +1,215 @@
+use crate::models::{Page, Sound};
+use crate::tags;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Upper bounds, in seconds, of the duration histogram buckets
+///
+/// A last bucket holds the sounds longer than the last bound.
+pub const DURATION_BUCKETS: [f32; 7] = [1.0, 2.0, 5.0, 10.0, 30.0, 60.0, 300.0];
+
+/// Number of sounds whose duration falls in a range
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DurationBucket {
+    /// Lower bound, in seconds, included
+    pub min: f32,
+    /// Upper bound, in seconds, excluded; `None` for the last bucket
+    pub max: Option<f32>,
+    /// Number of sounds in the range
+    pub count: usize,
+}
+
+/// Aggregate statistics over a set of sounds
+///
+/// Built from a page of results with [`Page::stats`], or accumulated with
+/// [`add`](Self::add) while going through a stream of results. Statistics about
+/// a field are only meaningful if the sounds were fetched with it (`duration`,
+/// `license`, `tags`, `avg_rating` and `num_ratings`).
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{SearchStats, Sound};
+///
+/// let sound = |duration: f32, license: &str, tags: &[&str]| Sound {
+///     duration,
+///     license: license.to_string(),
+///     tags: tags.iter().map(|t| t.to_string()).collect(),
+///     ..Default::default()
+/// };
+/// let sounds = [
+///     sound(0.5, "Creative Commons 0", &["kick", "drum"]),
+///     sound(1.5, "Attribution", &["snare", "drum"]),
+///     sound(12.0, "Creative Commons 0", &["loop", "drum"]),
+/// ];
+///
+/// let stats: SearchStats = sounds.iter().collect();
+/// assert_eq!(stats.sounds, 3);
+/// assert_eq!(stats.licenses["Creative Commons 0"], 2);
+/// assert_eq!(stats.duration_histogram[0].count, 1);
+/// assert_eq!(stats.top_tags(1), [("drum".to_string(), 3)]);
+/// assert_eq!(stats.average_rating, None);
+/// ```
+///
+/// Over all the pages of a search:
+///
+/// ```no_run
+/// use std::env;
+/// use futures::TryStreamExt;
+/// use freesound_rs::{FreesoundClient, SearchQueryBuilder, SearchStats};
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     dotenvy::dotenv().ok();
+///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+///     let client = FreesoundClient::new(api_key, None);
+/// let query = SearchQueryBuilder::new()
+///     .query("thunder")
+///     .fields(["id", "duration", "license", "tags", "avg_rating", "num_ratings"])
+///     .page_size(150)
+///     .build();
+/// let stats = client
+///     .search_stream(&query)
+///     .try_fold(SearchStats::default(), |mut stats, sound| async move {
+///         stats.add(&sound);
+///         Ok(stats)
+///     })
+///     .await?;
+/// println!("{:#?}", stats.top_tags(10));
+///      Ok(())
+///  }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SearchStats {
+    /// Number of sounds
+    pub sounds: usize,
+    /// Sum of the durations, in seconds
+    pub total_duration: f64,
+    /// Number of sounds per duration range (see [`DURATION_BUCKETS`])
+    pub duration_histogram: Vec<DurationBucket>,
+    /// Number of sounds per license
+    pub licenses: BTreeMap<String, usize>,
+    /// Number of sounds per normalized tag
+    pub tags: BTreeMap<String, usize>,
+    /// Number of sounds rated at least once
+    pub rated_sounds: usize,
+    /// Mean of the average ratings of the rated sounds
+    pub average_rating: Option<f64>,
+}
+
+impl Default for SearchStats {
+    fn default() -> Self {
+        let mut min = 0.0;
+        let mut duration_histogram = Vec::new();
+        for max in DURATION_BUCKETS.into_iter().map(Some).chain([None]) {
+            duration_histogram.push(DurationBucket { min, max, count: 0 });
+            min = max.unwrap_or_default();
+        }
+
+        Self {
+            sounds: 0,
+            total_duration: 0.0,
+            duration_histogram,
+            licenses: BTreeMap::new(),
+            tags: BTreeMap::new(),
+            rated_sounds: 0,
+            average_rating: None,
+        }
+    }
+}
+
+impl SearchStats {
+    /// Accounts for one more sound
+    pub fn add(&mut self, sound: &Sound) {
+        self.sounds += 1;
+        self.total_duration += f64::from(sound.duration);
+
+        if let Some(bucket) = self
+            .duration_histogram
+            .iter_mut()
+            .find(|bucket| bucket.max.is_none_or(|max| sound.duration < max))
+        {
+            bucket.count += 1;
+        }
+
+        if !sound.license.is_empty() {
+            *self.licenses.entry(sound.license.clone()).or_default() += 1;
+        }
+        for tag in tags::normalize(&sound.tags) {
+            *self.tags.entry(tag).or_default() += 1;
+        }
+
+        if sound.num_ratings > 0 {
+            self.rated_sounds += 1;
+            let mean = self.average_rating.unwrap_or_default();
+            let rating = f64::from(sound.avg_rating);
+            self.average_rating = Some(mean + (rating - mean) / self.rated_sounds as f64);
+        }
+    }
+
+    /// Returns the mean duration, in seconds, if there are sounds
+    pub fn mean_duration(&self) -> Option<f64> {
+        (self.sounds > 0).then(|| self.total_duration / self.sounds as f64)
+    }
+
+    /// Returns the most frequent tags, ties broken alphabetically
+    pub fn top_tags(&self, limit: usize) -> Vec<(String, usize)> {
+        let mut tags: Vec<_> = self.tags.iter().map(|(t, n)| (t.clone(), *n)).collect();
+        tags.sort_by(|(a, m), (b, n)| n.cmp(m).then_with(|| a.cmp(b)));
+        tags.truncate(limit);
+        tags
+    }
+
+    /// Merges the statistics of another set of sounds
+    pub fn merge(&mut self, other: &SearchStats) {
+        let rated = self.rated_sounds + other.rated_sounds;
+        self.average_rating = match (self.average_rating, other.average_rating) {
+            (Some(a), Some(b)) => {
+                Some((a * self.rated_sounds as f64 + b * other.rated_sounds as f64) / rated as f64)
+            }
+            (a, b) => a.or(b),
+        };
+        self.rated_sounds = rated;
+        self.sounds += other.sounds;
+        self.total_duration += other.total_duration;
+        for (bucket, other) in self
+            .duration_histogram
+            .iter_mut()
+            .zip(&other.duration_histogram)
+        {
+            bucket.count += other.count;
+        }
+        merge_counts(&mut self.licenses, &other.licenses);
+        merge_counts(&mut self.tags, &other.tags);
+    }
+}
+
+fn merge_counts(counts: &mut BTreeMap<String, usize>, other: &BTreeMap<String, usize>) {
+    for (key, count) in other {
+        *counts.entry(key.clone()).or_default() += count;
+    }
+}
+
+impl<'a> Extend<&'a Sound> for SearchStats {
+    fn extend<I: IntoIterator<Item = &'a Sound>>(&mut self, sounds: I) {
+        for sound in sounds {
+            self.add(sound);
+        }
+    }
+}
+
+impl<'a> FromIterator<&'a Sound> for SearchStats {
+    fn from_iter<I: IntoIterator<Item = &'a Sound>>(sounds: I) -> Self {
+        let mut stats = Self::default();
+        stats.extend(sounds);
+        stats
+    }
+}
+
+impl Page<Sound> {
+    /// Computes statistics over the sounds of this page
+    ///
+    /// See [`SearchStats`], which can also be accumulated over all the pages.
+    pub fn stats(&self) -> SearchStats {
+        self.results.iter().collect()
+    }
+}
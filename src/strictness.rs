@@ -0,0 +1,148 @@
+use crate::models::{AcAnalysis, Avatar, Images, Previews};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::str::FromStr;
+
+/// How tolerant the client is of responses not matching the models
+///
+/// Optional structures of a response, such as the `previews`, `images` or
+/// `ac_analysis` of a sound or the `avatar` of a user, may come back in a shape
+/// the models do not expect when Freesound changes its API. A lenient client
+/// reads them as `None` and keeps the rest of the response, which suits
+/// production; a strict client fails with the deserialization error, which
+/// suits a CI job watching for schema changes. Values the API may extend, such
+/// as the musical key or the processing state of an upload, are kept as text,
+/// so a new variant never fails a response.
+///
+/// Clients are lenient unless told otherwise. Models deserialized outside of
+/// a client, e.g. with `serde_json::from_str`, are read strictly; use
+/// [`decode`](Self::decode) to read them leniently.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{FreesoundClient, Sound, Strictness};
+///
+/// let client = FreesoundClient::new("API_KEY".to_string(), None)
+///     .with_strictness(Strictness::Strict);
+/// assert_eq!(client.strictness(), Strictness::Strict);
+/// assert_eq!("lenient".parse(), Ok(Strictness::Lenient));
+///
+/// // Outside of a client, a malformed structure fails unless read leniently
+/// let json = r#"{"id": 1, "previews": {"preview-hq-mp3": 42}}"#;
+/// assert!(serde_json::from_str::<Sound>(json).is_err());
+/// let sound: Sound = Strictness::Lenient.decode(serde_json::from_str(json)?)?;
+/// assert!(sound.previews.is_none());
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Strictness {
+    /// Read malformed optional structures as `None`
+    #[default]
+    Lenient,
+    /// Fail on malformed optional structures
+    Strict,
+}
+
+/// Checks that a JSON value matches a model
+type Check = fn(&Value) -> serde_json::Result<()>;
+
+/// The optional structures a lenient client drops when malformed, by field
+const OPTIONAL_STRUCTURES: [(&str, Check); 4] = [
+    ("previews", check::<Previews>),
+    ("images", check::<Images>),
+    ("ac_analysis", check::<AcAnalysis>),
+    ("avatar", check::<Avatar>),
+];
+
+impl Strictness {
+    /// Deserializes a model from a JSON response with this strictness
+    pub fn decode<T: DeserializeOwned>(self, mut value: Value) -> serde_json::Result<T> {
+        if self == Self::Lenient {
+            self.relax(&mut value, &mut |_, _| {});
+        }
+        serde_json::from_value(value)
+    }
+
+    /// Checks the optional structures of `value`, replacing the malformed ones
+    /// by `null` if lenient
+    ///
+    /// `failed` is called with the path of each malformed structure, such as
+    /// `.results[].previews`, and its error.
+    pub(crate) fn relax(
+        self,
+        value: &mut Value,
+        failed: &mut dyn FnMut(String, serde_json::Error),
+    ) {
+        self.relax_at(value, &mut String::new(), failed);
+    }
+
+    fn relax_at(
+        self,
+        value: &mut Value,
+        path: &mut String,
+        failed: &mut dyn FnMut(String, serde_json::Error),
+    ) {
+        let len = path.len();
+        match value {
+            Value::Object(object) => {
+                for (key, value) in object.iter_mut() {
+                    path.push('.');
+                    path.push_str(key);
+                    let structure = OPTIONAL_STRUCTURES
+                        .iter()
+                        .find(|(field, _)| field == key)
+                        .filter(|_| !value.is_null());
+                    match structure {
+                        Some((_, check)) => {
+                            if let Err(e) = check(value) {
+                                failed(path.clone(), e);
+                                if self == Self::Lenient {
+                                    *value = Value::Null;
+                                }
+                            }
+                        }
+                        None => self.relax_at(value, path, failed),
+                    }
+                    path.truncate(len);
+                }
+            }
+            Value::Array(items) => {
+                path.push_str("[]");
+                for item in items {
+                    self.relax_at(item, path, failed);
+                }
+                path.truncate(len);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn check<T: DeserializeOwned>(value: &Value) -> serde_json::Result<()> {
+    T::deserialize(value).map(drop)
+}
+
+impl fmt::Display for Strictness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Lenient => "lenient",
+            Self::Strict => "strict",
+        })
+    }
+}
+
+impl FromStr for Strictness {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "lenient" => Ok(Self::Lenient),
+            "strict" => Ok(Self::Strict),
+            other => Err(format!("unknown strictness: {other}")),
+        }
+    }
+}
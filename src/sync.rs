@@ -0,0 +1,365 @@
+use crate::client::FreesoundClient;
+use crate::download::{
+    DownloadJob, DownloadManager, DownloadSource, DownloadStatus, ProgressSink, sanitize_file_name,
+};
+use crate::error::Result;
+use crate::models::SearchQueryBuilder;
+use crate::write::BookmarkRequest;
+use futures::stream::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+/// Name of the report written at the root of a synchronised directory
+pub const SYNC_REPORT_FILE: &str = "sync-report.json";
+
+/// Name of the file listing the files a synchronisation manages, at the root
+///
+/// Only the files listed there, relative to the root, are ever removed.
+pub const SYNC_MANIFEST_FILE: &str = ".sync-manifest.json";
+
+/// A download that failed during a synchronisation
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SyncFailure {
+    /// The identifier of the sound
+    pub sound_id: i32,
+    /// Where the file should have been written
+    pub destination: PathBuf,
+    /// Why the download failed
+    pub error: String,
+}
+
+/// Outcome of a bookmark synchronisation
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SyncReport {
+    /// Names of the directories of the bookmark categories, relative to the root
+    pub categories: Vec<String>,
+    /// Files downloaded by this run
+    pub downloaded: Vec<PathBuf>,
+    /// Number of bookmarked sounds already on disk
+    pub up_to_date: usize,
+    /// Downloads that failed
+    pub failed: Vec<SyncFailure>,
+    /// Files written by a synchronisation that no longer match a bookmark, kept on disk
+    pub orphans: Vec<PathBuf>,
+    /// Files written by a synchronisation that no longer match a bookmark, removed from disk
+    pub removed: Vec<PathBuf>,
+    /// Sounds whose files were added locally, bookmarked by this run
+    #[serde(default)]
+    pub pushed: Vec<i32>,
+}
+
+/// The files managed by a synchronisation, relative to its root
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct SyncManifest {
+    files: BTreeSet<PathBuf>,
+}
+
+/// Mirrors the bookmarks of the authenticated user into a local directory
+///
+/// Each bookmark category gets its own sub-directory, named after the category.
+/// Sounds missing on disk are downloaded, and the files written are listed in
+/// [`SYNC_MANIFEST_FILE`]. Those that no longer match a bookmark are reported
+/// as orphans, and removed if asked to; files the synchronisation did not
+/// write are never removed. An account listing no bookmark at all removes
+/// nothing either, unless [`allow_empty`](Self::allow_empty) is set, so that
+/// an emptied or misread account does not wipe the directory. A
+/// [`SyncReport`] is written to [`SYNC_REPORT_FILE`] at the root of the
+/// directory.
+///
+/// The other way round, [`push_local`](Self::push_local) bookmarks the sounds
+/// whose files were added to a category directory by hand, named after their
+/// id like the downloaded ones (e.g. `Pads/1234.wav`). Freesound offers no way
+/// to remove a bookmark, so files deleted locally are downloaded again.
+///
+/// The client needs an OAuth2 access token (see
+/// [`FreesoundClient::with_access_token`]).
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::env;
+/// use freesound_rs::{BookmarkSync, DownloadSource, FreesoundClient};
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     dotenvy::dotenv().ok();
+///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+///     let access_token = env::var("FREESOUND_ACCESS_TOKEN")?;
+///     let client = FreesoundClient::new(api_key, None).with_access_token(access_token);
+/// let report = BookmarkSync::new(client, "bookmarks")
+///     .source(DownloadSource::Original)
+///     .remove_orphans(true)
+///     .run()
+///     .await?;
+/// println!(
+///     "{} downloaded, {} up to date, {} removed",
+///     report.downloaded.len(),
+///     report.up_to_date,
+///     report.removed.len()
+/// );
+///      Ok(())
+///  }
+/// ```
+pub struct BookmarkSync {
+    client: FreesoundClient,
+    root: PathBuf,
+    source: DownloadSource,
+    remove_orphans: bool,
+    allow_empty: bool,
+    push_local: bool,
+    manager: DownloadManager,
+}
+
+impl BookmarkSync {
+    /// Create a synchronisation of the user's bookmarks into `root`
+    pub fn new(client: FreesoundClient, root: impl Into<PathBuf>) -> Self {
+        Self {
+            manager: DownloadManager::new(client.clone()),
+            client,
+            root: root.into(),
+            source: DownloadSource::default(),
+            remove_orphans: false,
+            allow_empty: false,
+            push_local: false,
+        }
+    }
+
+    /// Set which file of each sound to download (defaults to the HQ MP3 preview)
+    pub fn source(mut self, source: DownloadSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Remove the files that no longer match a bookmark (defaults to false)
+    pub fn remove_orphans(mut self, remove_orphans: bool) -> Self {
+        self.remove_orphans = remove_orphans;
+        self
+    }
+
+    /// Remove orphans even when the account lists no bookmark (defaults to false)
+    pub fn allow_empty(mut self, allow_empty: bool) -> Self {
+        self.allow_empty = allow_empty;
+        self
+    }
+
+    /// Bookmark the sounds whose files were added locally (defaults to false)
+    ///
+    /// A file of a category directory named after a sound id, e.g. `1234.wav`,
+    /// that the synchronisation did not write is bookmarked in the category of
+    /// the directory, which is created if it does not exist yet.
+    pub fn push_local(mut self, push_local: bool) -> Self {
+        self.push_local = push_local;
+        self
+    }
+
+    /// Set the number of downloads running at the same time
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.manager = self.manager.concurrency(concurrency);
+        self
+    }
+
+    /// Report download progress to `sink`
+    pub fn progress(mut self, sink: impl ProgressSink + 'static) -> Self {
+        self.manager = self.manager.progress(sink);
+        self
+    }
+
+    /// Run the synchronisation and write its report
+    ///
+    /// Failing downloads do not stop the synchronisation and are listed in the
+    /// report; listing the bookmarks, pushing local files or touching the
+    /// directory does.
+    pub async fn run(mut self) -> Result<SyncReport> {
+        let mut report = SyncReport::default();
+        let mut expected = HashSet::new();
+        std::fs::create_dir_all(&self.root)?;
+        let mut manifest = self.read_manifest()?;
+
+        let query = SearchQueryBuilder::new()
+            .page_size(150)
+            .fields(["id", "name", "type", "previews"])
+            .build();
+
+        // Directory names of the categories, and the sounds bookmarked in each
+        let mut categories_by_dir = HashMap::new();
+        let first = self.client.get_bookmark_categories().await?;
+        let mut categories = Box::pin(self.client.items(first));
+        while let Some(category) = categories.try_next().await? {
+            let dir_name = sanitize_file_name(&category.name);
+            let dir = self.root.join(&dir_name);
+            report.categories.push(dir_name.clone());
+            let bookmarked = categories_by_dir
+                .entry(dir_name)
+                .or_insert((category.name.clone(), HashSet::new()));
+
+            let first = self
+                .client
+                .get_bookmark_category_sounds(category.id, &query)
+                .await?;
+            let mut sounds = Box::pin(self.client.items(first));
+            while let Some(sound) = sounds.try_next().await? {
+                bookmarked.1.insert(sound.id);
+                let job = DownloadJob::new(&self.client, &sound, self.source, &dir)?;
+                if !expected.insert(job.destination.clone()) {
+                    continue;
+                }
+                if job.destination.exists() {
+                    report.up_to_date += 1;
+                } else {
+                    self.manager.enqueue(job)?;
+                }
+            }
+        }
+
+        if self.push_local {
+            for (path, sound_id) in self.local_additions(&manifest, &expected)? {
+                let dir_name = path
+                    .parent()
+                    .and_then(Path::file_name)
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let (category, bookmarked) = categories_by_dir
+                    .entry(dir_name.clone())
+                    .or_insert((dir_name, HashSet::new()));
+                // The file stays the user's: it is not added to the manifest
+                if bookmarked.insert(sound_id) {
+                    let request = BookmarkRequest::new(sound_id).category(category.clone());
+                    self.client.bookmark_sound(&request).await?;
+                    report.pushed.push(sound_id);
+                }
+            }
+        }
+
+        for item in self.manager.run().await.items {
+            match item.status {
+                DownloadStatus::Downloaded => {
+                    manifest.files.insert(self.relative(&item.job.destination));
+                    report.downloaded.push(item.job.destination);
+                }
+                DownloadStatus::Skipped => report.up_to_date += 1,
+                DownloadStatus::Failed => report.failed.push(SyncFailure {
+                    sound_id: item.job.sound_id,
+                    destination: item.job.destination,
+                    error: item.error.unwrap_or_default(),
+                }),
+            }
+        }
+
+        // Only the files written by a synchronisation can be orphans
+        manifest.files.retain(|file| self.root.join(file).is_file());
+        let orphans: Vec<PathBuf> = manifest
+            .files
+            .iter()
+            .map(|file| self.root.join(file))
+            .filter(|path| !expected.contains(path))
+            .collect();
+        let remove = self.remove_orphans && (self.allow_empty || !expected.is_empty());
+        if self.remove_orphans && !remove && !orphans.is_empty() {
+            log::warn!(
+                "No bookmark listed, keeping the {} synchronised files of {}",
+                orphans.len(),
+                self.root.display()
+            );
+        }
+        for path in orphans {
+            if remove {
+                std::fs::remove_file(&path)?;
+                manifest.files.remove(&self.relative(&path));
+                remove_empty_parent(&self.root, &path)?;
+                report.removed.push(path);
+            } else {
+                report.orphans.push(path);
+            }
+        }
+
+        write_json(&self.root.join(SYNC_MANIFEST_FILE), &manifest)?;
+        write_json(&self.root.join(SYNC_REPORT_FILE), &report)?;
+        Ok(report)
+    }
+
+    fn read_manifest(&self) -> Result<SyncManifest> {
+        match std::fs::read_to_string(self.root.join(SYNC_MANIFEST_FILE)) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(SyncManifest::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Returns `path` relative to the root
+    fn relative(&self, path: &Path) -> PathBuf {
+        path.strip_prefix(&self.root).unwrap_or(path).to_path_buf()
+    }
+
+    /// Lists the files of the category directories named after a sound id that
+    /// are neither managed nor expected, with their sound id
+    fn local_additions(
+        &self,
+        manifest: &SyncManifest,
+        expected: &HashSet<PathBuf>,
+    ) -> Result<Vec<(PathBuf, i32)>> {
+        let mut additions = Vec::new();
+        for entry in std::fs::read_dir(&self.root)? {
+            let dir = entry?.path();
+            if !dir.is_dir() || is_hidden(&dir) {
+                continue;
+            }
+            for entry in std::fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if !path.is_file()
+                    || is_hidden(&path)
+                    || expected.contains(&path)
+                    || manifest.files.contains(&self.relative(&path))
+                {
+                    continue;
+                }
+                let sound_id = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.parse().ok());
+                if let Some(sound_id) = sound_id {
+                    additions.push((path, sound_id));
+                }
+            }
+        }
+        additions.sort();
+        Ok(additions)
+    }
+}
+
+/// Mirror the bookmarks of the authenticated user into `local_dir`
+///
+/// Downloads HQ MP3 previews and keeps orphan files. See [`BookmarkSync`] to
+/// change these defaults.
+pub async fn sync_bookmarks(
+    client: &FreesoundClient,
+    local_dir: impl Into<PathBuf>,
+) -> Result<SyncReport> {
+    BookmarkSync::new(client.clone(), local_dir).run().await
+}
+
+/// Removes the directory of a removed file if it is empty, unless it is the root
+fn remove_empty_parent(root: &Path, path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent()
+        && dir != root
+        && std::fs::read_dir(dir)?.next().is_none()
+    {
+        std::fs::remove_dir(dir)?;
+    }
+    Ok(())
+}
+
+/// Writes a JSON file, replacing the previous one once fully written
+fn write_json(path: &Path, value: &impl Serialize) -> Result<()> {
+    let json = serde_json::to_vec_pretty(value)?;
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(format!(".{}.tmp", std::process::id()));
+    std::fs::write(&temporary, json)?;
+    std::fs::rename(&temporary, path)?;
+    Ok(())
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.'))
+}
@@ -0,0 +1,90 @@
+//! Tag utilities
+//!
+//! [`normalize`] turns tags into the form Freesound stores, as uploads do, and
+//! [`related`] suggests tags to refine a query from the tags of its results.
+
+use crate::models::Sound;
+use std::collections::{HashMap, HashSet};
+
+/// Normalize a list of tags as Freesound stores them
+///
+/// Tags are lowercased, multiword tags are split on whitespace and commas, and
+/// duplicates are dropped, keeping the first occurrence.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::tags;
+///
+/// let normalized = tags::normalize(["Field Recording", "rain,ROOF", "rain"]);
+/// assert_eq!(normalized, ["field", "recording", "rain", "roof"]);
+/// ```
+pub fn normalize<I, S>(tags: I) -> Vec<String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut seen = HashSet::new();
+    let mut normalized = Vec::new();
+    for tag in tags {
+        for word in tag
+            .as_ref()
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|w| !w.is_empty())
+        {
+            let word = word.to_lowercase();
+            if seen.insert(word.clone()) {
+                normalized.push(word);
+            }
+        }
+    }
+    normalized
+}
+
+/// Suggest tags often found along with `tags` in a result set
+///
+/// Only the sounds carrying all of `tags` are considered (all sounds if `tags`
+/// is empty). Their other tags are counted and returned most frequent first,
+/// ties broken alphabetically, up to `limit` suggestions. The sounds must have
+/// been fetched with their `tags` field.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{Sound, tags};
+///
+/// let sound = |tags: &[&str]| Sound {
+///     tags: tags.iter().map(|t| t.to_string()).collect(),
+///     ..Default::default()
+/// };
+/// let results = vec![
+///     sound(&["rain", "roof", "night"]),
+///     sound(&["rain", "roof"]),
+///     sound(&["rain", "thunder"]),
+///     sound(&["wind", "night"]),
+/// ];
+///
+/// let suggestions = tags::related(&results, &["Rain"], 2);
+/// assert_eq!(suggestions, [("roof".to_string(), 2), ("night".to_string(), 1)]);
+/// ```
+pub fn related<S: AsRef<str>>(sounds: &[Sound], tags: &[S], limit: usize) -> Vec<(String, usize)> {
+    let wanted: HashSet<_> = normalize(tags).into_iter().collect();
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for sound in sounds {
+        let sound_tags = normalize(&sound.tags);
+        if !wanted.iter().all(|tag| sound_tags.contains(tag)) {
+            continue;
+        }
+        for tag in sound_tags {
+            if !wanted.contains(&tag) {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+    }
+
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|(a, m), (b, n)| n.cmp(m).then_with(|| a.cmp(b)));
+    counts.truncate(limit);
+    counts
+}
@@ -0,0 +1,179 @@
+use crate::client::FreesoundClient;
+use crate::download::{DownloadSource, JobStatus, Manifest};
+use crate::error::{FreesoundError, Result};
+use crate::models::{GetSoundOptions, SearchQueryBuilder, Sound};
+use futures::{StreamExt, TryStreamExt, stream};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Number of requests [`FreesoundClient::check_for_updates`] runs at once
+pub const CHECK_CONCURRENCY: usize = 4;
+
+/// Number of sounds fetched by each search of [`FreesoundClient::check_for_updates`]
+const CHECK_BATCH_SIZE: usize = 150;
+
+/// Fields fetched to compare a sound with its downloaded file
+const CHECKED_FIELDS: [&str; 2] = ["id", "md5"];
+
+/// How a downloaded sound changed
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SoundChange {
+    /// The file was replaced
+    Reuploaded {
+        /// The digest of the downloaded file
+        old_md5: String,
+        /// The digest of the current file
+        new_md5: String,
+    },
+    /// The sound is gone
+    Deleted,
+}
+
+/// A change of a downloaded sound, reported by [`FreesoundClient::check_for_updates`]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SoundUpdate {
+    /// The identifier of the sound
+    pub id: i32,
+    /// The file downloaded from the sound
+    pub file: PathBuf,
+    /// What changed
+    pub change: SoundChange,
+}
+
+impl FreesoundClient {
+    /// Compare the files of a download manifest with the current sounds
+    ///
+    /// Every completed job of the [`Manifest`] at `path` is checked: originals
+    /// whose digest, as recorded in the job, differs from the current one are
+    /// reported as [re-uploaded](SoundChange::Reuploaded), and the files of
+    /// sounds that are gone as [deleted](SoundChange::Deleted). Downloading a
+    /// sound again records its new digest, which acknowledges the change.
+    ///
+    /// The sounds are fetched by batches of 150 through the search endpoint.
+    /// Those the search does not return are fetched one by one, and reported as
+    /// deleted if the API answers 404. Sounds still being processed are left
+    /// out, to be checked later. Updates follow the order of the manifest.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FreesoundClient, SoundChange};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// for update in client.check_for_updates("assets/manifest.jsonl").await? {
+    ///     match update.change {
+    ///         SoundChange::Deleted => println!("{} was deleted", update.file.display()),
+    ///         change => println!("{} changed: {change:?}", update.file.display()),
+    ///     }
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn check_for_updates(&self, path: impl AsRef<Path>) -> Result<Vec<SoundUpdate>> {
+        let entries: Vec<_> = Manifest::latest(path)?
+            .into_iter()
+            .filter(|entry| entry.status == JobStatus::Completed)
+            .collect();
+        let mut ids: Vec<i32> = entries.iter().map(|entry| entry.job.sound_id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        let current = self
+            .current_sounds(&ids, &CHECKED_FIELDS.map(String::from))
+            .await?;
+
+        let mut updates = Vec::new();
+        for entry in &entries {
+            let id = entry.job.sound_id;
+            // Previews have digests of their own, not comparable to the sound's
+            let original = entry.fetched.unwrap_or(entry.job.source) == DownloadSource::Original;
+            let change = match current.get(&id) {
+                Some(Some(sound)) => match &entry.job.md5 {
+                    Some(old_md5)
+                        if original
+                            && !sound.md5.is_empty()
+                            && !old_md5.eq_ignore_ascii_case(&sound.md5) =>
+                    {
+                        SoundChange::Reuploaded {
+                            old_md5: old_md5.clone(),
+                            new_md5: sound.md5.clone(),
+                        }
+                    }
+                    _ => continue,
+                },
+                Some(None) => SoundChange::Deleted,
+                // Being processed again, it can only be checked later
+                None => continue,
+            };
+            updates.push(SoundUpdate {
+                id,
+                file: entry.file(),
+                change,
+            });
+        }
+        Ok(updates)
+    }
+
+    /// Fetches the current state of sounds, by batches through the search
+    ///
+    /// Sounds the search does not return are fetched one by one: deleted ones
+    /// map to `None`, and those being processed are left out.
+    pub(crate) async fn current_sounds(
+        &self,
+        ids: &[i32],
+        fields: &[String],
+    ) -> Result<HashMap<i32, Option<Sound>>> {
+        let batches: Vec<Vec<Sound>> = stream::iter(ids.chunks(CHECK_BATCH_SIZE))
+            .map(|ids| {
+                let filter = format!(
+                    "id:({})",
+                    ids.iter()
+                        .map(|id| id.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" OR ")
+                );
+                let query = SearchQueryBuilder::new()
+                    .filter(filter)
+                    .page_size(CHECK_BATCH_SIZE as i32)
+                    .fields(fields.iter().cloned())
+                    .build();
+                async move { self.search(&query).await.map(|page| page.results) }
+            })
+            .buffered(CHECK_CONCURRENCY)
+            .try_collect()
+            .await?;
+        let mut current: HashMap<i32, Option<Sound>> = batches
+            .into_iter()
+            .flatten()
+            .map(|sound| (sound.id, Some(sound)))
+            .collect();
+
+        // Sounds missing from the search may only be missing from its index
+        let options = GetSoundOptions::new().fields(fields.iter().cloned());
+        let missing: Vec<Option<(i32, Option<Sound>)>> =
+            stream::iter(ids.iter().copied().filter(|id| !current.contains_key(id)))
+                .map(|id| {
+                    let options = &options;
+                    async move {
+                        match self.get_sound(id, options).await {
+                            Ok(sound) => Ok(Some((id, Some(sound)))),
+                            Err(e) => match e.root() {
+                                FreesoundError::SoundDeleted(_) => Ok(Some((id, None))),
+                                FreesoundError::SoundProcessing(_) => Ok(None),
+                                _ => Err(e),
+                            },
+                        }
+                    }
+                })
+                .buffered(CHECK_CONCURRENCY)
+                .try_collect()
+                .await?;
+        current.extend(missing.into_iter().flatten());
+        Ok(current)
+    }
+}
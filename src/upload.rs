@@ -0,0 +1,623 @@
+//! Uploading sounds
+//!
+//! [`bulk`], or a [`BulkUpload`] for more options, uploads every audio file of
+//! a directory with the name, tags and license given in a CSV or JSON sidecar,
+//! keeping a journal so an interrupted batch resumes where it stopped. Single
+//! files are uploaded with [`FreesoundClient::upload_sound`].
+
+use crate::client::FreesoundClient;
+use crate::download::{JobStatus, Manifest, read_entries};
+use crate::error::{FreesoundError, Result};
+use crate::models::{
+    FilterBuilder, GetSoundOptions, PendingUploads, SearchQueryBuilder, SortOption, UploadStatus,
+};
+use crate::tags;
+use futures::AsyncReadExt;
+use futures::stream::{self, Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Extensions of the audio files picked up by [`BulkUpload`]
+pub const AUDIO_EXTENSIONS: &[&str] = &["wav", "aif", "aiff", "flac", "ogg", "mp3", "m4a"];
+
+/// Minimum number of tags of a described sound
+pub const MIN_TAGS: usize = 3;
+
+/// Size of the chunks an uploaded file is read and sent in
+const UPLOAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The description of an uploaded file
+///
+/// Sidecar files list one description per file, matched by `filename`. In CSV
+/// sidecars, the header names the columns and tags are separated by spaces.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::SoundDescription;
+///
+/// let csv = "filename,name,tags,description,license\n\
+///            rain.wav,Rain on a roof,rain roof water,\"Light rain, recorded at night\",Creative Commons 0\n";
+/// let descriptions = SoundDescription::from_csv(csv)?;
+/// assert_eq!(descriptions[0].filename, "rain.wav");
+/// assert_eq!(descriptions[0].tags, ["rain", "roof", "water"]);
+/// assert_eq!(descriptions[0].description, "Light rain, recorded at night");
+/// assert!(descriptions[0].validate().is_ok());
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SoundDescription {
+    /// Name of the file described, relative to the uploaded directory
+    pub filename: String,
+    /// Name of the sound, defaults to the file name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Tags of the sound, at least [`MIN_TAGS`] once normalized (see [`tags::normalize`])
+    pub tags: Vec<String>,
+    /// Description of the sound
+    pub description: String,
+    /// License, e.g. `"Attribution"`, `"Attribution NonCommercial"` or `"Creative Commons 0"`
+    pub license: String,
+    /// Name of the pack to add the sound to, created if it does not exist
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pack: Option<String>,
+    /// Location of the recording, as `"<latitude>,<longitude>,<zoom>"`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub geotag: Option<String>,
+}
+
+impl SoundDescription {
+    /// Checks the description against the API requirements
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: String| Err(FreesoundError::InvalidRequest(message));
+        if tags::normalize(&self.tags).len() < MIN_TAGS {
+            return invalid(format!(
+                "{}: at least {MIN_TAGS} tags are required",
+                self.filename
+            ));
+        }
+        if self.description.trim().is_empty() {
+            return invalid(format!("{}: description must not be empty", self.filename));
+        }
+        if self.license.trim().is_empty() {
+            return invalid(format!("{}: license must not be empty", self.filename));
+        }
+        Ok(())
+    }
+
+    /// Parse descriptions from a JSON array
+    pub fn from_json(json: &str) -> Result<Vec<Self>> {
+        serde_json::from_str(json)
+            .map_err(|e| FreesoundError::InvalidRequest(format!("Invalid sidecar: {e}")))
+    }
+
+    /// Parse descriptions from CSV, with a header line
+    pub fn from_csv(csv: &str) -> Result<Vec<Self>> {
+        let mut rows = parse_csv(csv).into_iter();
+        let Some(header) = rows.next() else {
+            return Ok(Vec::new());
+        };
+
+        rows.filter(|row| row.iter().any(|cell| !cell.trim().is_empty()))
+            .map(|row| {
+                let mut description = Self::default();
+                for (column, value) in header.iter().zip(row) {
+                    let value = value.trim().to_string();
+                    let optional = || (!value.is_empty()).then(|| value.clone());
+                    match column.trim() {
+                        "filename" => description.filename = value,
+                        "name" => description.name = optional(),
+                        "tags" => {
+                            description.tags = value.split_whitespace().map(String::from).collect()
+                        }
+                        "description" => description.description = value,
+                        "license" => description.license = value,
+                        "pack" => description.pack = optional(),
+                        "geotag" => description.geotag = optional(),
+                        _ => {}
+                    }
+                }
+                if description.filename.is_empty() {
+                    return Err(FreesoundError::InvalidRequest(
+                        "Invalid sidecar: a row has no filename".to_string(),
+                    ));
+                }
+                Ok(description)
+            })
+            .collect()
+    }
+
+    /// Read descriptions from a `.csv` or `.json` sidecar file
+    pub fn load(path: impl AsRef<Path>) -> Result<Vec<Self>> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => Self::from_csv(&content),
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::from_json(&content),
+            _ => Err(FreesoundError::InvalidRequest(format!(
+                "Unsupported sidecar format: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    fn form(&self) -> Vec<(&'static str, String)> {
+        let mut form = vec![
+            ("tags", tags::normalize(&self.tags).join(" ")),
+            ("description", self.description.clone()),
+            ("license", self.license.clone()),
+        ];
+        for (field, value) in [
+            ("name", &self.name),
+            ("pack", &self.pack),
+            ("geotag", &self.geotag),
+        ] {
+            if let Some(value) = value {
+                form.push((field, value.clone()));
+            }
+        }
+        form
+    }
+}
+
+/// Response of the API to an upload
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct UploadResponse {
+    /// The confirmation message of the API
+    pub detail: String,
+    /// The id of the sound, for described uploads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+}
+
+/// A line of a bulk upload journal, see [`Manifest`]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct UploadJournalEntry {
+    /// Name of the file, relative to the uploaded directory
+    pub filename: String,
+    /// Whether the upload completed or failed
+    pub status: JobStatus,
+    /// The id of the sound, for described uploads
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sound_id: Option<i32>,
+    /// Error message, for failed uploads
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Uploads every audio file of a directory, described from a sidecar file
+///
+/// Files listed in the sidecar are uploaded with their description; the other
+/// audio files are uploaded undescribed, to be described on the website. All
+/// descriptions are validated before anything is sent. With a journal, files
+/// already uploaded by a previous run are skipped.
+///
+/// The client needs an OAuth2 access token (see
+/// [`FreesoundClient::with_access_token`]).
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::env;
+/// use freesound_rs::{BulkUpload, FreesoundClient};
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     dotenvy::dotenv().ok();
+///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+///     let access_token = env::var("FREESOUND_ACCESS_TOKEN")?;
+///     let client = FreesoundClient::new(api_key, None).with_access_token(access_token);
+/// let outcomes = BulkUpload::new(client, "session", "session/metadata.csv")?
+///     .concurrency(2)
+///     .journal("session/upload-journal.jsonl")
+///     .run()
+///     .await?;
+/// for (file, result) in outcomes {
+///     if let Err(e) = result {
+///         eprintln!("{} failed: {e}", file.display());
+///     }
+/// }
+///      Ok(())
+///  }
+/// ```
+#[derive(Debug)]
+pub struct BulkUpload {
+    client: FreesoundClient,
+    dir: PathBuf,
+    descriptions: HashMap<String, SoundDescription>,
+    concurrency: usize,
+    journal: Option<PathBuf>,
+}
+
+impl BulkUpload {
+    /// Prepare the upload of `dir`, described by `metadata_file`
+    ///
+    /// Fails if the sidecar cannot be read or one of its descriptions is invalid.
+    pub fn new(
+        client: FreesoundClient,
+        dir: impl Into<PathBuf>,
+        metadata_file: impl AsRef<Path>,
+    ) -> Result<Self> {
+        let mut descriptions = HashMap::new();
+        for description in SoundDescription::load(metadata_file)? {
+            description.validate()?;
+            descriptions.insert(description.filename.clone(), description);
+        }
+        Ok(Self {
+            client,
+            dir: dir.into(),
+            descriptions,
+            concurrency: 2,
+            journal: None,
+        })
+    }
+
+    /// Set the maximum number of uploads running at the same time (defaults to 2)
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Record every upload in a journal file, and skip the files it lists as uploaded
+    pub fn journal(mut self, path: impl Into<PathBuf>) -> Self {
+        self.journal = Some(path.into());
+        self
+    }
+
+    /// Returns the audio files of the directory that remain to be uploaded
+    pub fn pending(&self) -> Result<Vec<PathBuf>> {
+        let done = match &self.journal {
+            Some(path) => uploaded_files(path)?,
+            None => HashSet::new(),
+        };
+
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let is_audio = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| AUDIO_EXTENSIONS.iter().any(|a| a.eq_ignore_ascii_case(e)));
+            if path.is_file() && is_audio && !done.contains(&file_name(&path)) {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Upload the pending files and return their outcomes, in completion order
+    ///
+    /// A failing upload does not stop the others. An upload whose outcome cannot
+    /// be recorded in the journal is reported as failed.
+    pub async fn run(self) -> Result<Vec<(PathBuf, Result<UploadResponse>)>> {
+        let files = self.pending()?;
+        let journal = match &self.journal {
+            Some(path) => Some(Manifest::<UploadJournalEntry>::open(path)?),
+            None => None,
+        };
+        let this = &self;
+        let journal = &journal;
+
+        Ok(stream::iter(files)
+            .map(|path| async move {
+                let filename = file_name(&path);
+                let mut result = this
+                    .client
+                    .upload_sound(&path, this.descriptions.get(&filename))
+                    .await;
+                if let Some(journal) = journal {
+                    let entry = UploadJournalEntry {
+                        filename,
+                        status: match result {
+                            Ok(_) => JobStatus::Completed,
+                            Err(_) => JobStatus::Failed,
+                        },
+                        sound_id: result.as_ref().ok().and_then(|r| r.id),
+                        error: result.as_ref().err().map(|e| e.to_string()),
+                    };
+                    if let Err(e) = journal.record(&entry) {
+                        result = Err(e);
+                    }
+                }
+                (path, result)
+            })
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await)
+    }
+}
+
+/// Upload every audio file of `dir`, described by `metadata_file`
+///
+/// Uses a journal named `upload-journal.jsonl` in `dir`, so running it again
+/// resumes an interrupted batch. See [`BulkUpload`] for more options.
+pub async fn bulk(
+    client: &FreesoundClient,
+    dir: impl Into<PathBuf>,
+    metadata_file: impl AsRef<Path>,
+) -> Result<Vec<(PathBuf, Result<UploadResponse>)>> {
+    let dir = dir.into();
+    let journal = dir.join("upload-journal.jsonl");
+    BulkUpload::new(client.clone(), dir, metadata_file)?
+        .journal(journal)
+        .run()
+        .await
+}
+
+struct TrackState {
+    client: FreesoundClient,
+    filename: String,
+    interval: Duration,
+    polled: bool,
+    last: Option<UploadStatus>,
+    done: bool,
+}
+
+impl FreesoundClient {
+    /// Upload an audio file, optionally with its description
+    ///
+    /// Described uploads are processed and moderated right away; the others wait
+    /// for a description on the website. The file is streamed from disk rather
+    /// than read into memory. The upload is bound by the download
+    /// timeout of the client, not by its API request timeout. Requires an OAuth2 access token (see
+    /// [`with_access_token`](Self::with_access_token)).
+    ///
+    /// The upload is sent once and never tried again automatically. A file
+    /// Freesound refuses as already uploaded fails with
+    /// [`AlreadyExists`](FreesoundError::AlreadyExists).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FreesoundClient, SoundDescription};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let access_token = env::var("FREESOUND_ACCESS_TOKEN")?;
+    ///     let client = FreesoundClient::new(api_key, None).with_access_token(access_token);
+    /// let description = SoundDescription {
+    ///     filename: "rain.wav".to_string(),
+    ///     tags: vec!["rain".into(), "roof".into(), "water".into()],
+    ///     description: "Light rain on a tin roof".to_string(),
+    ///     license: "Creative Commons 0".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// let response = client.upload_sound("rain.wav", Some(&description)).await?;
+    /// println!("Uploaded as sound {:?}", response.id);
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn upload_sound(
+        &self,
+        path: impl AsRef<Path>,
+        description: Option<&SoundDescription>,
+    ) -> Result<UploadResponse> {
+        self.require_oauth("uploading sounds")?;
+        let path = path.as_ref();
+        if let Some(description) = description {
+            description.validate()?;
+        }
+
+        let file = upload_part(path.to_path_buf())
+            .await?
+            .file_name(file_name(path));
+        let mut form = reqwest::multipart::Form::new().part("audiofile", file);
+        for (field, value) in description.map(|d| d.form()).unwrap_or_default() {
+            form = form.text(field, value);
+        }
+
+        // Like downloads, uploads are not bound by the API request timeout
+        let url = format!("{}/sounds/upload", self.base_url());
+        let request = self.authorize(self.http_client().post(url)).multipart(form);
+        self.send_json(self.download_request(request))
+            .await
+            .map_err(FreesoundError::for_write)
+    }
+
+    /// List the uploads of the authenticated user that are not published yet
+    ///
+    /// Requires an OAuth2 access token (see [`with_access_token`](Self::with_access_token)).
+    pub async fn pending_uploads(&self) -> Result<PendingUploads> {
+        self.require_oauth("listing pending uploads")?;
+        self.send_json(self.request(reqwest::Method::GET, "me/pending_uploads"))
+            .await
+    }
+
+    /// Follow an upload until it is published
+    ///
+    /// The pending uploads are polled every `interval`, and the status of the
+    /// upload named `filename` is yielded each time it changes, starting with
+    /// the current one. The stream ends after [`UploadStatus::Published`].
+    ///
+    /// Once described, the upload is followed by sound id. When it is no longer
+    /// pending, the sound is looked up, by id or else among the sounds of the
+    /// user by file name, and [`UploadStatus::Published`] is yielded if it is
+    /// public; otherwise, e.g. when it was rejected, an error is yielded and
+    /// the stream ends. Other errors are yielded and do not end the stream; the
+    /// next poll comes `interval` later in any case.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use std::time::Duration;
+    /// use futures::StreamExt;
+    /// use freesound_rs::FreesoundClient;
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let access_token = env::var("FREESOUND_ACCESS_TOKEN")?;
+    ///     let client = FreesoundClient::new(api_key, None).with_access_token(access_token);
+    /// let mut statuses = Box::pin(client.track_upload("rain.wav", Duration::from_secs(60)));
+    /// while let Some(status) = statuses.next().await {
+    ///     println!("{:?}", status?);
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn track_upload(
+        &self,
+        filename: &str,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<UploadStatus>> + use<> {
+        let state = TrackState {
+            client: self.clone(),
+            filename: filename.to_string(),
+            interval,
+            polled: false,
+            last: None,
+            done: false,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            if state.done {
+                return None;
+            }
+            loop {
+                if state.polled {
+                    state.client.sleeper().sleep(state.interval).await;
+                }
+                state.polled = true;
+
+                let pending = match state.client.pending_uploads().await {
+                    Ok(pending) => pending,
+                    Err(e) => return Some((Err(e), state)),
+                };
+                let sound_id = state.last.and_then(|s| s.sound_id());
+                let status = match pending.status_of(&state.filename, sound_id) {
+                    Some(status) => status,
+                    None => match state.client.published_id(&state.filename, sound_id).await {
+                        Ok(Some(id)) => UploadStatus::Published(id),
+                        Ok(None) => {
+                            state.done = true;
+                            let error = FreesoundError::ApiError(format!(
+                                "Upload {} is neither pending nor published",
+                                state.filename
+                            ));
+                            return Some((Err(error), state));
+                        }
+                        Err(e) => return Some((Err(e), state)),
+                    },
+                };
+
+                if state.last != Some(status) {
+                    state.last = Some(status);
+                    state.done = status.is_published();
+                    return Some((Ok(status), state));
+                }
+            }
+        })
+    }
+}
+
+impl FreesoundClient {
+    /// Looks up the public sound of an upload that is no longer pending
+    async fn published_id(&self, filename: &str, sound_id: Option<i32>) -> Result<Option<i32>> {
+        let fields = GetSoundOptions::new().fields(["id"]);
+        if let Some(id) = sound_id {
+            return match self.get_sound(id, &fields).await {
+                Ok(sound) => Ok(Some(sound.id)),
+                Err(e) if matches!(e.root(), FreesoundError::SoundDeleted { .. }) => Ok(None),
+                Err(e) => Err(e),
+            };
+        }
+        let me = self.get_me().await?;
+        let filter = FilterBuilder::new().raw(format!(
+            "original_filename:\"{}\"",
+            filename.replace('\\', "\\\\").replace('"', "\\\"")
+        ));
+        let query = SearchQueryBuilder::new()
+            .filter(filter.build())
+            .by_user(&me.username)
+            .sort(SortOption::CreatedDesc)
+            .page_size(1)
+            .fields(["id"])
+            .build();
+        let page = self.search(&query).await?;
+        Ok(page.results.first().map(|sound| sound.id))
+    }
+}
+
+/// Streams a file into a multipart part, without holding it in memory
+///
+/// The file is read off the executor with the `blocking` crate, which works
+/// whatever the async runtime.
+async fn upload_part(path: PathBuf) -> Result<reqwest::multipart::Part> {
+    let (file, len) = blocking::unblock(move || {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        Ok::<_, std::io::Error>((file, len))
+    })
+    .await?;
+    let chunks = stream::try_unfold(blocking::Unblock::new(file), |mut file| async move {
+        let mut chunk = vec![0; UPLOAD_CHUNK_SIZE];
+        let read = file.read(&mut chunk).await?;
+        chunk.truncate(read);
+        Ok::<_, std::io::Error>((read > 0).then_some((chunk, file)))
+    });
+    Ok(reqwest::multipart::Part::stream_with_length(
+        reqwest::Body::wrap_stream(chunks),
+        len,
+    ))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Returns the files a journal lists as uploaded
+fn uploaded_files(path: &Path) -> Result<HashSet<String>> {
+    if !path.exists() {
+        return Ok(HashSet::new());
+    }
+    let mut done = HashSet::new();
+    for entry in read_entries::<UploadJournalEntry>(path)? {
+        if entry.status == JobStatus::Completed {
+            done.insert(entry.filename);
+        } else {
+            done.remove(&entry.filename);
+        }
+    }
+    Ok(done)
+}
+
+/// Splits CSV text into rows of cells, following RFC 4180 quoting
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => row.push(std::mem::take(&mut cell)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                row.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut row));
+            }
+            c => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !row.is_empty() {
+        row.push(cell);
+        rows.push(row);
+    }
+    rows
+}
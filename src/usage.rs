@@ -0,0 +1,171 @@
+use crate::client::FreesoundClient;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// API usage of a client since it was built
+///
+/// Returned by [`FreesoundClient::usage_report`]. It can be printed as a
+/// summary at the end of a run, or serialized to keep a log of the quota used.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct UsageReport {
+    /// Time since the client was built
+    pub elapsed: Duration,
+    /// Requests sent, by method and endpoint, such as `GET sounds/{id}`
+    ///
+    /// Requests to other hosts, such as previews, are counted under the host name.
+    pub requests: BTreeMap<String, u64>,
+    /// Bytes of previews, originals and images written to disk
+    pub bytes_downloaded: u64,
+    /// Search pages read from the [search cache](crate::SearchCache)
+    pub cache_hits: u64,
+    /// Search pages requested while the cache did not have them
+    pub cache_misses: u64,
+    /// Requests refused by Freesound with `429 Too Many Requests`
+    pub rate_limited: u64,
+}
+
+impl UsageReport {
+    /// Total number of requests sent
+    pub fn total_requests(&self) -> u64 {
+        self.requests.values().sum()
+    }
+
+    /// Share of the searches answered by the cache, if the cache was used
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        match self.cache_hits + self.cache_misses {
+            0 => None,
+            lookups => Some(self.cache_hits as f64 / lookups as f64),
+        }
+    }
+}
+
+impl fmt::Display for UsageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} requests in {:.1}s",
+            self.total_requests(),
+            self.elapsed.as_secs_f64()
+        )?;
+        for (endpoint, count) in &self.requests {
+            writeln!(f, "  {count:>6}  {endpoint}")?;
+        }
+        writeln!(f, "{} bytes downloaded", self.bytes_downloaded)?;
+        if let Some(rate) = self.cache_hit_rate() {
+            writeln!(
+                f,
+                "cache hit rate: {:.1}% ({} hits, {} misses)",
+                rate * 100.0,
+                self.cache_hits,
+                self.cache_misses
+            )?;
+        }
+        write!(f, "{} rate-limited requests", self.rate_limited)
+    }
+}
+
+/// Usage counters shared by a client and its clones
+#[derive(Debug)]
+pub(crate) struct Usage {
+    started: Instant,
+    report: Mutex<UsageReport>,
+}
+
+impl Default for Usage {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            report: Mutex::new(UsageReport::default()),
+        }
+    }
+}
+
+impl Usage {
+    pub(crate) fn record_request(&self, endpoint: String, status: Option<reqwest::StatusCode>) {
+        let mut report = self.report.lock().unwrap();
+        *report.requests.entry(endpoint).or_default() += 1;
+        if status == Some(reqwest::StatusCode::TOO_MANY_REQUESTS) {
+            report.rate_limited += 1;
+        }
+    }
+
+    pub(crate) fn record_bytes(&self, bytes: u64) {
+        self.report.lock().unwrap().bytes_downloaded += bytes;
+    }
+
+    pub(crate) fn record_cache_lookup(&self, hit: bool) {
+        let mut report = self.report.lock().unwrap();
+        match hit {
+            true => report.cache_hits += 1,
+            false => report.cache_misses += 1,
+        }
+    }
+
+    fn report(&self) -> UsageReport {
+        UsageReport {
+            elapsed: self.started.elapsed(),
+            ..self.report.lock().unwrap().clone()
+        }
+    }
+}
+
+/// Names the endpoint of a URL, with the ids and usernames replaced by placeholders
+pub(crate) fn endpoint_name(base_url: &str, url: &reqwest::Url) -> String {
+    let Some(path) = url.as_str().strip_prefix(base_url.trim_end_matches('/')) else {
+        return url.host_str().unwrap_or_default().to_string();
+    };
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+
+    let mut previous = "";
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let name = if segment.parse::<u64>().is_ok() {
+                "{id}"
+            } else if previous == "users" {
+                "{username}"
+            } else {
+                segment
+            };
+            previous = segment;
+            name
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+impl FreesoundClient {
+    /// Returns the API usage of the client since it was built
+    ///
+    /// Clones of the client share their usage, so the report covers every
+    /// request made through any of them.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{FreesoundClient, SearchQueryBuilder};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let query = SearchQueryBuilder::new().query("rain").build();
+    /// let results = client.search(&query).await?;
+    /// for sound in &results.results {
+    ///     client.get_sound(sound.id, &Default::default()).await?;
+    /// }
+    ///
+    /// let report = client.usage_report();
+    /// assert_eq!(report.requests["GET search/text"], 1);
+    /// eprintln!("{report}");
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn usage_report(&self) -> UsageReport {
+        self.usage().report()
+    }
+}
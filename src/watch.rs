@@ -0,0 +1,255 @@
+use crate::client::FreesoundClient;
+use crate::error::Result;
+use crate::models::{SortOption, Sound, Username};
+use futures::stream::{self, Stream};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Persists the highest sound id seen by a watcher
+///
+/// Restoring the mark on startup lets a restarted watcher emit only the sounds
+/// uploaded while it was down, instead of starting over.
+pub trait WatermarkStore: Send + Sync {
+    /// Returns the last saved mark, if any
+    fn load(&self) -> Result<Option<i32>>;
+
+    /// Saves a new mark
+    fn save(&self, high_water_mark: i32) -> Result<()>;
+}
+
+/// A [`WatermarkStore`] keeping the mark in memory only
+#[derive(Debug, Default)]
+pub struct MemoryWatermarkStore(Mutex<Option<i32>>);
+
+impl WatermarkStore for MemoryWatermarkStore {
+    fn load(&self) -> Result<Option<i32>> {
+        Ok(*self.0.lock().unwrap())
+    }
+
+    fn save(&self, high_water_mark: i32) -> Result<()> {
+        *self.0.lock().unwrap() = Some(high_water_mark);
+        Ok(())
+    }
+}
+
+/// A [`WatermarkStore`] keeping the mark in a text file
+#[derive(Debug, Clone)]
+pub struct FileWatermarkStore {
+    path: PathBuf,
+}
+
+impl FileWatermarkStore {
+    /// Creates a store using the file at `path`, which is created on first save
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl WatermarkStore for FileWatermarkStore {
+    fn load(&self) -> Result<Option<i32>> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => Ok(content.trim().parse().ok()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn save(&self, high_water_mark: i32) -> Result<()> {
+        std::fs::write(&self.path, high_water_mark.to_string())?;
+        Ok(())
+    }
+}
+
+struct WatchState<W> {
+    client: FreesoundClient,
+    query: Vec<(String, String)>,
+    interval: Duration,
+    store: W,
+    high_water_mark: Option<i32>,
+    pending: VecDeque<Sound>,
+    polled: bool,
+    loaded: bool,
+}
+
+impl FreesoundClient {
+    /// Watch a search for newly uploaded sounds
+    ///
+    /// The search is re-run every `interval`, sorted by creation date (newest
+    /// first), and only sounds with an id above the highest id seen so far are
+    /// yielded, oldest first. The first run only records the current high-water
+    /// mark, so existing sounds are not emitted. Only the first page of each run
+    /// is inspected: use a `page_size` large enough for the expected upload rate.
+    ///
+    /// Search errors are yielded as they happen and do not end the stream; the
+    /// next run comes `interval` later. The search cache of the client, if any,
+    /// is bypassed.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use std::time::Duration;
+    /// use futures::StreamExt;
+    /// use freesound_rs::{FreesoundClient, SearchQueryBuilder};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// let query = SearchQueryBuilder::new().filter("tag:field-recording").build();
+    /// let mut new_sounds = Box::pin(client.watch(&query, Duration::from_secs(600)));
+    /// while let Some(sound) = new_sounds.next().await {
+    ///     let sound = sound?;
+    ///     println!("New upload: {} by {}", sound.name, sound.username);
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn watch(
+        &self,
+        query: &[(String, String)],
+        interval: Duration,
+    ) -> impl Stream<Item = Result<Sound>> + use<> {
+        self.watch_with_store(query, interval, MemoryWatermarkStore::default())
+    }
+
+    /// Watch a search for newly uploaded sounds, persisting the high-water mark
+    ///
+    /// Works like [`watch`](Self::watch), but the mark is loaded from `store` on
+    /// the first run and saved each time a sound is yielded. When a mark was
+    /// restored, the sounds uploaded since are emitted on the first run. An
+    /// error loading the mark is yielded, and loading is tried again `interval`
+    /// later, before searching.
+    pub fn watch_with_store<W: WatermarkStore>(
+        &self,
+        query: &[(String, String)],
+        interval: Duration,
+        store: W,
+    ) -> impl Stream<Item = Result<Sound>> + use<W> {
+        let mut query: Vec<_> = query.iter().filter(|(k, _)| k != "sort").cloned().collect();
+        query.push(("sort".to_string(), SortOption::CreatedDesc.to_string()));
+
+        let state = WatchState {
+            client: self.without_search_cache(),
+            query,
+            interval,
+            store,
+            high_water_mark: None,
+            pending: VecDeque::new(),
+            polled: false,
+            loaded: false,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            loop {
+                if let Some(sound) = state.pending.pop_front() {
+                    let item = state.store.save(sound.id).map(|_| sound);
+                    return Some((item, state));
+                }
+
+                if state.polled {
+                    state.client.sleeper().sleep(state.interval).await;
+                }
+                state.polled = true;
+                if !state.loaded {
+                    match state.store.load() {
+                        Ok(mark) => state.high_water_mark = mark,
+                        Err(e) => return Some((Err(e), state)),
+                    }
+                    state.loaded = true;
+                }
+
+                let results = match state.client.search(&state.query).await {
+                    Ok(response) => response.results,
+                    Err(e) => return Some((Err(e), state)),
+                };
+                if let Err(e) = state.record(results) {
+                    return Some((Err(e), state));
+                }
+            }
+        })
+    }
+
+    /// Watch a user's uploads
+    ///
+    /// Works like [`watch`](Self::watch) on a search for the sounds of `username`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use std::time::Duration;
+    /// use futures::StreamExt;
+    /// use freesound_rs::{FileWatermarkStore, FreesoundClient};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let client = FreesoundClient::new(api_key, None);
+    /// // Restarting the program does not emit the same sounds again
+    /// let store = FileWatermarkStore::new("Jovica.last_seen");
+    /// let mut uploads = Box::pin(client.watch_user_sounds_with_store(
+    ///     &"Jovica".parse()?,
+    ///     Duration::from_secs(3600),
+    ///     store,
+    /// ));
+    /// while let Some(sound) = uploads.next().await {
+    ///     println!("Jovica uploaded {}", sound?.name);
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub fn watch_user_sounds(
+        &self,
+        username: &Username,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<Sound>> + use<> {
+        self.watch_user_sounds_with_store(username, interval, MemoryWatermarkStore::default())
+    }
+
+    /// Watch a user's uploads, persisting the high-water mark
+    ///
+    /// See [`watch_with_store`](Self::watch_with_store).
+    pub fn watch_user_sounds_with_store<W: WatermarkStore>(
+        &self,
+        username: &Username,
+        interval: Duration,
+        store: W,
+    ) -> impl Stream<Item = Result<Sound>> + use<W> {
+        let filter = format!("username:\"{}\"", username.replace('"', "\\\""));
+        self.watch_with_store(&[("filter".to_string(), filter)], interval, store)
+    }
+}
+
+impl<W: WatermarkStore> WatchState<W> {
+    /// Queues the sounds above the high-water mark, then raises the mark
+    ///
+    /// Without a known mark, the mark is only seeded and saved. A search
+    /// without results seeds it with 0, so that every sound found later is new.
+    fn record(&mut self, sounds: Vec<Sound>) -> Result<()> {
+        let Some(newest) = sounds.iter().map(|s| s.id).max() else {
+            if self.high_water_mark.is_none() {
+                self.high_water_mark = Some(0);
+                self.store.save(0)?;
+            }
+            return Ok(());
+        };
+
+        match self.high_water_mark {
+            Some(mark) => {
+                let mut fresh: Vec<_> = sounds.into_iter().filter(|s| s.id > mark).collect();
+                fresh.sort_by_key(|s| s.id);
+                self.pending.extend(fresh);
+                self.high_water_mark = Some(mark.max(newest));
+            }
+            None => {
+                self.high_water_mark = Some(newest);
+                self.store.save(newest)?;
+            }
+        }
+
+        Ok(())
+    }
+}
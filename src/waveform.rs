@@ -0,0 +1,59 @@
+//! Waveform drawing from decoded audio
+//!
+//! Requires the `decode` feature.
+
+use crate::decode::Pcm;
+
+/// The lowest and highest sample of a slice of audio
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Peak {
+    /// Lowest sample, between -1 and 0
+    pub min: f32,
+    /// Highest sample, between 0 and 1
+    pub max: f32,
+}
+
+/// Splits audio into `buckets` slices of equal length and returns their peaks
+///
+/// Every channel is taken into account, so the peaks cover the loudest one.
+/// Each peak spans at least 0, so silence gives flat `0.0` peaks, and so do the
+/// buckets left empty when there are fewer frames than buckets. Draw one
+/// vertical line per peak, from `min` to `max`, to get a waveform of any width.
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::Pcm;
+/// use freesound_rs::waveform::{Peak, peaks};
+///
+/// let pcm = Pcm {
+///     sample_rate: 44_100,
+///     channels: 1,
+///     samples: vec![0.5, -0.25, 0.1, 0.2, -0.8, 0.0],
+/// };
+/// assert_eq!(
+///     peaks(&pcm, 3),
+///     [
+///         Peak { min: -0.25, max: 0.5 },
+///         Peak { min: 0.0, max: 0.2 },
+///         Peak { min: -0.8, max: 0.0 },
+///     ]
+/// );
+/// assert_eq!(peaks(&pcm, 0), []);
+/// ```
+pub fn peaks(pcm: &Pcm, buckets: usize) -> Vec<Peak> {
+    let channels = pcm.channels.max(1) as usize;
+    let frames = pcm.frames();
+    (0..buckets)
+        .map(|i| {
+            let start = i * frames / buckets * channels;
+            let end = (i + 1) * frames / buckets * channels;
+            pcm.samples[start..end]
+                .iter()
+                .fold(Peak::default(), |peak, &sample| Peak {
+                    min: peak.min.min(sample),
+                    max: peak.max.max(sample),
+                })
+        })
+        .collect()
+}
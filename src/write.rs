@@ -0,0 +1,350 @@
+use crate::client::FreesoundClient;
+use crate::error::{FreesoundError, Result};
+use crate::models::BookmarkCategory;
+use futures::TryStreamExt;
+use serde::Deserialize;
+
+/// Longest bookmark or bookmark category name accepted
+pub const MAX_BOOKMARK_NAME_LENGTH: usize = 128;
+
+/// Longest comment accepted
+pub const MAX_COMMENT_LENGTH: usize = 4000;
+
+/// Highest rating a sound can be given
+pub const MAX_RATING: u8 = 5;
+
+/// A write operation on a sound, validated before being sent
+///
+/// These operations act on behalf of a user, so the client needs an OAuth2
+/// access token (see [`FreesoundClient::with_access_token`]).
+pub trait WriteRequest {
+    /// The API endpoint path
+    fn path(&self) -> String;
+
+    /// Checks the parameters against the API limits
+    fn validate(&self) -> Result<()>;
+
+    /// Returns the form fields to post, after validation
+    fn form(&self) -> Result<Vec<(&'static str, String)>>;
+}
+
+/// Bookmarks a sound, optionally with a name and in a category
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{BookmarkRequest, WriteRequest};
+///
+/// let request = BookmarkRequest::new(1234).name("Nice pad").category("Pads");
+/// assert_eq!(request.path(), "sounds/1234/bookmark");
+/// assert_eq!(
+///     request.form()?,
+///     [("name", "Nice pad".to_string()), ("category", "Pads".to_string())]
+/// );
+///
+/// assert!(BookmarkRequest::new(1234).category("").validate().is_err());
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookmarkRequest {
+    sound_id: i32,
+    name: Option<String>,
+    category: Option<String>,
+}
+
+impl BookmarkRequest {
+    /// Create a bookmark request for a sound
+    pub fn new(sound_id: i32) -> Self {
+        Self {
+            sound_id,
+            name: None,
+            category: None,
+        }
+    }
+
+    /// Set the name of the bookmark (defaults to the sound name)
+    pub fn name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the category of the bookmark, created if it does not exist
+    pub fn category<S: Into<String>>(mut self, category: S) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+}
+
+impl WriteRequest for BookmarkRequest {
+    fn path(&self) -> String {
+        format!("sounds/{}/bookmark", self.sound_id)
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (field, value) in [("name", &self.name), ("category", &self.category)] {
+            if let Some(value) = value {
+                check_text(field, value, MAX_BOOKMARK_NAME_LENGTH)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn form(&self) -> Result<Vec<(&'static str, String)>> {
+        self.validate()?;
+        let mut form = Vec::new();
+        if let Some(name) = &self.name {
+            form.push(("name", name.clone()));
+        }
+        if let Some(category) = &self.category {
+            form.push(("category", category.clone()));
+        }
+        Ok(form)
+    }
+}
+
+/// Posts a comment on a sound
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{CommentRequest, WriteRequest};
+///
+/// assert!(CommentRequest::new(1234, "Great recording!").validate().is_ok());
+/// assert!(CommentRequest::new(1234, "   ").validate().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentRequest {
+    sound_id: i32,
+    comment: String,
+}
+
+impl CommentRequest {
+    /// Create a comment request for a sound
+    pub fn new<S: Into<String>>(sound_id: i32, comment: S) -> Self {
+        Self {
+            sound_id,
+            comment: comment.into(),
+        }
+    }
+}
+
+impl WriteRequest for CommentRequest {
+    fn path(&self) -> String {
+        format!("sounds/{}/comment", self.sound_id)
+    }
+
+    fn validate(&self) -> Result<()> {
+        check_text("comment", &self.comment, MAX_COMMENT_LENGTH)
+    }
+
+    fn form(&self) -> Result<Vec<(&'static str, String)>> {
+        self.validate()?;
+        Ok(vec![("comment", self.comment.clone())])
+    }
+}
+
+/// Rates a sound, from 0 to [`MAX_RATING`]
+///
+/// # Examples
+///
+/// ```
+/// use freesound_rs::{RateRequest, WriteRequest};
+///
+/// assert_eq!(RateRequest::new(1234, 4).form()?, [("rating", "4".to_string())]);
+/// assert!(RateRequest::new(1234, 6).validate().is_err());
+/// # Ok::<(), freesound_rs::FreesoundError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateRequest {
+    sound_id: i32,
+    rating: u8,
+}
+
+impl RateRequest {
+    /// Create a rating request for a sound
+    pub fn new(sound_id: i32, rating: u8) -> Self {
+        Self { sound_id, rating }
+    }
+}
+
+impl WriteRequest for RateRequest {
+    fn path(&self) -> String {
+        format!("sounds/{}/rate", self.sound_id)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.rating > MAX_RATING {
+            return Err(FreesoundError::InvalidRequest(format!(
+                "rating must be between 0 and {MAX_RATING}, got {}",
+                self.rating
+            )));
+        }
+        Ok(())
+    }
+
+    fn form(&self) -> Result<Vec<(&'static str, String)>> {
+        self.validate()?;
+        Ok(vec![("rating", self.rating.to_string())])
+    }
+}
+
+fn check_text(field: &str, value: &str, max: usize) -> Result<()> {
+    if value.trim().is_empty() {
+        return Err(FreesoundError::InvalidRequest(format!(
+            "{field} must not be empty"
+        )));
+    }
+    if value.chars().count() > max {
+        return Err(FreesoundError::InvalidRequest(format!(
+            "{field} must be at most {max} characters long"
+        )));
+    }
+    Ok(())
+}
+
+/// The outcome of [`FreesoundClient::bookmark_in_category`]
+#[derive(Debug, Clone, Default)]
+pub struct CategoryBookmark {
+    /// The confirmation message of the API
+    pub message: String,
+    /// The category holding the bookmark, if it could be found afterwards
+    pub category: Option<BookmarkCategory>,
+    /// Whether the category was created by this bookmark
+    pub created: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct Detail {
+    #[serde(default)]
+    detail: String,
+}
+
+impl FreesoundClient {
+    /// Validate and send a write operation
+    ///
+    /// Writes are sent once and never tried again automatically. A write
+    /// Freesound already holds, such as a sound rated before, fails with
+    /// [`AlreadyExists`](FreesoundError::AlreadyExists), so retrying a write
+    /// whose outcome is unknown is safe for ratings and bookmarks; comments
+    /// cannot be told apart and would be posted twice.
+    ///
+    /// # Returns
+    ///
+    /// The confirmation message of the API
+    pub async fn submit(&self, request: &impl WriteRequest) -> Result<String> {
+        self.require_oauth(&format!("POST {}", request.path()))?;
+        let form = request.form()?;
+        let response: Detail = self
+            .send_json(
+                self.request(reqwest::Method::POST, &request.path())
+                    .form(&form),
+            )
+            .await
+            .map_err(FreesoundError::for_write)?;
+        Ok(response.detail)
+    }
+
+    /// Bookmark a sound
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{BookmarkRequest, FreesoundClient, FreesoundError};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let access_token = env::var("FREESOUND_ACCESS_TOKEN")?;
+    ///     let client = FreesoundClient::new(api_key, None).with_access_token(access_token);
+    /// match client
+    ///     .bookmark_sound(&BookmarkRequest::new(1234).category("Pads"))
+    ///     .await
+    /// {
+    ///     Ok(message) => println!("{message}"),
+    ///     Err(e) if matches!(e.root(), FreesoundError::AlreadyExists(_)) => {}
+    ///     Err(e) => return Err(e.into()),
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn bookmark_sound(&self, request: &BookmarkRequest) -> Result<String> {
+        self.submit(request).await
+    }
+
+    /// Bookmark a sound in a category, telling whether the category was created
+    ///
+    /// Freesound creates a category the first time a sound is bookmarked in it,
+    /// and offers no other way to create one, nor to rename or delete one (this
+    /// is done on the website). The categories are listed before and after the
+    /// bookmark to find out; as this is not atomic, a category created at the
+    /// same time by another client is reported as created by this one.
+    ///
+    /// Fails with [`InvalidRequest`](FreesoundError::InvalidRequest) if the
+    /// request has no category.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::env;
+    /// use freesound_rs::{BookmarkRequest, FreesoundClient};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     dotenvy::dotenv().ok();
+    ///     let api_key = env::var("FREESOUND_API_KEY").expect("FREESOUND_API_KEY must be set");
+    ///     let access_token = env::var("FREESOUND_ACCESS_TOKEN")?;
+    ///     let client = FreesoundClient::new(api_key, None).with_access_token(access_token);
+    /// let bookmark = client
+    ///     .bookmark_in_category(&BookmarkRequest::new(1234).category("Field recordings"))
+    ///     .await?;
+    /// if bookmark.created {
+    ///     println!("New category: {:?}", bookmark.category.map(|c| c.name));
+    /// }
+    ///      Ok(())
+    ///  }
+    /// ```
+    pub async fn bookmark_in_category(
+        &self,
+        request: &BookmarkRequest,
+    ) -> Result<CategoryBookmark> {
+        let Some(name) = &request.category else {
+            return Err(FreesoundError::InvalidRequest(
+                "a category is needed to bookmark in a category".to_string(),
+            ));
+        };
+        request.validate()?;
+        let existed = self.find_bookmark_category(name).await?.is_some();
+        let message = self.submit(request).await?;
+        let category = self.find_bookmark_category(name).await?;
+        Ok(CategoryBookmark {
+            message,
+            created: !existed && category.is_some(),
+            category,
+        })
+    }
+
+    /// Find a bookmark category of the authenticated user by its exact name
+    ///
+    /// Walks through every page of [`get_bookmark_categories`](Self::get_bookmark_categories).
+    pub async fn find_bookmark_category(&self, name: &str) -> Result<Option<BookmarkCategory>> {
+        let first = self.get_bookmark_categories().await?;
+        let categories = self.items(first);
+        let mut categories = std::pin::pin!(categories);
+        while let Some(category) = categories.try_next().await? {
+            if category.name == name {
+                return Ok(Some(category));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Post a comment on a sound
+    pub async fn comment_sound(&self, request: &CommentRequest) -> Result<String> {
+        self.submit(request).await
+    }
+
+    /// Rate a sound
+    pub async fn rate_sound(&self, request: &RateRequest) -> Result<String> {
+        self.submit(request).await
+    }
+}